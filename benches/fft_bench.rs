@@ -0,0 +1,51 @@
+use freqshow::freq::{fft_pair, synth};
+use std::time::Instant;
+
+/// Time forward and inverse transforms of the built-in test patterns at a few sizes,
+/// including awkward non-power-of-two ones, and two real images transformed separately
+/// versus packed into one complex FFT. Run with `cargo bench --bench fft_bench`.
+fn main() {
+    for size in [256u32, 512, 1000, 1024] {
+        let patterns = [
+            ("zone_plate", synth::zone_plate(size, size)),
+            ("grating", synth::grating(size, size, 17.0, 5.0)),
+            ("checkerboard", synth::checkerboard(size, size, 8)),
+        ];
+        for (name, pattern) in patterns {
+            let mut fi = pattern.clone();
+            let start = Instant::now();
+            fi.fft_forward();
+            let forward = start.elapsed();
+            let start = Instant::now();
+            fi.fft_inverse();
+            let inverse = start.elapsed();
+
+            let max_err = fi.data.iter().zip(&pattern.data).map(|(a, b)| (a - b).norm()).fold(0.0, f64::max);
+            println!(
+                "{:>4}x{:<4} {:<12} forward {:>10.3?}  inverse {:>10.3?}  round-trip error {:.1e}",
+                size, size, name, forward, inverse, max_err
+            );
+        }
+    }
+
+    for size in [512u32, 1024] {
+        let a = synth::zone_plate(size, size);
+        let b = synth::checkerboard(size, size, 8);
+        let start = Instant::now();
+        let (mut fa, mut fb) = (a.clone(), b.clone());
+        fa.fft_forward();
+        fb.fft_forward();
+        let separate = start.elapsed();
+        let start = Instant::now();
+        fft_pair(&a, &b).unwrap();
+        let packed = start.elapsed();
+        println!(
+            "{:>4}x{:<4} two real FFTs {:>10.3?}  fft_pair {:>10.3?}  speedup {:.2}x",
+            size,
+            size,
+            separate,
+            packed,
+            separate.as_secs_f64() / packed.as_secs_f64()
+        );
+    }
+}
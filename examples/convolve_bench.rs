@@ -0,0 +1,51 @@
+use freqshow::freq::{synth, Kernel2D};
+use freqshow::FreqImage;
+use rustfft::num_complex::Complex;
+use std::time::Instant;
+
+/// Time FFT convolution against direct spatial convolution for a 31x31 box kernel
+/// on a 1024x1024 image. Run with `--release`.
+fn main() {
+    let size = 1024u32;
+    let fi = synth::spectral_noise(size, size, 0.0, 1);
+    let kernel = Kernel2D::box_blur(31);
+
+    let start = Instant::now();
+    let fast = fi.convolve(&kernel);
+    let fft_time = start.elapsed();
+
+    let start = Instant::now();
+    let slow = convolve_direct(&fi, &kernel);
+    let direct_time = start.elapsed();
+
+    let max_err = fast
+        .data
+        .iter()
+        .zip(&slow.data)
+        .map(|(a, b)| (a - b).norm())
+        .fold(0.0, f64::max);
+    println!("fft:    {:?}", fft_time);
+    println!("direct: {:?}", direct_time);
+    println!("speedup {:.1}x, max abs difference {:.3e}", direct_time.as_secs_f64() / fft_time.as_secs_f64(), max_err);
+}
+
+/// Zero-boundary convolution by direct summation.
+fn convolve_direct(fi: &FreqImage, kernel: &Kernel2D) -> FreqImage {
+    let (cx, cy) = kernel.center();
+    let mut out = fi.clone();
+    for (x, y, c) in out.enumerate_pixels_mut() {
+        let mut acc = Complex::new(0.0, 0.0);
+        for (i, &w) in kernel.data().iter().enumerate() {
+            let sx = x as i64 - (i as i64 % kernel.width() as i64 - cx as i64);
+            let sy = y as i64 - (i as i64 / kernel.width() as i64 - cy as i64);
+            if sx < 0 || sy < 0 {
+                continue;
+            }
+            if let Some(v) = fi.get(sx as u32, sy as u32) {
+                acc += v * w;
+            }
+        }
+        *c = acc;
+    }
+    out
+}
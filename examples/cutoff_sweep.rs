@@ -0,0 +1,29 @@
+use freqshow::freq::viz::contact_sheet;
+use freqshow::FreqImage;
+
+/// Render low-pass results over a range of cutoffs into one labelled contact sheet.
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let path = std::env::args().nth(1).unwrap_or_else(|| "img/mandrill.jpg".to_string());
+    let mut spectrum = FreqImage::open(&path)?;
+    spectrum.fft_forward();
+    spectrum.fftshift();
+
+    let cutoffs = [0.02, 0.04, 0.06, 0.08, 0.10, 0.15, 0.20, 0.30];
+    let mut tiles = Vec::new();
+    let mut labels = Vec::new();
+    for cutoff in cutoffs {
+        let mut fi = spectrum.clone();
+        fi.apply_filter(&fi.low_pass_mask(cutoff, 0.01));
+        fi.ifftshift();
+        fi.fft_inverse();
+        tiles.push(fi.to_image());
+        labels.push(format!("cutoff {:.2}", cutoff));
+    }
+    let sheet = contact_sheet(&tiles, 4, 4, Some(&labels))?;
+
+    std::fs::create_dir_all("output")?;
+    let outfile = "output/cutoff_sweep.png";
+    println!("writing to: {}", outfile);
+    sheet.save(outfile)?;
+    Ok(())
+}
@@ -0,0 +1,47 @@
+use freqshow::FreqImage;
+use rustfft::num_complex::Complex;
+use std::f64::consts::PI;
+
+/// Remove the halftone screen from a scan, or from a synthetic halftone when no file is given.
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args: Vec<_> = std::env::args().collect();
+    let mut fi = match args.get(1) {
+        Some(path) => FreqImage::open(path)?,
+        None => synthetic_halftone(512, 6.0),
+    };
+    std::fs::create_dir_all("output")?;
+    fi.to_image().save("output/descreen_input.png")?;
+
+    match fi.detect_screen() {
+        Some(screen) => println!(
+            "screen: {:.4} cycles/pixel (period {:.2} px) at {:.1} degrees",
+            screen.frequency_cpp,
+            1.0 / screen.frequency_cpp,
+            screen.angle
+        ),
+        None => println!("no halftone screen detected"),
+    }
+    fi.descreen(1.0);
+
+    let outfile = "output/descreen.png";
+    println!("writing to: {}", outfile);
+    fi.to_image().save(outfile)?;
+    Ok(())
+}
+
+/// Threshold a smooth gradient against a 45° clustered-dot screen of the given period.
+fn synthetic_halftone(size: u32, period: f64) -> FreqImage {
+    FreqImage {
+        width: size,
+        height: size,
+        data: (0..size * size)
+            .map(|i| {
+                let (x, y) = ((i % size) as f64, (i / size) as f64);
+                let gray = 0.2 + 0.6 * x / size as f64;
+                let (u, v) = ((x + y) / 2f64.sqrt(), (x - y) / 2f64.sqrt());
+                let screen = 0.5 + 0.25 * ((2.0 * PI * u / period).cos() + (2.0 * PI * v / period).cos());
+                Complex::new(if gray > screen { 1.0 } else { 0.0 }, 0.0)
+            })
+            .collect(),
+    }
+}
@@ -0,0 +1,35 @@
+use freqshow::freq::{ClampPolicy, Normalize};
+use freqshow::FreqImage;
+use std::f64::consts::PI;
+
+/// Detect edges by applying the Laplacian in the frequency domain, then render
+/// the signed result with mid-gray as zero so both sides of an edge are visible.
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args: Vec<_> = std::env::args().collect();
+    if args.len() < 2 {
+        eprintln!("usage: edge_detect <image_file_name>");
+        std::process::exit(1);
+    }
+    let mut fi = FreqImage::open(&args[1])?;
+    fi.fft_forward();
+
+    // Laplacian transfer function -4π²(fx² + fy²), with frequencies in cycles per
+    // pixel on the unshifted layout (bins past the midpoint are negative).
+    let (width, height) = (fi.width as f64, fi.height as f64);
+    let signed = |k: u32, n: f64| if k as f64 > n / 2.0 { k as f64 - n } else { k as f64 };
+    for (x, y, c) in fi.enumerate_pixels_mut() {
+        let fx = signed(x, width) / width;
+        let fy = signed(y, height) / height;
+        *c *= -4.0 * PI * PI * (fx * fx + fy * fy);
+    }
+    fi.fft_inverse();
+    // The Laplacian is signed, so keep the range and let `Centered` map it to gray.
+    let report = fi.finalize_real(ClampPolicy::None);
+    println!("edge response in [{:.3}, {:.3}], imaginary residue {:.1e}", report.min, report.max, report.max_imaginary);
+
+    std::fs::create_dir_all("output")?;
+    let outfile = "output/edges.png";
+    println!("writing to: {}", outfile);
+    fi.to_image_with(Normalize::Centered).save(outfile)?;
+    Ok(())
+}
@@ -0,0 +1,29 @@
+use freqshow::FreqImage;
+use rustfft::num_complex::Complex;
+use std::f64::consts::PI;
+
+/// Compute the horizontal derivative of an image in the frequency domain and
+/// render it with a diverging colormap: falling edges blue, rising edges red.
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args: Vec<_> = std::env::args().collect();
+    if args.len() < 2 {
+        eprintln!("usage: gradient <image_file_name>");
+        std::process::exit(1);
+    }
+    let mut fi = FreqImage::open(&args[1])?;
+    fi.fft_forward();
+
+    // d/dx has transfer function i·2π·fx, fx in cycles per pixel on the unshifted layout.
+    let width = fi.width as f64;
+    for (x, _, c) in fi.enumerate_pixels_mut() {
+        let k = if x as f64 > width / 2.0 { x as f64 - width } else { x as f64 };
+        *c *= Complex::new(0.0, 2.0 * PI * k / width);
+    }
+    fi.fft_inverse();
+
+    std::fs::create_dir_all("output")?;
+    let outfile = "output/gradient_x.png";
+    println!("writing to: {}", outfile);
+    fi.to_image_diverging(0.0).save(outfile)?;
+    Ok(())
+}
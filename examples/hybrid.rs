@@ -0,0 +1,28 @@
+use freqshow::freq::hybrid;
+use freqshow::freq::io::{save_png_with_meta, FilterRecord};
+use freqshow::FreqImage;
+
+/// Blend the low frequencies of one image with the high frequencies of another.
+/// Both images must have the same dimensions.
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args: Vec<_> = std::env::args().collect();
+    if args.len() < 3 {
+        eprintln!("usage: hybrid <far_image> <near_image> [cutoff]");
+        std::process::exit(1);
+    }
+    let far = FreqImage::open(&args[1])?;
+    let near = FreqImage::open(&args[2])?;
+    let cutoff = match args.get(3) {
+        Some(c) => c.parse()?,
+        None => 0.03,
+    };
+
+    let blended = hybrid(&far, &near, cutoff, 0.01)?;
+    let record = FilterRecord::new(&["hybrid"], &[cutoff], 0.01);
+
+    std::fs::create_dir_all("output")?;
+    let outfile = "output/hybrid.png";
+    println!("writing to: {}", outfile);
+    save_png_with_meta(&blended.to_image(), outfile, &record.to_meta())?;
+    Ok(())
+}
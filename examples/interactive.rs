@@ -0,0 +1,149 @@
+use freqshow::freq::io::{save_png_with_meta, FilterRecord};
+use freqshow::freq::viewer::image_view;
+use freqshow::freq::FftContext;
+use freqshow::FreqImage;
+use image::GrayImage;
+use show_image::event::{VirtualKeyCode, WindowEvent};
+
+/// How far one key press moves the cutoff or the smoothing.
+const STEP: f64 = 0.01;
+
+/// Filter parameters being tuned, and how a key press changes them.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct TuningState {
+    cutoff: f64,
+    smoothing: f64,
+    high_pass: bool,
+}
+
+/// What the viewer should do after a key press.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Action {
+    Redraw,
+    Save,
+    Quit,
+    Ignore,
+}
+
+impl TuningState {
+    /// Up/down move the cutoff, left/right the smoothing, `h` toggles high-pass, `s` saves
+    /// and `q` quits. Neither parameter goes below zero.
+    fn handle_key(&mut self, key: VirtualKeyCode) -> Action {
+        match key {
+            VirtualKeyCode::Up => self.cutoff = step(self.cutoff, STEP),
+            VirtualKeyCode::Down => self.cutoff = step(self.cutoff, -STEP),
+            VirtualKeyCode::Right => self.smoothing = step(self.smoothing, STEP),
+            VirtualKeyCode::Left => self.smoothing = step(self.smoothing, -STEP),
+            VirtualKeyCode::H => self.high_pass = !self.high_pass,
+            VirtualKeyCode::S => return Action::Save,
+            VirtualKeyCode::Q => return Action::Quit,
+            _ => return Action::Ignore,
+        }
+        Action::Redraw
+    }
+
+    fn operation(&self) -> &'static str {
+        if self.high_pass {
+            "high_pass"
+        } else {
+            "low_pass"
+        }
+    }
+
+    /// Filter a copy of the centered `spectrum` and transform it back with `ctx`.
+    fn render(&self, spectrum: &FreqImage, ctx: &mut FftContext) -> GrayImage {
+        let mut fi = spectrum.clone();
+        let mask = if self.high_pass {
+            fi.high_pass_mask(self.cutoff, self.smoothing)
+        } else {
+            fi.low_pass_mask(self.cutoff, self.smoothing)
+        };
+        fi.apply_filter(&mask);
+        fi.ifftshift();
+        ctx.inverse(&mut fi);
+        fi.to_image()
+    }
+}
+
+/// `value + delta`, clamped at zero and rounded to whole steps so repeated presses
+/// don't accumulate rounding error.
+fn step(value: f64, delta: f64) -> f64 {
+    ((value + delta).max(0.0) / STEP).round() * STEP
+}
+
+/// Tune a low- or high-pass filter live: up/down change the cutoff, left/right the
+/// smoothing, `h` switches between low- and high-pass, `s` saves, `q` quits.
+#[show_image::main]
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let path = std::env::args().nth(1).unwrap_or_else(|| "img/mandrill.jpg".to_string());
+    let mut ctx = FftContext::new();
+    let mut spectrum = FreqImage::open(&path)?;
+    ctx.forward(&mut spectrum);
+    spectrum.fftshift();
+
+    let mut state = TuningState { cutoff: 0.10, smoothing: 0.02, high_pass: false };
+    let mut result = state.render(&spectrum, &mut ctx);
+    let window = show_image::create_window("interactive", Default::default())?;
+    window.set_image("result", image_view(&result))?;
+    for event in window.event_channel()? {
+        let WindowEvent::KeyboardInput(event) = event else { continue };
+        let Some(key) = event.input.key_code else { continue };
+        if !event.input.state.is_pressed() {
+            continue;
+        }
+        match state.handle_key(key) {
+            Action::Redraw => {
+                result = state.render(&spectrum, &mut ctx);
+                window.set_image("result", image_view(&result))?;
+            }
+            Action::Save => {
+                let record = FilterRecord::new(&[state.operation()], &[state.cutoff], state.smoothing);
+                std::fs::create_dir_all("output")?;
+                let outfile = "output/interactive.png";
+                println!("writing to: {}", outfile);
+                save_png_with_meta(&result, outfile, &record.to_meta())?;
+                println!("{} cutoff {:.2} smoothing {:.2}", state.operation(), state.cutoff, state.smoothing);
+            }
+            Action::Quit => break,
+            Action::Ignore => {}
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn test_tuning_keys() {
+    let mut state = TuningState { cutoff: 0.10, smoothing: 0.02, high_pass: false };
+    assert_eq!(state.handle_key(VirtualKeyCode::Up), Action::Redraw);
+    assert_eq!(state.cutoff, 0.11);
+    state.handle_key(VirtualKeyCode::Down);
+    state.handle_key(VirtualKeyCode::Down);
+    assert_eq!(state.cutoff, 0.09);
+    state.handle_key(VirtualKeyCode::Right);
+    assert_eq!(state.smoothing, 0.03);
+    state.handle_key(VirtualKeyCode::Left);
+    assert_eq!(state.smoothing, 0.02);
+
+    assert_eq!(state.handle_key(VirtualKeyCode::H), Action::Redraw);
+    assert!(state.high_pass && state.operation() == "high_pass");
+    state.handle_key(VirtualKeyCode::H);
+    assert!(!state.high_pass);
+
+    let before = state;
+    assert_eq!(state.handle_key(VirtualKeyCode::S), Action::Save);
+    assert_eq!(state.handle_key(VirtualKeyCode::Q), Action::Quit);
+    assert_eq!(state.handle_key(VirtualKeyCode::Escape), Action::Ignore);
+    assert_eq!(state, before);
+}
+
+#[test]
+fn test_tuning_clamps_at_zero() {
+    let mut state = TuningState { cutoff: 0.02, smoothing: 0.0, high_pass: false };
+    for _ in 0..5 {
+        state.handle_key(VirtualKeyCode::Down);
+        state.handle_key(VirtualKeyCode::Left);
+    }
+    assert_eq!((state.cutoff, state.smoothing), (0.0, 0.0));
+    state.handle_key(VirtualKeyCode::Up);
+    assert_eq!(state.cutoff, 0.01);
+}
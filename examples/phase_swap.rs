@@ -0,0 +1,26 @@
+use freqshow::freq::swap_phase;
+use freqshow::FreqImage;
+
+/// Swap the phase spectra of two images to show that phase carries most of the structure.
+/// Larger inputs are cropped to their common size.
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args: Vec<_> = std::env::args().collect();
+    if args.len() < 3 {
+        eprintln!("usage: phase_swap <image_a> <image_b>");
+        std::process::exit(1);
+    }
+    let a = FreqImage::open(&args[1])?;
+    let b = FreqImage::open(&args[2])?;
+    let (width, height) = (a.width.min(b.width), a.height.min(b.height));
+    let (a, b) = (a.crop(0, 0, width, height), b.crop(0, 0, width, height));
+
+    let (mag_a_phase_b, mag_b_phase_a) = swap_phase(&a, &b)?;
+
+    std::fs::create_dir_all("output")?;
+    for (name, fi) in [("mag_a_phase_b", mag_a_phase_b), ("mag_b_phase_a", mag_b_phase_a)] {
+        let outfile = format!("output/{}.png", name);
+        println!("writing to: {}", outfile);
+        fi.to_image().save(&outfile)?;
+    }
+    Ok(())
+}
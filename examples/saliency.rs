@@ -0,0 +1,13 @@
+use freqshow::FreqImage;
+
+/// Compute the spectral residual saliency map of an image.
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let path = std::env::args().nth(1).unwrap_or_else(|| "img/mandrill.jpg".to_string());
+    let saliency = FreqImage::open(&path)?.saliency_map();
+
+    std::fs::create_dir_all("output")?;
+    let outfile = "output/saliency.png";
+    println!("writing to: {}", outfile);
+    saliency.to_image().save(outfile)?;
+    Ok(())
+}
@@ -0,0 +1,106 @@
+#ifndef FREQSHOW_H
+#define FREQSHOW_H
+
+/* Generated with cbindgen:0.26.0 */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * The call succeeded.
+ */
+#define FREQSHOW_OK 0
+
+/**
+ * A required pointer was null.
+ */
+#define FREQSHOW_ERR_NULL 1
+
+/**
+ * A width, height or buffer length was zero or didn't match the image.
+ */
+#define FREQSHOW_ERR_DIMENSIONS 2
+
+/**
+ * A parameter was out of range, such as a negative cutoff.
+ */
+#define FREQSHOW_ERR_ARGUMENT 3
+
+/**
+ * An unexpected internal error; the image may be left partially modified.
+ */
+#define FREQSHOW_ERR_PANIC 4
+
+/**
+ * A grayscale image held as a row-major buffer of complex values, either in the
+ * spatial domain or (after a forward FFT) in the frequency domain.
+ */
+typedef struct FreqImage FreqImage;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Create an image from `width * height` row-major real samples, usually in [0, 1].
+ * Returns null if `data` is null, either dimension is zero, or the size overflows.
+ *
+ * # Safety
+ * `data` must be null or point to `width * height` readable doubles.
+ */
+FreqImage *freqshow_create(uint32_t width, uint32_t height, const double *data);
+
+/**
+ * Forward 2D FFT in place, see [`FreqImage::fft_forward`].
+ *
+ * # Safety
+ * `image` must be null or a live handle from [`freqshow_create`].
+ */
+int freqshow_fft_forward(FreqImage *image);
+
+/**
+ * Inverse 2D FFT in place, see [`FreqImage::fft_inverse`].
+ *
+ * # Safety
+ * `image` must be null or a live handle from [`freqshow_create`].
+ */
+int freqshow_fft_inverse(FreqImage *image);
+
+/**
+ * Low-pass filter a spatial image in place: transform, apply
+ * [`FreqImage::low_pass_mask`]`(cutoff, smoothing)` to the centered spectrum and
+ * transform back. Both are fractions of the diagonal and must be finite and
+ * non-negative.
+ *
+ * # Safety
+ * `image` must be null or a live handle from [`freqshow_create`].
+ */
+int freqshow_low_pass(FreqImage *image, double cutoff, double smoothing);
+
+/**
+ * Copy the coefficients into `out` as interleaved `(re, im)` pairs in row-major order.
+ * `len` is the capacity of `out` in doubles and must be at least `2 * width * height`.
+ *
+ * # Safety
+ * `image` must be null or a live handle from [`freqshow_create`], and `out` must be
+ * null or point to `len` writable doubles.
+ */
+int freqshow_get_data(const FreqImage *image, double *out, size_t len);
+
+/**
+ * Release an image. Null is ignored.
+ *
+ * # Safety
+ * `image` must be null or a live handle from [`freqshow_create`], which must not be
+ * used afterwards.
+ */
+void freqshow_free(FreqImage *image);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* FREQSHOW_H */
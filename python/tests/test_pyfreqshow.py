@@ -0,0 +1,54 @@
+# Run after building the extension: `maturin develop --release && pytest python/tests`.
+import numpy as np
+import pytest
+
+pyfreqshow = pytest.importorskip("pyfreqshow")
+
+
+def ramp(height=6, width=8):
+    return np.linspace(0.0, 1.0, height * width).reshape(height, width)
+
+
+def test_roundtrip():
+    data = ramp()
+    img = pyfreqshow.FreqImage.from_numpy(data)
+    assert (img.height, img.width) == data.shape
+    img.fft_forward()
+    spectrum = img.to_numpy()
+    assert spectrum.dtype == np.complex128
+    np.testing.assert_allclose(spectrum, np.fft.fft2(data), atol=1e-9)
+    img.fft_inverse()
+    np.testing.assert_allclose(img.to_numpy().real, data, atol=1e-12)
+
+
+def test_strided_input_is_read_in_logical_order():
+    data = ramp(8, 8)
+    view = data[::2, ::-1]
+    img = pyfreqshow.FreqImage.from_numpy(view)
+    np.testing.assert_array_equal(img.to_numpy().real, view)
+
+
+def test_low_pass_matches_fftshifted_mask():
+    img = pyfreqshow.FreqImage.from_numpy(ramp())
+    img.fft_forward()
+    img.fftshift()
+    mask = img.low_pass_mask(0.1, 0.02)
+    assert mask.shape == (6, 8) and mask.dtype == np.float64
+    before = img.to_numpy()
+    img.apply_filter(mask)
+    np.testing.assert_allclose(img.to_numpy(), before * mask)
+
+    view = img.view_fft_norm()
+    assert view.shape == (6, 8) and view.dtype == np.uint8
+
+
+def test_bad_shapes_raise_value_error():
+    with pytest.raises(ValueError):
+        pyfreqshow.FreqImage.from_numpy(np.zeros(5))
+    with pytest.raises(ValueError):
+        pyfreqshow.FreqImage.from_numpy(np.zeros((0, 4)))
+    img = pyfreqshow.FreqImage.from_numpy(ramp())
+    with pytest.raises(ValueError):
+        img.apply_filter(np.ones((8, 6)))
+    with pytest.raises(TypeError):
+        pyfreqshow.FreqImage.from_numpy(np.zeros((2, 2), dtype=np.int32))
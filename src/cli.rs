@@ -0,0 +1,650 @@
+//! Argument parsing for the `freqshow` binary, and the mapping from parsed commands to
+//! library calls. Errors are [`FreqError::InvalidArgument`] with a message meant for
+//! the terminal.
+
+pub mod batch;
+pub mod hybrid;
+pub mod profile;
+pub mod register;
+
+use std::io::{Cursor, IsTerminal, Read, Write};
+use std::path::{Path, PathBuf};
+
+use freqshow::freq::pipeline::{FilterPipeline, MaskSpec};
+use freqshow::freq::trace::StageTimer;
+use freqshow::freq::viz::side_by_side;
+use freqshow::freq::{Channels, Progress};
+use freqshow::{FreqError, FreqImage};
+use image::{DynamicImage, GrayImage, ImageOutputFormat};
+
+pub const USAGE: &str = "\
+usage: freqshow spectrum <in> [-o <out>]
+       freqshow lowpass <in> --cutoff <c> [--smoothing <s>] [--compare | --color] [-o <out>]
+       freqshow highpass <in> --cutoff <c> [--smoothing <s>] [--compare | --color] [-o <out>]
+       freqshow bandpass <in> --low <l> --high <h> [--smoothing <s>] [--compare | --color]
+                         [-o <out>]
+       freqshow batch <in_dir> <out_dir> --op <command> [options] [--recursive]
+                      [--pattern <glob>] [--suffix <s>]
+       freqshow hybrid <low_source> <high_source> --cutoff <c> [--smoothing <s>] [--swap]
+                       [--preview] [--resize-to-smaller] [-o <out>]
+       freqshow profile <in>... [--bins <n>] [-o <out.csv>] [--plot <out.png>]
+       freqshow register <reference> <moving> [--apply <out>] [--json] [--full]
+                         [--crop-to-common]
+
+Cutoffs and smoothing are fractions of the image diagonal, between 0 and 1.
+Smoothing defaults to 0.02. Without -o, results go to output/<name>_<command>.png.
+An input of - reads an encoded image from stdin; -o - or --stdout writes a PNG to
+stdout, which must not be a terminal unless --force is given.
+--compare writes the original and the result side by side; --compare-with-spectrum
+adds the spectrum of the result. --color filters each color channel separately and
+keeps alpha and 16-bit depth (JPEG output drops both); without it, color inputs are
+converted to gray. --timing prints how long each stage took to stderr,
+--timing-json the same as JSON.
+Batch mode keeps relative paths and file names, adding the suffix to each name, and
+skips files it can't process; it exits with status 1 if any were skipped.
+hybrid keeps the low frequencies of <low_source> and the high frequencies of
+<high_source>; --swap exchanges them, --preview also writes each filtered part as
+<out>_low.png and <out>_high.png. Inputs of different sizes need --resize-to-smaller,
+which scales the one with more pixels to cover the other and crops its center.
+profile writes the radially averaged power spectrum (128 rings by default) as CSV:
+a frequency column in cycles per pixel, then one column per input. Without -o it goes
+to output/<name>_profile.csv; -o - writes it to stdout. --plot adds a log-log chart.
+register prints the shift (dx, dy) that moves <moving> onto <reference>, with
+rotation and scale too under --full; --apply writes <moving> shifted into line.
+Images of different sizes need --crop-to-common, which registers their centers.";
+
+/// The path that stands for stdin as an input and stdout as an output.
+const STDIO: &str = "-";
+
+/// Width in pixels of the dividers in `--compare` output.
+const COMPARE_DIVIDER: u32 = 2;
+
+/// Characters in the track of the progress bar.
+const PROGRESS_WIDTH: usize = 30;
+
+/// Roll-off width used when `--smoothing` isn't given.
+const DEFAULT_SMOOTHING: f64 = 0.02;
+
+/// What to do with the input image.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Operation {
+    /// Save the centered log-magnitude spectrum.
+    Spectrum,
+    /// Filter with one radial mask and save the result.
+    Filter(MaskSpec),
+}
+
+/// What goes in the output image of a filtering command.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Layout {
+    /// Just the result.
+    Single,
+    /// The original and the result, side by side.
+    Compare,
+    /// The original, the result and the spectrum of the result.
+    CompareWithSpectrum,
+}
+
+/// Whether and how to report stage timings.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Timing {
+    Off,
+    Table,
+    Json,
+}
+
+/// A fully parsed invocation.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Command {
+    pub operation: Operation,
+    pub input: PathBuf,
+    pub output: PathBuf,
+    pub layout: Layout,
+    pub timing: Timing,
+    /// Filter each color channel instead of converting to gray first.
+    pub color: bool,
+    /// Write binary output even when stdout is a terminal.
+    pub force: bool,
+}
+
+/// Parse the arguments after the program name. Options may come in any order after
+/// the subcommand.
+pub fn parse_args(args: &[String]) -> Result<Command, FreqError> {
+    let (name, rest) = args.split_first().ok_or_else(|| invalid("missing command"))?;
+    let mut opts = Options::parse(rest)?;
+    let operation = opts.operation(name)?;
+    opts.reject_batch_options(name)?;
+    let input = match opts.paths[..] {
+        [] => return Err(invalid("missing input image")),
+        [input] => PathBuf::from(input),
+        [_, extra, ..] => return Err(invalid(format!("unexpected argument {:?}", extra))),
+    };
+    let output = match (opts.output, opts.stdout) {
+        (Some(_), true) => return Err(invalid("give either -o or --stdout, not both")),
+        (Some(path), false) => PathBuf::from(path),
+        (None, true) => PathBuf::from(STDIO),
+        (None, false) => default_output(&input, name),
+    };
+    let layout = match (opts.compare, opts.compare_spectrum) {
+        (_, true) => Layout::CompareWithSpectrum,
+        (true, false) => Layout::Compare,
+        (false, false) => Layout::Single,
+    };
+    if layout != Layout::Single && operation == Operation::Spectrum {
+        return Err(invalid("spectrum does not take --compare"));
+    }
+    if opts.color && operation == Operation::Spectrum {
+        return Err(invalid("spectrum does not take --color"));
+    }
+    if opts.color && layout != Layout::Single {
+        return Err(invalid("--color cannot be combined with --compare"));
+    }
+    let timing = match (opts.timing, opts.timing_json) {
+        (_, true) => Timing::Json,
+        (true, false) => Timing::Table,
+        (false, false) => Timing::Off,
+    };
+    Ok(Command { operation, input, output, layout, timing, color: opts.color, force: opts.force })
+}
+
+/// The arguments after a subcommand, sorted by kind but not yet checked against what
+/// the subcommand accepts.
+#[derive(Default)]
+struct Options<'a> {
+    paths: Vec<&'a str>,
+    numbers: Vec<(&'a str, f64)>,
+    output: Option<&'a str>,
+    op: Option<&'a str>,
+    pattern: Option<&'a str>,
+    suffix: Option<&'a str>,
+    recursive: bool,
+    stdout: bool,
+    force: bool,
+    compare: bool,
+    compare_spectrum: bool,
+    color: bool,
+    timing: bool,
+    timing_json: bool,
+}
+
+impl<'a> Options<'a> {
+    fn parse(args: &'a [String]) -> Result<Self, FreqError> {
+        let mut opts = Options::default();
+        let mut rest = args.iter();
+        while let Some(arg) = rest.next() {
+            match arg.as_str() {
+                "-o" | "--output" => opts.output = Some(next_value(arg, &mut rest)?),
+                "--cutoff" | "--smoothing" | "--low" | "--high" => {
+                    opts.numbers.push((arg, parse_number(arg, next_value(arg, &mut rest)?)?));
+                }
+                "--op" => opts.op = Some(next_value(arg, &mut rest)?),
+                "--pattern" => opts.pattern = Some(next_value(arg, &mut rest)?),
+                "--suffix" => opts.suffix = Some(next_value(arg, &mut rest)?),
+                "-r" | "--recursive" => opts.recursive = true,
+                "--stdout" => opts.stdout = true,
+                "--force" => opts.force = true,
+                "--compare" => opts.compare = true,
+                "--compare-with-spectrum" => opts.compare_spectrum = true,
+                "--color" => opts.color = true,
+                "--timing" => opts.timing = true,
+                "--timing-json" => opts.timing_json = true,
+                flag if flag.starts_with('-') && flag.len() > 1 => {
+                    return Err(invalid(format!("unknown option {}", flag)));
+                }
+                path => opts.paths.push(path),
+            }
+        }
+        Ok(opts)
+    }
+
+    /// Build and check the operation `name`, consuming the numeric options it takes and
+    /// rejecting any others.
+    fn operation(&mut self, name: &str) -> Result<Operation, FreqError> {
+        let numbers = &mut self.numbers;
+        let mut take = |flag: &str| {
+            let i = numbers.iter().rposition(|&(f, _)| f == flag)?;
+            let value = numbers[i].1;
+            numbers.retain(|&(f, _)| f != flag);
+            Some(value)
+        };
+        let required = |flag: &str, value: Option<f64>| value.ok_or_else(|| invalid(format!("{} requires {}", name, flag)));
+        let operation = match name {
+            "spectrum" => Operation::Spectrum,
+            "lowpass" => Operation::Filter(MaskSpec::LowPass {
+                cutoff: required("--cutoff", take("--cutoff"))?,
+                smoothing: take("--smoothing").unwrap_or(DEFAULT_SMOOTHING),
+            }),
+            "highpass" => Operation::Filter(MaskSpec::HighPass {
+                cutoff: required("--cutoff", take("--cutoff"))?,
+                smoothing: take("--smoothing").unwrap_or(DEFAULT_SMOOTHING),
+            }),
+            "bandpass" => Operation::Filter(MaskSpec::BandPass {
+                low: required("--low", take("--low"))?,
+                high: required("--high", take("--high"))?,
+                smoothing: take("--smoothing").unwrap_or(DEFAULT_SMOOTHING),
+            }),
+            other => return Err(invalid(format!("unknown command {:?}", other))),
+        };
+        if let Some(&(flag, _)) = self.numbers.first() {
+            return Err(invalid(format!("{} does not take {}", name, flag)));
+        }
+        check_operation(&operation)?;
+        Ok(operation)
+    }
+
+    fn reject_batch_options(&self, name: &str) -> Result<(), FreqError> {
+        let given = [
+            ("--op", self.op.is_some()),
+            ("--pattern", self.pattern.is_some()),
+            ("--suffix", self.suffix.is_some()),
+            ("--recursive", self.recursive),
+        ];
+        match given.iter().find(|(_, set)| *set) {
+            Some((flag, _)) => Err(invalid(format!("{} does not take {}; did you mean batch?", name, flag))),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Operation {
+    /// The image to save for the spatial image `fi`.
+    pub fn apply(&self, fi: &FreqImage) -> Result<GrayImage, FreqError> {
+        self.apply_timed(fi, &mut StageTimer::new(), &mut |_| {})
+    }
+
+    /// [`Operation::apply`], timing each stage into `timer` and reporting the progress
+    /// of the FFTs to `progress`.
+    pub fn apply_timed(
+        &self,
+        fi: &FreqImage,
+        timer: &mut StageTimer,
+        progress: &mut dyn FnMut(Progress),
+    ) -> Result<GrayImage, FreqError> {
+        match *self {
+            Operation::Spectrum => {
+                let mut spectrum = fi.clone();
+                timer.time("fft_forward", || spectrum.fft_forward_with_progress(progress));
+                timer.time("shift", || spectrum.fftshift());
+                Ok(timer.time("render", || spectrum.view_fft_norm()))
+            }
+            Operation::Filter(spec) => {
+                let filtered = FilterPipeline::new().mask(spec).run_timed_with_progress(fi, timer, progress)?;
+                Ok(timer.time("render", || filtered.to_image()))
+            }
+        }
+    }
+}
+
+impl Command {
+    /// The output image for the spatial image `fi`, laid out as requested, timing each
+    /// stage into `timer` and reporting FFT progress to `progress`.
+    pub fn render(
+        &self,
+        fi: &FreqImage,
+        timer: &mut StageTimer,
+        progress: &mut dyn FnMut(Progress),
+    ) -> Result<GrayImage, FreqError> {
+        let Operation::Filter(spec) = self.operation else { return self.operation.apply_timed(fi, timer, progress) };
+        if self.layout == Layout::Single {
+            return self.operation.apply_timed(fi, timer, progress);
+        }
+        let filtered = FilterPipeline::new().mask(spec).run_timed_with_progress(fi, timer, progress)?;
+        let (original, result) = timer.time("render", || (fi.to_image(), filtered.to_image()));
+        if self.layout == Layout::Compare {
+            return timer.time("render", || side_by_side(&[&original, &result], COMPARE_DIVIDER));
+        }
+        let spectrum = Operation::Spectrum.apply_timed(&filtered, timer, progress)?;
+        timer.time("render", || side_by_side(&[&original, &result, &spectrum], COMPARE_DIVIDER))
+    }
+
+    /// The filtered color image for `img`: each color channel runs through the filter on
+    /// its own, alpha is passed through, and the result has the input's channel layout
+    /// and bit depth. Grayscale inputs are filtered as one channel, and FFT progress is
+    /// reported for each channel in turn.
+    pub fn render_color(
+        &self,
+        img: &DynamicImage,
+        timer: &mut StageTimer,
+        progress: &mut dyn FnMut(Progress),
+    ) -> Result<DynamicImage, FreqError> {
+        let Operation::Filter(spec) = self.operation else { return Err(invalid("spectrum does not take --color")) };
+        let mut channels = Channels::from_image(img);
+        let mut pipeline = FilterPipeline::new().mask(spec);
+        for plane in &mut channels.color {
+            *plane = pipeline.run_timed_with_progress(plane, timer, progress)?;
+        }
+        timer.time("render", || channels.to_image())
+    }
+
+    /// Read the input, apply the operation and write the output, creating its directory.
+    /// With stdout as the output, nothing but the PNG goes to stdout. Timings, if asked
+    /// for, go to stderr, as does a progress bar when stderr is a terminal.
+    pub fn run(&self) -> Result<(), FreqError> {
+        if self.output == Path::new(STDIO) {
+            check_binary_output(std::io::stdout().is_terminal(), self.force)?;
+        }
+        let (mut timer, mut progress) = (StageTimer::new(), progress_bar());
+        let result = if self.color {
+            let img = timer.time("load", || self.load_color())?;
+            self.render_color(&img, &mut timer, &mut progress)?
+        } else {
+            let fi = timer.time("load", || self.load())?;
+            DynamicImage::ImageLuma8(self.render(&fi, &mut timer, &mut progress)?)
+        };
+        timer.time("save", || self.save(&result))?;
+        match self.timing {
+            Timing::Off => {}
+            Timing::Table => eprint!("{}", timer.table()),
+            Timing::Json => eprintln!("{}", timer.to_json()),
+        }
+        Ok(())
+    }
+
+    fn load(&self) -> Result<FreqImage, FreqError> {
+        if self.input == Path::new(STDIO) {
+            FreqImage::from_encoded_bytes(&read_stdin()?)
+        } else {
+            Ok(FreqImage::open(&self.input)?)
+        }
+    }
+
+    fn load_color(&self) -> Result<DynamicImage, FreqError> {
+        if self.input == Path::new(STDIO) {
+            Ok(image::load_from_memory(&read_stdin()?)?)
+        } else {
+            Ok(image::open(&self.input)?)
+        }
+    }
+
+    fn save(&self, result: &DynamicImage) -> Result<(), FreqError> {
+        if self.output == Path::new(STDIO) {
+            eprintln!("writing to: stdout");
+            let mut bytes = Vec::new();
+            result.write_to(&mut Cursor::new(&mut bytes), ImageOutputFormat::Png)?;
+            let mut stdout = std::io::stdout().lock();
+            stdout.write_all(&bytes)?;
+            stdout.flush()?;
+            return Ok(());
+        }
+        if let Some(dir) = self.output.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        println!("writing to: {}", self.output.display());
+        if is_jpeg(&self.output) {
+            jpeg_compatible(result).save(&self.output)?;
+        } else {
+            result.save(&self.output)?;
+        }
+        Ok(())
+    }
+}
+
+fn read_stdin() -> Result<Vec<u8>, FreqError> {
+    let mut bytes = Vec::new();
+    std::io::stdin().lock().read_to_end(&mut bytes)?;
+    Ok(bytes)
+}
+
+fn is_jpeg(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("jpg") || ext.eq_ignore_ascii_case("jpeg"))
+}
+
+/// JPEG has no alpha and only 8 bits per channel, so drop alpha and reduce the depth.
+fn jpeg_compatible(img: &DynamicImage) -> DynamicImage {
+    if img.color().has_color() {
+        DynamicImage::ImageRgb8(img.to_rgb8())
+    } else {
+        DynamicImage::ImageLuma8(img.to_luma8())
+    }
+}
+
+/// A progress callback that draws a bar on stderr, redrawn in place and finished with a
+/// newline when each stage completes. It draws nothing unless stderr is a terminal.
+pub fn progress_bar() -> impl FnMut(Progress) + Send {
+    let enabled = std::io::stderr().is_terminal();
+    move |p| {
+        if enabled {
+            let mut stderr = std::io::stderr().lock();
+            let end = if p.completed == p.total { "\n" } else { "" };
+            let _ = write!(stderr, "\r{}{}", progress_line(p), end);
+            let _ = stderr.flush();
+        }
+    }
+}
+
+/// One frame of [`progress_bar`]: the stage, a bar [`PROGRESS_WIDTH`] characters wide
+/// and the percentage.
+fn progress_line(p: Progress) -> String {
+    let fraction = if p.total == 0 { 1.0 } else { p.completed as f64 / p.total as f64 };
+    let filled = (fraction * PROGRESS_WIDTH as f64).round() as usize;
+    format!("{:<12} [{}{}] {:>3.0}%", p.stage, "#".repeat(filled), " ".repeat(PROGRESS_WIDTH - filled), fraction * 100.0)
+}
+
+/// `output/<input stem>_<command>.png`, or `output/stdin_<command>.png`.
+fn default_output(input: &Path, command: &str) -> PathBuf {
+    let stem = match input.file_stem() {
+        _ if input == Path::new(STDIO) => "stdin".into(),
+        Some(stem) => stem.to_string_lossy(),
+        None => "image".into(),
+    };
+    Path::new("output").join(format!("{}_{}.png", stem, command))
+}
+
+/// Reject radii outside [0, 1] and empty bands with a message naming the option.
+fn check_operation(operation: &Operation) -> Result<(), FreqError> {
+    let Operation::Filter(spec) = *operation else { return Ok(()) };
+    let (radii, smoothing) = match spec {
+        MaskSpec::LowPass { cutoff, smoothing } | MaskSpec::HighPass { cutoff, smoothing } => {
+            (vec![("--cutoff", cutoff)], smoothing)
+        }
+        MaskSpec::BandPass { low, high, smoothing } => {
+            if low >= high {
+                return Err(invalid(format!("--low ({}) must be less than --high ({})", low, high)));
+            }
+            (vec![("--low", low), ("--high", high)], smoothing)
+        }
+    };
+    for (flag, value) in radii.into_iter().chain([("--smoothing", smoothing)]) {
+        if !(0.0..=1.0).contains(&value) {
+            return Err(invalid(format!("{} must be between 0 and 1, got {}", flag, value)));
+        }
+    }
+    Ok(())
+}
+
+/// Binary data on a terminal is unreadable and can garble it, so ask for `--force`.
+fn check_binary_output(is_terminal: bool, force: bool) -> Result<(), FreqError> {
+    if is_terminal && !force {
+        return Err(invalid("refusing to write image data to a terminal; redirect stdout or pass --force"));
+    }
+    Ok(())
+}
+
+fn next_value<'a>(flag: &str, rest: &mut impl Iterator<Item = &'a String>) -> Result<&'a str, FreqError> {
+    rest.next().map(String::as_str).ok_or_else(|| invalid(format!("{} needs a value", flag)))
+}
+
+fn parse_number(flag: &str, text: &str) -> Result<f64, FreqError> {
+    text.parse().map_err(|_| invalid(format!("{} expects a number, got {:?}", flag, text)))
+}
+
+fn invalid(msg: impl Into<String>) -> FreqError {
+    FreqError::InvalidArgument(msg.into())
+}
+
+#[cfg(test)]
+fn args(line: &str) -> Vec<String> {
+    line.split_whitespace().map(str::to_string).collect()
+}
+
+#[cfg(test)]
+fn error_text(line: &str) -> String {
+    match parse_args(&args(line)) {
+        Err(FreqError::InvalidArgument(msg)) => msg,
+        other => panic!("{:?} parsed as {:?}", line, other),
+    }
+}
+
+#[test]
+fn test_parse_commands() {
+    let cmd = parse_args(&args("lowpass img/mandrill.jpg --cutoff 0.15 --smoothing 0.05 -o out/lp.png")).unwrap();
+    assert_eq!(cmd.operation, Operation::Filter(MaskSpec::LowPass { cutoff: 0.15, smoothing: 0.05 }));
+    assert_eq!(cmd.input, PathBuf::from("img/mandrill.jpg"));
+    assert_eq!(cmd.output, PathBuf::from("out/lp.png"));
+
+    let cmd = parse_args(&args("highpass --cutoff 0.1 scan.tif")).unwrap();
+    assert_eq!(cmd.operation, Operation::Filter(MaskSpec::HighPass { cutoff: 0.1, smoothing: DEFAULT_SMOOTHING }));
+    assert_eq!(cmd.output, Path::new("output").join("scan_highpass.png"));
+
+    let cmd = parse_args(&args("bandpass a.png --high 0.3 --low 0.1 --smoothing 0")).unwrap();
+    assert_eq!(cmd.operation, Operation::Filter(MaskSpec::BandPass { low: 0.1, high: 0.3, smoothing: 0.0 }));
+
+    let cmd = parse_args(&args("spectrum a.png")).unwrap();
+    assert_eq!(cmd.operation, Operation::Spectrum);
+    assert_eq!(cmd.output, Path::new("output").join("a_spectrum.png"));
+}
+
+#[test]
+fn test_parse_missing_args() {
+    assert!(error_text("").contains("missing command"));
+    assert!(error_text("spectrum").contains("missing input"));
+    assert!(error_text("lowpass a.png").contains("requires --cutoff"));
+    assert!(error_text("bandpass a.png --low 0.1").contains("requires --high"));
+    assert!(error_text("lowpass a.png --cutoff").contains("--cutoff needs a value"));
+    assert!(error_text("spectrum a.png -o").contains("-o needs a value"));
+}
+
+#[test]
+fn test_parse_bad_values() {
+    assert!(error_text("lowpass a.png --cutoff abc").contains("--cutoff expects a number, got \"abc\""));
+    assert!(error_text("lowpass a.png --cutoff 1.5").contains("--cutoff must be between 0 and 1"));
+    assert!(error_text("highpass a.png --cutoff -0.1").contains("between 0 and 1"));
+    assert!(error_text("lowpass a.png --cutoff NaN").contains("between 0 and 1"));
+    assert!(error_text("lowpass a.png --cutoff 0.1 --smoothing 2").contains("--smoothing must be"));
+    assert!(error_text("bandpass a.png --low 0.3 --high 0.1").contains("must be less than --high"));
+    assert!(error_text("lowpass a.png --cutoff 0.1 --low 0.2").contains("lowpass does not take --low"));
+    assert!(error_text("spectrum a.png --cutoff 0.1").contains("does not take --cutoff"));
+    assert!(error_text("sharpen a.png").contains("unknown command"));
+    assert!(error_text("spectrum a.png --verbose").contains("unknown option --verbose"));
+    assert!(error_text("spectrum a.png b.png").contains("unexpected argument"));
+}
+
+#[test]
+fn test_parse_stdio() {
+    let cmd = parse_args(&args("lowpass --cutoff 0.1 - --stdout")).unwrap();
+    assert_eq!((cmd.input.as_path(), cmd.output.as_path()), (Path::new("-"), Path::new("-")));
+    assert!(!cmd.force);
+    let cmd = parse_args(&args("spectrum - -o - --force")).unwrap();
+    assert_eq!(cmd.output, Path::new("-"));
+    assert!(cmd.force);
+    let cmd = parse_args(&args("spectrum -")).unwrap();
+    assert_eq!(cmd.output, Path::new("output").join("stdin_spectrum.png"));
+    assert!(error_text("spectrum a.png -o b.png --stdout").contains("either -o or --stdout"));
+
+    assert!(check_binary_output(false, false).is_ok());
+    assert!(check_binary_output(true, true).is_ok());
+    assert!(matches!(check_binary_output(true, false), Err(FreqError::InvalidArgument(msg)) if msg.contains("--force")));
+}
+
+#[test]
+fn test_compare_layout() {
+    let img = GrayImage::from_fn(12, 9, |x, y| image::Luma([(x * 20 + y * 7) as u8]));
+    let fi = FreqImage::from_image(image::DynamicImage::ImageLuma8(img.clone()));
+    let single = parse_args(&args("lowpass a.png --cutoff 0.2")).unwrap();
+    assert_eq!(single.layout, Layout::Single);
+    let filtered = single.render(&fi, &mut StageTimer::new(), &mut |_| {}).unwrap();
+
+    let compare = parse_args(&args("lowpass a.png --cutoff 0.2 --compare")).unwrap();
+    let out = compare.render(&fi, &mut StageTimer::new(), &mut |_| {}).unwrap();
+    assert_eq!(out.dimensions(), (2 * 12 + COMPARE_DIVIDER, 9));
+    for (x, y, p) in img.enumerate_pixels() {
+        assert_eq!(out.get_pixel(x, y), p);
+        assert_eq!(out.get_pixel(12 + COMPARE_DIVIDER + x, y), filtered.get_pixel(x, y));
+    }
+
+    let with_spectrum = parse_args(&args("bandpass a.png --low 0.05 --high 0.3 --compare-with-spectrum")).unwrap();
+    assert_eq!(with_spectrum.layout, Layout::CompareWithSpectrum);
+    assert_eq!(with_spectrum.render(&fi, &mut StageTimer::new(), &mut |_| {}).unwrap().dimensions(), (3 * 12 + 2 * COMPARE_DIVIDER, 9));
+    assert!(error_text("spectrum a.png --compare").contains("spectrum does not take --compare"));
+}
+
+#[test]
+fn test_timed_stages() {
+    let fi = FreqImage::from_image(image::DynamicImage::ImageLuma8(GrayImage::from_fn(12, 9, |x, y| {
+        image::Luma([(x * 20 + y * 7) as u8])
+    })));
+    let cmd = parse_args(&args("lowpass a.png --cutoff 0.2 --timing")).unwrap();
+    assert_eq!(cmd.timing, Timing::Table);
+    let mut timer = StageTimer::new();
+    assert_eq!(cmd.render(&fi, &mut timer, &mut |_| {}).unwrap(), cmd.render(&fi, &mut StageTimer::new(), &mut |_| {}).unwrap());
+    let names: Vec<&str> = timer.stages().iter().map(|(n, _)| n.as_str()).collect();
+    assert_eq!(names, ["fft_forward", "shift", "mask", "filter", "fft_inverse", "render"]);
+
+    let cmd = parse_args(&args("spectrum a.png --timing-json")).unwrap();
+    assert_eq!(cmd.timing, Timing::Json);
+    let mut timer = StageTimer::new();
+    cmd.render(&fi, &mut timer, &mut |_| {}).unwrap();
+    let json: serde_json::Value = serde_json::from_str(&timer.to_json()).unwrap();
+    let names: Vec<&str> = json["stages"].as_array().unwrap().iter().map(|s| s["name"].as_str().unwrap()).collect();
+    assert_eq!(names, ["fft_forward", "shift", "render"]);
+}
+
+#[test]
+fn test_apply_keeps_dimensions() {
+    let fi = FreqImage::from_image(image::DynamicImage::ImageLuma8(GrayImage::from_fn(12, 9, |x, y| {
+        image::Luma([(x * 20 + y * 7) as u8])
+    })));
+    for line in ["spectrum a.png", "lowpass a.png --cutoff 0.2", "bandpass a.png --low 0.05 --high 0.3"] {
+        let cmd = parse_args(&args(line)).unwrap();
+        assert_eq!(cmd.operation.apply(&fi).unwrap().dimensions(), (12, 9));
+    }
+}
+
+#[test]
+fn test_color_render() {
+    use image::{ImageBuffer, Luma, Rgb, Rgba};
+
+    let cmd = parse_args(&args("lowpass a.png --cutoff 0.2 --color")).unwrap();
+    assert!(cmd.color);
+    let rgba = image::RgbaImage::from_fn(12, 9, |x, y| Rgba([(x * 20) as u8, (y * 25) as u8, ((x + y) * 9) as u8, 40 + x as u8]));
+    let out = cmd.render_color(&DynamicImage::ImageRgba8(rgba.clone()), &mut StageTimer::new(), &mut |_| {}).unwrap();
+    let out = out.as_rgba8().expect("RGBA input gives RGBA output");
+    let spec = MaskSpec::LowPass { cutoff: 0.2, smoothing: DEFAULT_SMOOTHING };
+    for c in 0..3 {
+        let plane = GrayImage::from_fn(12, 9, |x, y| Luma([rgba.get_pixel(x, y)[c]]));
+        let expected = FilterPipeline::new().mask(spec).run(&FreqImage::from_image(DynamicImage::ImageLuma8(plane))).unwrap().to_image();
+        assert!(out.enumerate_pixels().all(|(x, y, p)| p[c] == expected.get_pixel(x, y)[0]), "channel {}", c);
+    }
+    assert!(out.enumerate_pixels().all(|(x, y, p)| p[3] == rgba.get_pixel(x, y)[3]));
+
+    let gray = DynamicImage::ImageLuma8(GrayImage::from_fn(12, 9, |x, y| Luma([(x * 20 + y * 7) as u8])));
+    let out = cmd.render_color(&gray, &mut StageTimer::new(), &mut |_| {}).unwrap();
+    let plain = parse_args(&args("lowpass a.png --cutoff 0.2")).unwrap();
+    assert_eq!(out, DynamicImage::ImageLuma8(plain.render(&FreqImage::from_image(gray), &mut StageTimer::new(), &mut |_| {}).unwrap()));
+
+    let deep = DynamicImage::ImageRgb16(ImageBuffer::from_fn(12, 9, |x, y| Rgb([x as u16 * 5000, y as u16 * 7000, 30000])));
+    let out = cmd.render_color(&deep, &mut StageTimer::new(), &mut |_| {}).unwrap();
+    assert!(out.as_rgb16().is_some());
+    assert_eq!(jpeg_compatible(&out).color(), image::ColorType::Rgb8);
+    assert_eq!(jpeg_compatible(&DynamicImage::ImageLumaA8(ImageBuffer::new(2, 2))).color(), image::ColorType::L8);
+    assert!(is_jpeg(Path::new("out/a.JPEG")) && !is_jpeg(Path::new("out/a.png")));
+
+    assert!(error_text("spectrum a.png --color").contains("spectrum does not take --color"));
+    assert!(error_text("lowpass a.png --cutoff 0.2 --color --compare").contains("cannot be combined"));
+}
+
+#[test]
+fn test_progress_line() {
+    let line = |completed, total| progress_line(Progress { stage: "fft_rows", completed, total });
+    assert_eq!(line(0, 200), format!("fft_rows     [{}]   0%", " ".repeat(PROGRESS_WIDTH)));
+    assert_eq!(line(100, 200), format!("fft_rows     [{}{}]  50%", "#".repeat(15), " ".repeat(15)));
+    assert_eq!(line(200, 200), format!("fft_rows     [{}] 100%", "#".repeat(PROGRESS_WIDTH)));
+    assert_eq!(line(0, 0), line(1, 1));
+
+    let fi = FreqImage::from_image(image::DynamicImage::ImageLuma8(GrayImage::new(12, 9)));
+    let mut reports = Vec::new();
+    let cmd = parse_args(&args("highpass a.png --cutoff 0.2")).unwrap();
+    cmd.render(&fi, &mut StageTimer::new(), &mut |p| reports.push(p)).unwrap();
+    let finished: Vec<_> = reports.iter().filter(|p| p.completed == p.total).map(|p| (p.stage, p.total)).collect();
+    assert_eq!(finished, [("fft_rows", 9), ("fft_columns", 12), ("ifft_rows", 9), ("ifft_columns", 12)]);
+}
@@ -0,0 +1,271 @@
+//! `freqshow batch`: run one operation over every matching image in a directory tree.
+
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use freqshow::freq::Progress;
+use freqshow::{FreqError, FreqImage};
+
+use super::{invalid, Operation, Options};
+
+/// Extensions picked up when no `--pattern` is given, compared case-insensitively.
+const IMAGE_EXTENSIONS: [&str; 10] = ["bmp", "gif", "jpeg", "jpg", "pgm", "png", "pnm", "tif", "tiff", "webp"];
+
+/// A parsed `batch` invocation.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Batch {
+    pub operation: Operation,
+    pub input_dir: PathBuf,
+    pub output_dir: PathBuf,
+    pub recursive: bool,
+    /// Glob on file names, with `*` and `?`; `None` matches [`IMAGE_EXTENSIONS`].
+    pub pattern: Option<String>,
+    /// Appended to each output file's stem.
+    pub suffix: String,
+}
+
+/// What a batch run did.
+#[derive(Debug)]
+pub struct Summary {
+    pub processed: usize,
+    /// Input paths, relative to the input directory, and why each was skipped.
+    pub skipped: Vec<(PathBuf, FreqError)>,
+    pub elapsed: Duration,
+}
+
+/// Parse the arguments after `batch`:
+/// `<input_dir> <output_dir> --op <command> [numbers] [--recursive] [--pattern <glob>] [--suffix <s>]`.
+pub fn parse_batch_args(args: &[String]) -> Result<Batch, FreqError> {
+    let mut opts = Options::parse(args)?;
+    let name = opts.op.ok_or_else(|| invalid("batch requires --op"))?;
+    if name == "batch" {
+        return Err(invalid("--op must be spectrum, lowpass, highpass or bandpass"));
+    }
+    let operation = opts.operation(name)?;
+    if opts.output.is_some() {
+        return Err(invalid("batch does not take -o; give the output directory instead"));
+    }
+    if opts.stdout || opts.force {
+        return Err(invalid("batch always writes files; it does not take --stdout or --force"));
+    }
+    if opts.compare || opts.compare_spectrum {
+        return Err(invalid("batch does not take --compare"));
+    }
+    if opts.color {
+        return Err(invalid("batch does not take --color"));
+    }
+    if opts.timing || opts.timing_json {
+        return Err(invalid("batch does not take --timing; it prints its own summary"));
+    }
+    let (input_dir, output_dir) = match opts.paths[..] {
+        [input, output] => (PathBuf::from(input), PathBuf::from(output)),
+        [_, _, extra, ..] => return Err(invalid(format!("unexpected argument {:?}", extra))),
+        _ => return Err(invalid("batch requires an input and an output directory")),
+    };
+    Ok(Batch {
+        operation,
+        input_dir,
+        output_dir,
+        recursive: opts.recursive,
+        pattern: opts.pattern.map(str::to_string),
+        suffix: opts.suffix.unwrap_or_default().to_string(),
+    })
+}
+
+impl Batch {
+    /// Matching files under the input directory, relative to it, in sorted order. Only
+    /// descends into subdirectories when recursive, and never follows directory symlinks.
+    pub fn find_images(&self) -> std::io::Result<Vec<PathBuf>> {
+        let mut found = Vec::new();
+        let mut dirs = vec![PathBuf::new()];
+        while let Some(dir) = dirs.pop() {
+            for entry in std::fs::read_dir(self.input_dir.join(&dir))? {
+                let entry = entry?;
+                let path = dir.join(entry.file_name());
+                if entry.file_type()?.is_dir() {
+                    if self.recursive {
+                        dirs.push(path);
+                    }
+                } else if self.matches(&entry.file_name().to_string_lossy()) {
+                    found.push(path);
+                }
+            }
+        }
+        found.sort();
+        Ok(found)
+    }
+
+    /// Where the result for the input at `relative` goes: the same relative path under
+    /// the output directory, with the suffix added to the file stem.
+    pub fn output_path(&self, relative: &Path) -> PathBuf {
+        let stem = relative.file_stem().unwrap_or_default().to_string_lossy();
+        let name = match relative.extension() {
+            Some(ext) => format!("{}{}.{}", stem, self.suffix, ext.to_string_lossy()),
+            None => format!("{}{}", stem, self.suffix),
+        };
+        self.output_dir.join(relative).with_file_name(name)
+    }
+
+    /// Process every matching image, in parallel with the `rayon` feature, reporting each
+    /// finished file to `progress` under the stage `files`. Files that fail are skipped
+    /// and reported in the summary; only an unreadable input directory fails the whole run.
+    pub fn run(&self, progress: &mut (dyn FnMut(Progress) + Send)) -> Result<Summary, FreqError> {
+        let start = Instant::now();
+        let files = self.find_images()?;
+        let total = files.len();
+        let finished = Mutex::new((0, progress));
+        let process = |rel: PathBuf| {
+            let result = self.process(&rel);
+            let mut finished = finished.lock().unwrap();
+            finished.0 += 1;
+            let completed = finished.0;
+            (finished.1)(Progress { stage: "files", completed, total });
+            (result, rel)
+        };
+        #[cfg(feature = "rayon")]
+        let results: Vec<_> = {
+            use rayon::prelude::*;
+            files.into_par_iter().map(process).collect()
+        };
+        #[cfg(not(feature = "rayon"))]
+        let results: Vec<_> = files.into_iter().map(process).collect();
+
+        let mut summary = Summary { processed: 0, skipped: Vec::new(), elapsed: Duration::ZERO };
+        for (result, rel) in results {
+            match result {
+                Ok(()) => summary.processed += 1,
+                Err(err) => summary.skipped.push((rel, err)),
+            }
+        }
+        summary.elapsed = start.elapsed();
+        Ok(summary)
+    }
+
+    fn process(&self, relative: &Path) -> Result<(), FreqError> {
+        let result = self.operation.apply(&FreqImage::open(self.input_dir.join(relative))?)?;
+        let output = self.output_path(relative);
+        if let Some(dir) = output.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        result.save(output)?;
+        Ok(())
+    }
+
+    fn matches(&self, file_name: &str) -> bool {
+        match &self.pattern {
+            Some(pattern) => glob_match(pattern, file_name),
+            None => Path::new(file_name)
+                .extension()
+                .is_some_and(|ext| IMAGE_EXTENSIONS.iter().any(|e| ext.eq_ignore_ascii_case(e))),
+        }
+    }
+}
+
+/// Whether `name` matches `pattern`, where `*` matches any run of characters and `?`
+/// exactly one.
+fn glob_match(pattern: &str, name: &str) -> bool {
+    fn matches(pattern: &[char], name: &[char]) -> bool {
+        match pattern.split_first() {
+            None => name.is_empty(),
+            Some(('*', rest)) => (0..=name.len()).any(|i| matches(rest, &name[i..])),
+            Some(('?', rest)) => !name.is_empty() && matches(rest, &name[1..]),
+            Some((c, rest)) => name.first() == Some(c) && matches(rest, &name[1..]),
+        }
+    }
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    matches(&pattern, &name)
+}
+
+#[cfg(test)]
+fn batch(line: &str) -> Result<Batch, FreqError> {
+    parse_batch_args(&super::args(line))
+}
+
+#[test]
+fn test_parse_batch() {
+    let b = batch("scans out --op lowpass --cutoff 0.1 --smoothing 0.02 --recursive --pattern *.png --suffix _lp").unwrap();
+    assert_eq!(b.operation, Operation::Filter(freqshow::freq::pipeline::MaskSpec::LowPass { cutoff: 0.1, smoothing: 0.02 }));
+    assert_eq!((b.input_dir, b.output_dir), (PathBuf::from("scans"), PathBuf::from("out")));
+    assert!(b.recursive);
+    assert_eq!(b.pattern.as_deref(), Some("*.png"));
+    assert_eq!(b.suffix, "_lp");
+
+    let b = batch("in out --op spectrum").unwrap();
+    assert!(!b.recursive && b.pattern.is_none() && b.suffix.is_empty());
+
+    let error = |line| match batch(line) {
+        Err(FreqError::InvalidArgument(msg)) => msg,
+        other => panic!("{:?} parsed as {:?}", line, other),
+    };
+    assert!(error("in out").contains("requires --op"));
+    assert!(error("in --op spectrum").contains("input and an output directory"));
+    assert!(error("in out extra --op spectrum").contains("unexpected argument"));
+    assert!(error("in out --op lowpass").contains("lowpass requires --cutoff"));
+    assert!(error("in out --op lowpass --cutoff 2").contains("between 0 and 1"));
+    assert!(error("in out --op spectrum -o x.png").contains("does not take -o"));
+    assert!(error("in out --op spectrum --stdout").contains("does not take --stdout"));
+    assert!(error("in out --op lowpass --cutoff 0.1 --color").contains("does not take --color"));
+    assert!(error("in out --op sharpen").contains("unknown command"));
+    assert!(error("in out --op batch").contains("--op must be"));
+    assert!(super::error_text("lowpass a.png --cutoff 0.1 --recursive").contains("did you mean batch"));
+}
+
+#[test]
+fn test_glob_and_names() {
+    assert!(glob_match("*.png", "scan 01.png"));
+    assert!(!glob_match("*.png", "scan.png.bak"));
+    assert!(glob_match("scan_??.*", "scan_07.tif"));
+    assert!(!glob_match("scan_??.*", "scan_7.tif"));
+    assert!(glob_match("*", ""));
+
+    let b = batch("in out --op spectrum --suffix _s").unwrap();
+    assert_eq!(b.output_path(Path::new("a/b/scan.png")), Path::new("out/a/b/scan_s.png"));
+    assert_eq!(b.output_path(Path::new("raw")), Path::new("out/raw_s"));
+    assert!(b.matches("SCAN.JPG") && b.matches("x.tiff") && !b.matches("notes.txt") && !b.matches("png"));
+}
+
+#[test]
+fn test_batch_run() {
+    use image::{GrayImage, Luma};
+
+    let root = std::env::temp_dir().join(format!("freqshow_batch_{}", std::process::id()));
+    let (input, output) = (root.join("in"), root.join("out"));
+    std::fs::create_dir_all(input.join("sub/deeper")).unwrap();
+    let img = GrayImage::from_fn(8, 6, |x, y| Luma([(x * 30 + y * 10) as u8]));
+    for name in ["a.png", "sub/b.png", "sub/deeper/c.png"] {
+        img.save(input.join(name)).unwrap();
+    }
+    std::fs::write(input.join("corrupt.png"), b"not a png").unwrap();
+    std::fs::write(input.join("notes.txt"), b"ignored").unwrap();
+
+    let line = format!("{} {} --op lowpass --cutoff 0.2 --suffix _lp", input.display(), output.display());
+    let mut b = batch(&line).unwrap();
+    assert_eq!(b.find_images().unwrap(), [Path::new("a.png"), Path::new("corrupt.png")]);
+    b.recursive = true;
+    let all = b.find_images().unwrap();
+    assert_eq!(all, ["a.png", "corrupt.png", "sub/b.png", "sub/deeper/c.png"].map(PathBuf::from));
+    b.pattern = Some("b*".to_string());
+    assert_eq!(b.find_images().unwrap(), [PathBuf::from("sub/b.png")]);
+    b.pattern = None;
+
+    let mut reports = Vec::new();
+    let summary = b.run(&mut |p| reports.push(p)).unwrap();
+    let written = [output.join("a_lp.png"), output.join("sub/b_lp.png"), output.join("sub/deeper/c_lp.png")];
+    let dims: Vec<_> = written.iter().map(|p| image::open(p).map(|i| (i.width(), i.height()))).collect();
+    let corrupt_written = output.join("corrupt_lp.png").exists();
+    let missing_dir = batch(&format!("{} {} --op spectrum", root.join("missing").display(), output.display())).unwrap().run(&mut |_| {});
+    std::fs::remove_dir_all(&root).unwrap();
+
+    assert_eq!(summary.processed, 3);
+    assert_eq!(reports.iter().map(|p| p.completed).collect::<Vec<_>>(), [1, 2, 3, 4]);
+    assert!(reports.iter().all(|p| p.stage == "files" && p.total == 4));
+    assert_eq!(summary.skipped.len(), 1);
+    assert_eq!(summary.skipped[0].0, Path::new("corrupt.png"));
+    for d in dims {
+        assert_eq!(d.unwrap(), (8, 6));
+    }
+    assert!(!corrupt_written);
+    assert!(matches!(missing_dir, Err(FreqError::Io(_))));
+}
@@ -0,0 +1,172 @@
+//! `freqshow hybrid`: the low frequencies of one image over the high frequencies of another.
+
+use std::path::{Path, PathBuf};
+
+use freqshow::freq::pipeline::{FilterPipeline, MaskSpec};
+use freqshow::freq::{hybrid, resize_to_smaller, Normalize};
+use freqshow::{FreqError, FreqImage};
+
+use super::{check_operation, default_output, invalid, next_value, parse_number, Operation, DEFAULT_SMOOTHING};
+
+/// A parsed `hybrid` invocation.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Hybrid {
+    /// The image seen from afar.
+    pub low_source: PathBuf,
+    /// The image seen up close.
+    pub high_source: PathBuf,
+    pub cutoff: f64,
+    pub smoothing: f64,
+    pub output: PathBuf,
+    /// Also write the two filtered components next to the output.
+    pub preview: bool,
+    /// Resize and crop the larger input to the smaller one instead of rejecting
+    /// different sizes; see [`resize_to_smaller`].
+    pub resize_to_smaller: bool,
+}
+
+/// Parse the arguments after `hybrid`: `<low_source> <high_source> --cutoff <c>
+/// [--smoothing <s>] [--swap] [--preview] [--resize-to-smaller] [-o <out>]`. `--swap` is
+/// resolved here, so the sources come back in the roles they'll play.
+pub fn parse_hybrid_args(args: &[String]) -> Result<Hybrid, FreqError> {
+    let (mut paths, mut output) = (Vec::new(), None);
+    let (mut cutoff, mut smoothing) = (None, DEFAULT_SMOOTHING);
+    let (mut swap, mut preview, mut resize) = (false, false, false);
+    let mut rest = args.iter();
+    while let Some(arg) = rest.next() {
+        match arg.as_str() {
+            "-o" | "--output" => output = Some(PathBuf::from(next_value(arg, &mut rest)?)),
+            "--cutoff" => cutoff = Some(parse_number(arg, next_value(arg, &mut rest)?)?),
+            "--smoothing" => smoothing = parse_number(arg, next_value(arg, &mut rest)?)?,
+            "--swap" => swap = true,
+            "--preview" => preview = true,
+            "--resize-to-smaller" => resize = true,
+            flag if flag.starts_with('-') && flag.len() > 1 => {
+                return Err(invalid(format!("hybrid does not take {}", flag)));
+            }
+            path => paths.push(path),
+        }
+    }
+    let (mut low_source, mut high_source) = match paths[..] {
+        [low, high] => (PathBuf::from(low), PathBuf::from(high)),
+        [_, _, extra, ..] => return Err(invalid(format!("unexpected argument {:?}", extra))),
+        _ => return Err(invalid("hybrid requires a low-frequency and a high-frequency source")),
+    };
+    let cutoff = cutoff.ok_or_else(|| invalid("hybrid requires --cutoff"))?;
+    check_operation(&Operation::Filter(MaskSpec::LowPass { cutoff, smoothing }))?;
+    if swap {
+        std::mem::swap(&mut low_source, &mut high_source);
+    }
+    let output = output.unwrap_or_else(|| default_output(&low_source, "hybrid"));
+    Ok(Hybrid { low_source, high_source, cutoff, smoothing, output, preview, resize_to_smaller: resize })
+}
+
+impl Hybrid {
+    /// Compose the hybrid and write it, plus `<stem>_low.png` and `<stem>_high.png` beside
+    /// it with `--preview`. Returns the paths written, the hybrid first.
+    pub fn run(&self) -> Result<Vec<PathBuf>, FreqError> {
+        let (mut low, mut high) = (FreqImage::open(&self.low_source)?, FreqImage::open(&self.high_source)?);
+        if (low.width, low.height) != (high.width, high.height) {
+            if !self.resize_to_smaller {
+                return Err(invalid(format!(
+                    "{} is {}x{} but {} is {}x{}; pass --resize-to-smaller to scale and crop the larger to match",
+                    self.low_source.display(), low.width, low.height,
+                    self.high_source.display(), high.width, high.height
+                )));
+            }
+            (low, high) = resize_to_smaller(&low, &high);
+        }
+        let mut written = vec![self.output.clone()];
+        save(&self.output, hybrid(&low, &high, self.cutoff, self.smoothing)?.to_image())?;
+        if self.preview {
+            let components = [
+                ("low", MaskSpec::LowPass { cutoff: self.cutoff, smoothing: self.smoothing }, &low, Normalize::Clamp),
+                // The high-pass component is centered on zero, so stretch it to be visible.
+                ("high", MaskSpec::HighPass { cutoff: self.cutoff, smoothing: self.smoothing }, &high, Normalize::MinMax),
+            ];
+            for (name, spec, source, mode) in components {
+                let path = self.component_path(name);
+                save(&path, FilterPipeline::new().mask(spec).run(source)?.to_image_with(mode))?;
+                written.push(path);
+            }
+        }
+        Ok(written)
+    }
+
+    /// `<output stem>_<name>.png` in the output's directory.
+    fn component_path(&self, name: &str) -> PathBuf {
+        let stem = self.output.file_stem().unwrap_or_default().to_string_lossy();
+        self.output.with_file_name(format!("{}_{}.png", stem, name))
+    }
+}
+
+fn save(path: &Path, img: image::GrayImage) -> Result<(), FreqError> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    println!("writing to: {}", path.display());
+    img.save(path)?;
+    Ok(())
+}
+
+#[cfg(test)]
+fn hybrid_cmd(line: &str) -> Result<Hybrid, FreqError> {
+    parse_hybrid_args(&super::args(line))
+}
+
+#[test]
+fn test_parse_hybrid() {
+    let h = hybrid_cmd("far.png near.png --cutoff 0.06 --smoothing 0.03 -o out/h.png").unwrap();
+    assert_eq!((h.low_source, h.high_source), (PathBuf::from("far.png"), PathBuf::from("near.png")));
+    assert_eq!((h.cutoff, h.smoothing, h.output), (0.06, 0.03, PathBuf::from("out/h.png")));
+    assert!(!h.preview && !h.resize_to_smaller);
+
+    let h = hybrid_cmd("far.png near.png --cutoff 0.06 --swap --preview").unwrap();
+    assert_eq!((h.low_source.as_path(), h.high_source.as_path()), (Path::new("near.png"), Path::new("far.png")));
+    assert_eq!(h.smoothing, DEFAULT_SMOOTHING);
+    assert_eq!(h.output, Path::new("output").join("near_hybrid.png"));
+    assert_eq!(h.component_path("low"), Path::new("output").join("near_hybrid_low.png"));
+
+    let error = |line| match hybrid_cmd(line) {
+        Err(FreqError::InvalidArgument(msg)) => msg,
+        other => panic!("{:?} parsed as {:?}", line, other),
+    };
+    assert!(error("far.png near.png").contains("requires --cutoff"));
+    assert!(error("far.png --cutoff 0.1").contains("low-frequency and a high-frequency source"));
+    assert!(error("a.png b.png c.png --cutoff 0.1").contains("unexpected argument"));
+    assert!(error("a.png b.png --cutoff 1.5").contains("--cutoff must be between 0 and 1"));
+    assert!(error("a.png b.png --cutoff 0.1 --json").contains("hybrid does not take --json"));
+}
+
+#[test]
+fn test_hybrid_run() {
+    use image::{GrayImage, Luma};
+
+    let root = std::env::temp_dir().join(format!("freqshow_hybrid_{}", std::process::id()));
+    std::fs::create_dir_all(&root).unwrap();
+    GrayImage::from_fn(24, 16, |x, y| Luma([(x * 10 + y * 3) as u8])).save(root.join("far.png")).unwrap();
+    GrayImage::from_fn(24, 16, |x, y| Luma([if (x / 2 + y / 2) % 2 == 0 { 220 } else { 30 }])).save(root.join("near.png")).unwrap();
+    GrayImage::from_fn(36, 20, |x, _| Luma([(x * 7) as u8])).save(root.join("large.png")).unwrap();
+
+    let paths = |a: &str, b: &str| (root.join(a).display().to_string(), root.join(b).display().to_string());
+    let (far, near) = paths("far.png", "near.png");
+    let out = root.join("out/h.png");
+    let written = hybrid_cmd(&format!("{} {} --cutoff 0.1 --preview -o {}", far, near, out.display())).unwrap().run();
+    let dims: Vec<_> = ["out/h.png", "out/h_low.png", "out/h_high.png"]
+        .iter()
+        .map(|p| image::open(root.join(p)).map(|i| (i.width(), i.height())))
+        .collect();
+    let (far, large) = paths("far.png", "large.png");
+    let mismatch = hybrid_cmd(&format!("{} {} --cutoff 0.1 -o {}", far, large, out.display())).unwrap().run();
+    let resized = hybrid_cmd(&format!("{} {} --cutoff 0.1 --resize-to-smaller -o {}", large, far, out.display())).unwrap().run();
+    let resized_dims = image::open(&out).map(|i| (i.width(), i.height()));
+    std::fs::remove_dir_all(&root).unwrap();
+
+    assert_eq!(written.unwrap().len(), 3);
+    for d in dims {
+        assert_eq!(d.unwrap(), (24, 16));
+    }
+    assert!(matches!(mismatch, Err(FreqError::InvalidArgument(msg)) if msg.contains("--resize-to-smaller")));
+    assert!(resized.is_ok());
+    assert_eq!(resized_dims.unwrap(), (24, 16));
+}
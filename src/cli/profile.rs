@@ -0,0 +1,190 @@
+//! `freqshow profile`: radially averaged power spectra as CSV, for comparing sharpness.
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use freqshow::freq::viz::plot_log_log;
+use freqshow::{FreqError, FreqImage};
+
+use super::{invalid, next_value, parse_number, STDIO};
+
+/// Rings used when `--bins` isn't given.
+const DEFAULT_BINS: usize = 128;
+
+/// Size in pixels of the `--plot` chart.
+const PLOT_SIZE: (u32, u32) = (640, 480);
+
+/// A parsed `profile` invocation.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Profile {
+    pub inputs: Vec<PathBuf>,
+    pub bins: usize,
+    /// The CSV file, or `-` for stdout.
+    pub output: PathBuf,
+    /// Where to write a log-log chart of the profiles.
+    pub plot: Option<PathBuf>,
+}
+
+/// Parse the arguments after `profile`: `<image>... [--bins <n>] [-o <out>] [--plot <png>]`.
+pub fn parse_profile_args(args: &[String]) -> Result<Profile, FreqError> {
+    let (mut inputs, mut output, mut plot, mut bins) = (Vec::new(), None, None, DEFAULT_BINS);
+    let mut rest = args.iter();
+    while let Some(arg) = rest.next() {
+        match arg.as_str() {
+            "-o" | "--output" => output = Some(PathBuf::from(next_value(arg, &mut rest)?)),
+            "--plot" => plot = Some(PathBuf::from(next_value(arg, &mut rest)?)),
+            "--bins" => {
+                let n = parse_number(arg, next_value(arg, &mut rest)?)?;
+                if n < 1.0 || n.fract() != 0.0 {
+                    return Err(invalid(format!("--bins must be a positive whole number, got {}", n)));
+                }
+                bins = n as usize;
+            }
+            flag if flag.starts_with('-') && flag.len() > 1 => {
+                return Err(invalid(format!("profile does not take {}", flag)));
+            }
+            path => inputs.push(PathBuf::from(path)),
+        }
+    }
+    let first = inputs.first().ok_or_else(|| invalid("missing input image"))?;
+    let output = output.unwrap_or_else(|| {
+        let stem = first.file_stem().unwrap_or_default().to_string_lossy();
+        Path::new("output").join(format!("{}_profile.csv", stem))
+    });
+    Ok(Profile { inputs, bins, output, plot })
+}
+
+impl Profile {
+    /// Compute every profile, then write the CSV and the chart.
+    pub fn run(&self) -> Result<(), FreqError> {
+        let profiles = self.inputs.iter().map(|path| self.profile_of(path)).collect::<Result<Vec<_>, _>>()?;
+        let csv = self.csv(&profiles);
+        if self.output == Path::new(STDIO) {
+            let mut stdout = std::io::stdout().lock();
+            stdout.write_all(csv.as_bytes())?;
+            stdout.flush()?;
+        } else {
+            self.announce(&self.output);
+            create_parent(&self.output)?;
+            std::fs::write(&self.output, csv)?;
+        }
+        if let Some(path) = &self.plot {
+            // The DC ring would flatten everything else on the chart, so leave it out.
+            let series: Vec<Vec<(f64, f64)>> = profiles
+                .iter()
+                .map(|p| p.iter().enumerate().skip(1).map(|(i, &power)| (self.frequency(i), power)).collect())
+                .collect();
+            self.announce(path);
+            create_parent(path)?;
+            plot_log_log(&series, PLOT_SIZE.0, PLOT_SIZE.1).save(path)?;
+        }
+        Ok(())
+    }
+
+    /// The mean power of `path` in each ring, from [`FreqImage::radial_power_spectrum`].
+    fn profile_of(&self, path: &Path) -> Result<Vec<f64>, FreqError> {
+        let mut fi = FreqImage::open(path)?;
+        fi.fft_forward();
+        Ok(fi.radial_power_spectrum(self.bins))
+    }
+
+    /// The center of ring `i` in cycles per pixel.
+    fn frequency(&self, i: usize) -> f64 {
+        (i as f64 + 0.5) * 0.5 / self.bins as f64
+    }
+
+    /// A `frequency` column followed by one power column per input, headed by its path.
+    fn csv(&self, profiles: &[Vec<f64>]) -> String {
+        let mut out = String::from("frequency");
+        for input in &self.inputs {
+            out.push(',');
+            out.push_str(&input.display().to_string());
+        }
+        out.push('\n');
+        for i in 0..self.bins {
+            out.push_str(&self.frequency(i).to_string());
+            for profile in profiles {
+                out.push(',');
+                out.push_str(&profile[i].to_string());
+            }
+            out.push('\n');
+        }
+        out
+    }
+
+    /// Progress notes go to stderr when the CSV goes to stdout.
+    fn announce(&self, path: &Path) {
+        if self.output == Path::new(STDIO) {
+            eprintln!("writing to: {}", path.display());
+        } else {
+            println!("writing to: {}", path.display());
+        }
+    }
+}
+
+fn create_parent(path: &Path) -> Result<(), FreqError> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+fn profile(line: &str) -> Result<Profile, FreqError> {
+    parse_profile_args(&super::args(line))
+}
+
+#[test]
+fn test_parse_profile() {
+    let p = profile("a.png b.png --bins 64 -o out/p.csv --plot out/p.png").unwrap();
+    assert_eq!(p.inputs, [PathBuf::from("a.png"), PathBuf::from("b.png")]);
+    assert_eq!((p.bins, p.output), (64, PathBuf::from("out/p.csv")));
+    assert_eq!(p.plot, Some(PathBuf::from("out/p.png")));
+    let p = profile("scan.tif").unwrap();
+    assert_eq!((p.bins, p.output, p.plot), (DEFAULT_BINS, Path::new("output").join("scan_profile.csv"), None));
+
+    let error = |line| match profile(line) {
+        Err(FreqError::InvalidArgument(msg)) => msg,
+        other => panic!("{:?} parsed as {:?}", line, other),
+    };
+    assert!(error("--bins 8").contains("missing input"));
+    assert!(error("a.png --bins 0").contains("positive whole number"));
+    assert!(error("a.png --bins 2.5").contains("positive whole number"));
+    assert!(error("a.png --cutoff 0.1").contains("profile does not take --cutoff"));
+}
+
+#[test]
+fn test_profile_csv() {
+    use freqshow::freq::synth::grating;
+
+    let root = std::env::temp_dir().join(format!("freqshow_profile_{}", std::process::id()));
+    std::fs::create_dir_all(&root).unwrap();
+    // 16 and 8 cycles across 64 pixels: 0.25 and 0.125 cycles per pixel.
+    grating(64, 64, 16.0, 0.0).to_image().save(root.join("fine.png")).unwrap();
+    grating(64, 64, 0.0, 8.0).to_image().save(root.join("coarse.png")).unwrap();
+    let line = format!(
+        "{} {} --bins 32 -o {} --plot {}",
+        root.join("fine.png").display(),
+        root.join("coarse.png").display(),
+        root.join("out/p.csv").display(),
+        root.join("out/p.png").display()
+    );
+    profile(&line).unwrap().run().unwrap();
+    let csv = std::fs::read_to_string(root.join("out/p.csv")).unwrap();
+    let plot = image::open(root.join("out/p.png")).map(|i| (i.width(), i.height()));
+    std::fs::remove_dir_all(&root).unwrap();
+
+    let mut lines = csv.lines();
+    let header: Vec<&str> = lines.next().unwrap().split(',').collect();
+    assert_eq!(header.len(), 3);
+    assert_eq!(header[0], "frequency");
+    assert!(header[1].ends_with("fine.png") && header[2].ends_with("coarse.png"));
+    let rows: Vec<Vec<f64>> = lines.map(|l| l.split(',').map(|v| v.parse().unwrap()).collect()).collect();
+    assert_eq!(rows.len(), 32);
+    // Skip the DC ring, then the strongest ring of each column holds its grating.
+    for (column, expected) in [(1, 0.25), (2, 0.125)] {
+        let peak = rows.iter().skip(1).max_by(|a, b| a[column].total_cmp(&b[column])).unwrap();
+        assert!((peak[0] - expected).abs() <= 0.5 / 32.0, "column {} peaks at {}", column, peak[0]);
+    }
+    assert_eq!(plot.unwrap(), PLOT_SIZE);
+}
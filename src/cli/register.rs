@@ -0,0 +1,169 @@
+//! `freqshow register`: estimate how far one image is shifted from another, and
+//! optionally write it back into alignment.
+
+use std::path::{Path, PathBuf};
+
+use freqshow::freq::register::{phase_correlate, register_full, FullRegistration};
+use freqshow::{FreqError, FreqImage};
+
+use super::{invalid, next_value};
+
+/// A parsed `register` invocation.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Register {
+    pub reference: PathBuf,
+    pub moving: PathBuf,
+    /// Where to write the moving image resampled into alignment.
+    pub apply: Option<PathBuf>,
+    /// Print JSON instead of text.
+    pub json: bool,
+    /// Estimate rotation and scale as well as translation.
+    pub full: bool,
+    /// Register the central region both images share instead of rejecting different sizes.
+    pub crop_to_common: bool,
+}
+
+/// Parse the arguments after `register`:
+/// `<reference> <moving> [--apply <out>] [--json] [--full] [--crop-to-common]`.
+pub fn parse_register_args(args: &[String]) -> Result<Register, FreqError> {
+    let (mut paths, mut apply) = (Vec::new(), None);
+    let (mut json, mut full, mut crop_to_common) = (false, false, false);
+    let mut rest = args.iter();
+    while let Some(arg) = rest.next() {
+        match arg.as_str() {
+            "--apply" => apply = Some(PathBuf::from(next_value(arg, &mut rest)?)),
+            "--json" => json = true,
+            "--full" => full = true,
+            "--crop-to-common" => crop_to_common = true,
+            flag if flag.starts_with('-') && flag.len() > 1 => {
+                return Err(invalid(format!("register does not take {}", flag)));
+            }
+            path => paths.push(path),
+        }
+    }
+    let (reference, moving) = match paths[..] {
+        [reference, moving] => (PathBuf::from(reference), PathBuf::from(moving)),
+        [_, _, extra, ..] => return Err(invalid(format!("unexpected argument {:?}", extra))),
+        _ => return Err(invalid("register requires a reference and a moving image")),
+    };
+    Ok(Register { reference, moving, apply, json, full, crop_to_common })
+}
+
+impl Register {
+    /// Register the moving image against the reference, writing the aligned image if
+    /// `--apply` was given. Without `--full`, the angle is 0 and the scale 1.
+    pub fn run(&self) -> Result<FullRegistration, FreqError> {
+        let (mut reference, mut moving) = (FreqImage::open(&self.reference)?, FreqImage::open(&self.moving)?);
+        if (reference.width, reference.height) != (moving.width, moving.height) {
+            if !self.crop_to_common {
+                return Err(invalid(format!(
+                    "{} is {}x{} but {} is {}x{}; pass --crop-to-common to register their common center",
+                    self.reference.display(), reference.width, reference.height,
+                    self.moving.display(), moving.width, moving.height
+                )));
+            }
+            let (w, h) = (reference.width.min(moving.width), reference.height.min(moving.height));
+            (reference, moving) = (reference.center_crop(w, h), moving.center_crop(w, h));
+        }
+        let reg = if self.full {
+            register_full(&reference, &moving)?
+        } else {
+            FullRegistration { angle: 0.0, scale: 1.0, translation: phase_correlate(&reference, &moving)? }
+        };
+        if let Some(path) = &self.apply {
+            self.write_aligned(path, &reg.apply(&moving))?;
+        }
+        Ok(reg)
+    }
+
+    /// The result as `dx`, `dy` and `peak` (plus `angle` and `scale` with `--full`),
+    /// one per line or as a JSON object.
+    pub fn report(&self, reg: &FullRegistration) -> String {
+        let t = &reg.translation;
+        let mut fields = Vec::new();
+        if self.full {
+            fields.extend([("angle", reg.angle), ("scale", reg.scale)]);
+        }
+        fields.extend([("dx", t.dx), ("dy", t.dy), ("peak", t.peak_value)]);
+        if self.json {
+            let fields: Vec<String> = fields.iter().map(|(k, v)| format!("\"{}\":{}", k, v)).collect();
+            format!("{{{}}}", fields.join(","))
+        } else {
+            let lines: Vec<String> = fields.iter().map(|(k, v)| format!("{}: {:.3}", k, v)).collect();
+            lines.join("\n")
+        }
+    }
+
+    /// Keeps stdout to the JSON alone when `--json` is given.
+    fn write_aligned(&self, path: &Path, aligned: &FreqImage) -> Result<(), FreqError> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        if self.json {
+            eprintln!("writing to: {}", path.display());
+        } else {
+            println!("writing to: {}", path.display());
+        }
+        aligned.to_image().save(path)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+fn register(line: &str) -> Result<Register, FreqError> {
+    parse_register_args(&super::args(line))
+}
+
+#[test]
+fn test_parse_register() {
+    let r = register("ref.png mov.png --apply out/aligned.png --json").unwrap();
+    assert_eq!((r.reference, r.moving), (PathBuf::from("ref.png"), PathBuf::from("mov.png")));
+    assert_eq!(r.apply, Some(PathBuf::from("out/aligned.png")));
+    assert!(r.json && !r.full && !r.crop_to_common);
+    let r = register("--full --crop-to-common ref.png mov.png").unwrap();
+    assert!(r.full && r.crop_to_common && r.apply.is_none());
+
+    let error = |line| match register(line) {
+        Err(FreqError::InvalidArgument(msg)) => msg,
+        other => panic!("{:?} parsed as {:?}", line, other),
+    };
+    assert!(error("ref.png").contains("reference and a moving image"));
+    assert!(error("a.png b.png c.png").contains("unexpected argument"));
+    assert!(error("a.png b.png --cutoff 0.1").contains("register does not take --cutoff"));
+    assert!(error("a.png b.png --apply").contains("--apply needs a value"));
+}
+
+#[test]
+fn test_register_run() {
+    use image::{GrayImage, Luma};
+
+    let root = std::env::temp_dir().join(format!("freqshow_register_{}", std::process::id()));
+    std::fs::create_dir_all(&root).unwrap();
+    let pattern = |x: u32, y: u32| Luma([((x * 37 + y * 11) % 64 * 3 + (x * y) % 29) as u8]);
+    let (dx, dy) = (5, 3);
+    GrayImage::from_fn(32, 24, pattern).save(root.join("ref.png")).unwrap();
+    // The moving image is the reference shifted left and up, so aligning it moves it back.
+    GrayImage::from_fn(32, 24, |x, y| pattern((x + dx) % 32, (y + dy) % 24)).save(root.join("mov.png")).unwrap();
+    GrayImage::new(30, 24).save(root.join("small.png")).unwrap();
+
+    let line = format!("{} {} --apply {} --json", root.join("ref.png").display(), root.join("mov.png").display(), root.join("out/aligned.png").display());
+    let cmd = register(&line).unwrap();
+    let reg = cmd.run().unwrap();
+    let aligned = image::open(root.join("out/aligned.png")).map(|i| i.into_luma8());
+    let reference = image::open(root.join("ref.png")).unwrap().into_luma8();
+    let mismatch = register(&format!("{} {}", root.join("ref.png").display(), root.join("small.png").display())).unwrap().run();
+    let cropped = register(&format!("{} {} --crop-to-common", root.join("ref.png").display(), root.join("small.png").display())).unwrap().run();
+    std::fs::remove_dir_all(&root).unwrap();
+
+    assert!((reg.translation.dx - dx as f64).abs() < 0.01 && (reg.translation.dy - dy as f64).abs() < 0.01, "{:?}", reg);
+    assert_eq!(aligned.unwrap(), reference);
+    let json: serde_json::Value = serde_json::from_str(&cmd.report(&reg)).unwrap();
+    assert!((json["dx"].as_f64().unwrap() - dx as f64).abs() < 0.01);
+    assert!(json.get("angle").is_none());
+    assert!(matches!(mismatch, Err(FreqError::InvalidArgument(msg)) if msg.contains("--crop-to-common")));
+    assert!(cropped.is_ok());
+
+    let text = Register { full: true, json: false, ..cmd }.report(&reg);
+    assert_eq!(text.lines().next(), Some("angle: 0.000"));
+    assert!(text.contains("dx: 5.000"));
+}
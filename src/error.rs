@@ -0,0 +1,140 @@
+use std::fmt;
+
+/// Errors returned by the fallible FreqImage APIs.
+#[derive(Debug)]
+pub enum FreqError {
+    /// A buffer did not hold the number of elements its dimensions call for.
+    LengthMismatch {
+        /// Number of elements required.
+        expected: usize,
+        /// Number of elements supplied.
+        got: usize,
+    },
+    /// Two images that must share dimensions did not.
+    DimensionMismatch {
+        /// `(width, height)` required.
+        expected: (u32, u32),
+        /// `(width, height)` supplied.
+        got: (u32, u32),
+    },
+    /// A frequency band was empty, reversed, negative or not finite.
+    InvalidCutoff {
+        /// Inner edge of the band.
+        low: f64,
+        /// Outer edge of the band.
+        high: f64,
+    },
+    /// An image's dimensions do not describe its buffer.
+    InvalidDimensions {
+        /// Declared width.
+        width: u32,
+        /// Declared height.
+        height: u32,
+        /// Number of elements actually held.
+        len: usize,
+    },
+    /// Some values were NaN or infinite.
+    NonFinite {
+        /// How many values were not finite.
+        count: usize,
+        /// Index of the first one in the row-major buffer.
+        first_index: usize,
+    },
+    /// An image would need more memory than allowed.
+    TooLarge {
+        /// Estimated bytes needed.
+        required: u64,
+        /// Bytes allowed.
+        limit: u64,
+    },
+    /// Reading or writing a file failed.
+    Io(std::io::Error),
+    /// Decoding, encoding or converting an image failed.
+    Image(image::ImageError),
+    /// A serialized buffer was malformed or used an unsupported layout.
+    InvalidFormat(String),
+    /// A parameter was outside the range the operation accepts.
+    InvalidArgument(String),
+    /// A display window could not be opened or updated.
+    Viewer(String),
+}
+
+impl fmt::Display for FreqError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FreqError::LengthMismatch { expected, got } => {
+                write!(f, "buffer length mismatch: expected {} elements, got {}", expected, got)
+            }
+            FreqError::DimensionMismatch { expected, got } => write!(
+                f,
+                "dimension mismatch: expected {}x{}, got {}x{}",
+                expected.0, expected.1, got.0, got.1
+            ),
+            FreqError::InvalidCutoff { low, high } => {
+                write!(f, "invalid cutoff: band [{}, {}] must satisfy 0 <= low < high", low, high)
+            }
+            FreqError::InvalidDimensions { width, height, len } => {
+                write!(f, "invalid dimensions: {}x{} image holds {} elements", width, height, len)
+            }
+            FreqError::NonFinite { count, first_index } => {
+                write!(f, "{} non-finite values, the first at index {}", count, first_index)
+            }
+            FreqError::TooLarge { required, limit } => {
+                write!(f, "image too large: needs about {} bytes, limit is {}", required, limit)
+            }
+            FreqError::Io(err) => write!(f, "I/O error: {}", err),
+            FreqError::Image(err) => write!(f, "image error: {}", err),
+            FreqError::InvalidFormat(msg) => write!(f, "invalid format: {}", msg),
+            FreqError::InvalidArgument(msg) => write!(f, "invalid argument: {}", msg),
+            FreqError::Viewer(msg) => write!(f, "viewer error: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for FreqError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            FreqError::Io(err) => Some(err),
+            FreqError::Image(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for FreqError {
+    fn from(err: std::io::Error) -> Self {
+        FreqError::Io(err)
+    }
+}
+
+impl From<image::ImageError> for FreqError {
+    fn from(err: image::ImageError) -> Self {
+        FreqError::Image(err)
+    }
+}
+
+#[test]
+fn test_display() {
+    use std::error::Error;
+
+    let cases = [
+        (FreqError::LengthMismatch { expected: 12, got: 7 }, "expected 12 elements, got 7"),
+        (FreqError::DimensionMismatch { expected: (4, 3), got: (5, 6) }, "expected 4x3, got 5x6"),
+        (FreqError::InvalidCutoff { low: 0.3, high: 0.1 }, "band [0.3, 0.1]"),
+        (FreqError::InvalidDimensions { width: 4, height: 2, len: 5 }, "4x2 image holds 5 elements"),
+        (FreqError::NonFinite { count: 3, first_index: 17 }, "3 non-finite values, the first at index 17"),
+        (FreqError::TooLarge { required: 5000, limit: 4096 }, "needs about 5000 bytes, limit is 4096"),
+        (FreqError::InvalidFormat("bad magic".into()), "invalid format: bad magic"),
+        (FreqError::InvalidArgument("max_dim must be positive".into()), "invalid argument: max_dim must be positive"),
+        (FreqError::Viewer("no display".into()), "viewer error: no display"),
+    ];
+    for (err, text) in cases {
+        assert!(err.to_string().contains(text), "{}", err);
+        assert!(err.source().is_none());
+    }
+
+    let io = FreqError::from(std::io::Error::new(std::io::ErrorKind::NotFound, "missing.png"));
+    assert!(io.to_string().contains("missing.png") && io.source().is_some());
+    let image = FreqError::from(image::ImageError::IoError(std::io::Error::other("truncated")));
+    assert!(image.to_string().starts_with("image error: ") && image.source().is_some());
+}
@@ -1,113 +1,323 @@
-use std::io::Cursor;
-use image::{io::Reader as ImageReader, DynamicImage};
+use std::ops::{Index, IndexMut};
+use std::path::Path;
+use image::DynamicImage;
 
-use rustfft::{FftPlanner, num_complex::Complex, FftDirection};
-use show_image::{ImageView, ImageInfo, create_window};
+use rustfft::num_complex::Complex;
 
+mod analysis;
+mod arith;
+mod bands;
+mod blockwise;
+#[cfg(feature = "ndarray")]
+mod array;
+mod channels;
+mod coherence;
+mod colormap;
+pub mod compat;
+mod context;
+mod convolve;
+mod correlate;
+mod csv;
+mod deconvolve;
+mod descreen;
+mod fft;
+mod filter;
+mod geometry;
+mod histogram;
+mod hybrid;
+mod interleaved;
+pub mod io;
+mod limits;
+mod logpolar;
+pub mod mask_ops;
+pub mod metrics;
+mod motion;
+mod normalize;
+#[cfg(feature = "exif")]
+mod orientation;
+mod npy;
+mod peaks;
+mod periodic;
+mod phase;
+pub mod pipeline;
+mod polar;
+pub mod profile;
+mod progress;
+mod quadrant;
+mod quantize;
+pub mod radial;
+mod raw;
+pub mod register;
+mod resample;
+mod rng;
+mod saliency;
+mod shear;
+mod shift;
+mod srgb;
+mod stats;
+pub mod synth;
+mod symmetry;
+mod template;
+#[cfg(feature = "testing")]
+pub mod testing;
+mod tiff;
+pub mod trace;
+mod view;
+#[cfg(feature = "viewer")]
+pub mod viewer;
+pub mod viz;
+mod watermark;
+mod window;
+mod zoom;
+#[cfg(feature = "serde")]
+mod serialize;
 
-fn main() -> Result<(), Box<dyn std::error::Error>> {    
+pub use bands::band_reconstruct;
+pub use blockwise::{blockwise_spectrum, blockwise_spectrum_with_progress, BlockSpectrum};
+pub use channels::Channels;
+pub use coherence::{coherence, cross_power};
+pub use colormap::{apply_colormap, Colormap};
+pub use context::FftContext;
+pub use convolve::{Boundary, Kernel2D};
+pub use csv::CsvPart;
+pub use descreen::ScreenInfo;
+#[allow(deprecated)]
+pub use fft::fft_forward;
+pub use histogram::{render_histogram, Histogram};
+pub use hybrid::hybrid;
+pub use logpolar::Interpolation;
+pub use motion::MotionBlurEstimate;
+pub use normalize::{ClampPolicy, FinalizeReport, Normalize};
+pub use peaks::SpectralPeak;
+pub use phase::{swap_phase, PhaseChoice};
+pub use progress::Progress;
+pub use quadrant::{mirror_from_top_half, Quadrant};
+pub use quantize::QuantizeMode;
+pub use resample::resize_to_smaller;
+pub use shear::RotateEdge;
+pub use stats::FreqStats;
+pub use symmetry::fft_pair;
+pub use template::{match_template, MatchResult};
+pub use window::WindowKind;
 
-    let args: Vec<_> = std::env::args().collect();
-    if args.len() < 1 {
-        println!("usage: freqshow <image_file_name>");
-    }
-    let image = read_image( args[1].clone()); //mandrill.jpg
+/// A grayscale image held as a row-major buffer of complex values, either in the
+/// spatial domain or (after a forward FFT) in the frequency domain.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(into = "serialize::RawFreqImage", try_from = "serialize::RawFreqImage")
+)]
+pub struct FreqImage {
+    /// Image width in pixels.
+    pub width: u32,
+    /// Image height in pixels.
+    pub height: u32,
+    /// Row-major pixel buffer, `data[y * width + x]`, of length `width * height`.
+    pub data: Vec<Complex<f64>>,
+}
 
-    let (width , height) = image.dimensions();
-    let mut img_buffer = dynimg2complex(image);
-    fft_forward(width as usize, height as usize, &mut img_buffer);
+impl FreqImage {
+    /// Open an image file and convert it to a grayscale FreqImage with values in [0, 1].
+    pub fn open<P: AsRef<Path>>(path: P) -> image::ImageResult<Self> {
+        Ok(Self::from_image(image::open(path)?))
+    }
 
+    /// Convert an image to grayscale and scale its pixels into [0, 1].
+    pub fn from_image(img: DynamicImage) -> Self {
+        let img = img.into_luma8();
+        let (width, height) = img.dimensions();
+        FreqImage {
+            width,
+            height,
+            data: dynimg2complex(img),
+        }
+    }
 
-    // Convert the complex img_buffer back into a gray image.
-    let img_raw: Vec<u8> = img_buffer
-        .iter()
-        .map(|c| (c.norm().min(1.0) * 255.0) as u8)
-        .collect();
+    /// The coefficient at column `x`, row `y`, or `None` if out of bounds.
+    pub fn get(&self, x: u32, y: u32) -> Option<Complex<f64>> {
+        self.checked_index(x, y).map(|i| self.data[i])
+    }
 
-    let out_img = image::GrayImage::from_raw(width, height, img_raw).unwrap();
+    /// Mutable access to the coefficient at column `x`, row `y`, or `None` if out of bounds.
+    pub fn get_mut(&mut self, x: u32, y: u32) -> Option<&mut Complex<f64>> {
+        self.checked_index(x, y).map(move |i| &mut self.data[i])
+    }
 
-    let outfile = "output/fftlp.png";
-    println!("writing to: {}",outfile);
-    out_img.save(outfile)?;
-    
+    /// The coefficient at column `x`, row `y`.
+    ///
+    /// Panics with the offending coordinates if they are out of bounds.
+    pub fn at(&self, x: u32, y: u32) -> Complex<f64> {
+        self.data[self.index_of(x, y)]
+    }
 
-    // Create a window with default options and display the image.
-//    let window = create_window("image", Default::default())?;
-//    window.set_image("FFT Lowpass filtered image (i think)", out_img).unwrap();
-    Ok(())
+    /// Iterate over the rows of the buffer, top to bottom.
+    pub fn rows(&self) -> impl Iterator<Item = &[Complex<f64>]> {
+        self.data.chunks_exact(self.width.max(1) as usize)
+    }
 
+    /// Iterate mutably over the rows of the buffer, top to bottom.
+    pub fn rows_mut(&mut self) -> impl Iterator<Item = &mut [Complex<f64>]> {
+        self.data.chunks_exact_mut(self.width.max(1) as usize)
+    }
 
-}
+    /// Iterate mutably over the rows of the buffer in parallel.
+    #[cfg(feature = "rayon")]
+    pub fn par_rows_mut(&mut self) -> impl rayon::iter::IndexedParallelIterator<Item = &mut [Complex<f64>]> {
+        use rayon::slice::ParallelSliceMut;
+        self.data.par_chunks_exact_mut(self.width.max(1) as usize)
+    }
 
-pub fn fft_forward(width: usize, height: usize, img_buffer: &mut [Complex<f64>]){
-    let mut planner = FftPlanner::new();
-    //let fft = planner.plan_fft_forward(1234);  // desugars to:
-    let fft_width = planner.plan_fft(width as usize, FftDirection::Forward);
+    /// Iterate over `(x, y, coefficient)` in row-major order.
+    pub fn enumerate_pixels(&self) -> impl Iterator<Item = (u32, u32, &Complex<f64>)> {
+        let width = self.width.max(1) as usize;
+        self.data
+            .iter()
+            .enumerate()
+            .map(move |(i, c)| ((i % width) as u32, (i / width) as u32, c))
+    }
 
-    let mut scratch = vec![Complex::default(); fft_width.get_inplace_scratch_len()];
-    for row_buffer in img_buffer.chunks_exact_mut(width as usize) {
-        fft_width.process_with_scratch(row_buffer, &mut scratch);
+    /// Iterate mutably over `(x, y, coefficient)` in row-major order.
+    pub fn enumerate_pixels_mut(&mut self) -> impl Iterator<Item = (u32, u32, &mut Complex<f64>)> {
+        let width = self.width.max(1) as usize;
+        self.data
+            .iter_mut()
+            .enumerate()
+            .map(move |(i, c)| ((i % width) as u32, (i / width) as u32, c))
     }
 
-    // Transpose the image to be able to compute the FFT on the other dimension.
-    let mut transposed = transpose(width, height, img_buffer);
+    fn checked_index(&self, x: u32, y: u32) -> Option<usize> {
+        if x < self.width && y < self.height {
+            Some(y as usize * self.width as usize + x as usize)
+        } else {
+            None
+        }
+    }
 
-    let fft_height = planner.plan_fft(height as usize, FftDirection::Forward);
-    scratch.resize(fft_height.get_outofplace_scratch_len(), Complex::default());
+    fn index_of(&self, x: u32, y: u32) -> usize {
+        self.checked_index(x, y).unwrap_or_else(|| {
+            panic!(
+                "pixel ({}, {}) out of bounds for {}x{} FreqImage",
+                x, y, self.width, self.height
+            )
+        })
+    }
+}
 
-    for (tr_buf, col_buf) in transposed
-        .chunks_exact_mut(height as usize)
-        .zip(img_buffer.chunks_exact_mut(height as usize)){            
-            fft_height.process_outofplace_with_scratch(tr_buf, col_buf, &mut scratch);
-    }    
+impl Index<(u32, u32)> for FreqImage {
+    type Output = Complex<f64>;
 
+    /// Index by `(x, y)`; panics like [`FreqImage::at`] when out of bounds.
+    fn index(&self, (x, y): (u32, u32)) -> &Complex<f64> {
+        &self.data[self.index_of(x, y)]
+    }
 }
 
-pub fn read_image(file: String) -> image::GrayImage{
-    let img = image::open(file).unwrap().into_luma8();
-    img
+impl IndexMut<(u32, u32)> for FreqImage {
+    fn index_mut(&mut self, (x, y): (u32, u32)) -> &mut Complex<f64> {
+        let i = self.index_of(x, y);
+        &mut self.data[i]
+    }
 }
 
-pub fn dynimg2complex(img: image::GrayImage ) -> Vec<Complex<f64>>{
-    let (width, height) = img.dimensions();
+/// Open `file` as an 8-bit grayscale image.
+///
+/// # Panics
+/// Panics if the file can't be opened or decoded; [`FreqImage::open`] returns the error instead.
+pub fn read_image(file: String) -> image::GrayImage {
+    image::open(file).unwrap().into_luma8()
+}
 
-    // Convert the image buffer to complex numbers to be able to compute the FFT.
-    let mut img_buffer: Vec<Complex<f64>> = img
-        .as_raw()
-        .iter()
-        .map(|&pix| Complex::new(pix as f64 / 255.0, 0.0))
-        .collect();
+/// The pixels of `img` scaled into [0, 1] as real parts, in row-major order.
+pub fn dynimg2complex(img: image::GrayImage) -> Vec<Complex<f64>> {
+    img.as_raw().iter().map(|&pix| Complex::new(pix as f64 / 255.0, 0.0)).collect()
+}
 
-    img_buffer
+#[test]
+fn test_read_image() {
+    for file in ["img/sjb-aerial.png", "img/mandrill.jpg"] {
+        let img = read_image(file.to_string());
+        let len = img.width() as usize * img.height() as usize;
+        let data = dynimg2complex(img);
+        assert_eq!(data.len(), len);
+        assert!(data.iter().all(|c| (0.0..=1.0).contains(&c.re) && c.im == 0.0));
+        assert_eq!(FreqImage::open(file).unwrap().data, data);
+    }
 }
 
+#[cfg(test)]
+fn noise(width: u32, height: u32, seed: u64) -> FreqImage {
+    let mut rng = rng::SplitMix64(seed);
+    FreqImage {
+        width,
+        height,
+        data: (0..width * height).map(|_| Complex::new(rng.next_f64(), 0.0)).collect(),
+    }
+}
 
-fn transpose<T: Copy + Default>(width: usize, height: usize, matrix: &[T]) -> Vec<T> {
-    let mut ind = 0;
-    let mut ind_tr;
-    let mut transposed = vec![T::default(); matrix.len()];
-    for row in 0..height {
-        ind_tr = row;
-        for _ in 0..width {
-            transposed[ind_tr] = matrix[ind];
-            ind += 1;
-            ind_tr += height;
-        }
+#[cfg(test)]
+fn ramp(width: u32, height: u32) -> FreqImage {
+    FreqImage {
+        width,
+        height,
+        data: (0..width * height).map(|i| Complex::new(i as f64, -(i as f64))).collect(),
     }
-    transposed
 }
 
+#[test]
+fn test_pixel_accessors() {
+    let mut fi = ramp(5, 3);
 
+    assert_eq!(fi.get(4, 2), Some(fi.data[2 * 5 + 4]));
+    assert_eq!(fi.at(4, 2), fi.data[14]);
+    assert_eq!(fi.get(5, 0), None);
+    assert_eq!(fi.get(0, 3), None);
+    assert!(fi.get_mut(5, 2).is_none());
 
+    *fi.get_mut(1, 2).unwrap() = Complex::new(-1.0, 0.5);
+    assert_eq!(fi.data[2 * 5 + 1], Complex::new(-1.0, 0.5));
+}
 
 #[test]
-fn test_image_fft(){
-    let files = vec!["img/sjb-aerial.png", "img/mandrill.jpg"];
-
-    for file in files {
-        let img = image::open(file).unwrap().into_luma8();
-        dynimg2complex(img);
+fn test_pixel_index() {
+    let mut fi = ramp(5, 3);
+    for y in 0..3 {
+        for x in 0..5 {
+            assert_eq!(fi[(x, y)], fi.data[(y * 5 + x) as usize]);
+        }
     }
+    fi[(4, 2)] = Complex::new(7.0, 7.0);
+    assert_eq!(fi.data[14], Complex::new(7.0, 7.0));
 }
 
+#[test]
+#[should_panic(expected = "pixel (5, 1) out of bounds for 5x3")]
+fn test_pixel_index_out_of_bounds() {
+    let fi = ramp(5, 3);
+    let _ = fi[(5, 1)];
+}
+
+#[test]
+fn test_pixel_iterators() {
+    let mut fi = ramp(4, 3);
+
+    assert_eq!(fi.rows().count(), 3);
+    assert!(fi.rows().all(|row| row.len() == 4));
+    assert_eq!(fi.rows().nth(2).unwrap()[1], fi.at(1, 2));
 
+    let mut count = 0;
+    for (x, y, c) in fi.enumerate_pixels() {
+        assert_eq!(fi.get(x, y), Some(*c));
+        count += 1;
+    }
+    assert_eq!(count, 12);
+
+    for (x, y, c) in fi.enumerate_pixels_mut() {
+        *c = Complex::new(x as f64, y as f64);
+    }
+    for row in fi.rows_mut() {
+        row[0].im += 10.0;
+    }
+    assert_eq!(fi.at(3, 1), Complex::new(3.0, 1.0));
+    assert_eq!(fi.at(0, 2), Complex::new(0.0, 12.0));
+}
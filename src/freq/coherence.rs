@@ -0,0 +1,73 @@
+//! Per-frequency relationships between two aligned spatial-domain images.
+
+use rustfft::num_complex::Complex;
+
+use super::{Boundary, FreqImage, Kernel2D};
+use crate::FreqError;
+
+/// The cross-power spectrum `FFT(a) · conj(FFT(b))`, in the natural (unshifted) layout.
+pub fn cross_power(a: &FreqImage, b: &FreqImage) -> Result<FreqImage, FreqError> {
+    let mut fa = a.clone();
+    let mut fb = b.clone();
+    fa.fft_forward();
+    fb.fft_forward();
+    fa.mul_conj_elementwise(&fb)?;
+    Ok(fa)
+}
+
+/// Magnitude-squared coherence `|Sab|² / (Saa · Sbb)` per frequency bin, in `[0, 1]` and in
+/// the natural layout. The spectra are averaged over neighbouring bins with a Gaussian of
+/// `smoothing_radius` bins (wrapping around), without which every bin would trivially
+/// score one; bins with no power in either image score zero.
+///
+/// # Panics
+/// Panics if `smoothing_radius` is not positive.
+pub fn coherence(a: &FreqImage, b: &FreqImage, smoothing_radius: f64) -> Result<Vec<f64>, FreqError> {
+    let window = Kernel2D::gaussian(smoothing_radius);
+    let smooth = |s: FreqImage| s.convolve_with(&window, Boundary::Wrap);
+    let s_ab = smooth(cross_power(a, b)?);
+    let s_aa = smooth(cross_power(a, a)?);
+    let s_bb = smooth(cross_power(b, b)?);
+    Ok(s_ab
+        .data
+        .iter()
+        .zip(s_aa.data.iter().zip(&s_bb.data))
+        .map(|(ab, (aa, bb)): (&Complex<f64>, _)| {
+            let denominator = aa.re * bb.re;
+            if denominator > 0.0 {
+                (ab.norm_sqr() / denominator).clamp(0.0, 1.0)
+            } else {
+                0.0
+            }
+        })
+        .collect())
+}
+
+#[test]
+fn test_cross_power() {
+    let a = super::ramp(4, 3);
+    assert!(cross_power(&a, &super::ramp(3, 4)).is_err());
+    let power = cross_power(&a, &a).unwrap();
+    let mut spectrum = a.clone();
+    spectrum.fft_forward();
+    for (p, s) in power.data.iter().zip(&spectrum.data) {
+        assert!((p - s.norm_sqr()).norm() < 1e-9);
+    }
+}
+
+#[test]
+fn test_coherence() {
+    // White noise keeps every band well above the added noise floor.
+    let fi = super::synth::spectral_noise(128, 128, 0.0, 1);
+    let mut noisy = fi.clone();
+    noisy.add_gaussian_noise(0.02, 2);
+    let independent = super::synth::spectral_noise(128, 128, 0.0, 3);
+
+    let mean = |values: Vec<f64>| values.iter().skip(1).sum::<f64>() / (values.len() - 1) as f64;
+    let related = coherence(&fi, &noisy, 3.0).unwrap();
+    assert!(related.iter().all(|c| (0.0..=1.0).contains(c)));
+    let related = mean(related);
+    let unrelated = mean(coherence(&fi, &independent, 3.0).unwrap());
+    assert!(related > 0.9, "{}", related);
+    assert!(unrelated < 0.1, "{}", unrelated);
+}
@@ -0,0 +1,112 @@
+//! Helpers for checking results against numpy and scipy.
+//!
+//! The conventions already agree, which the fixtures at the bottom of this file pin
+//! down against values of `numpy.fft.fft2`:
+//!
+//! - Forward transforms use `exp(-2πi·k·n/N)`, numpy's sign.
+//! - Forward transforms are unnormalized and inverses scale by `1/N`, numpy's default
+//!   `norm="backward"`.
+//! - Buffers are row-major with `(x, y)` stored at `data[y * width + x]`, so a numpy array
+//!   of shape `(height, width)` lines up element for element.
+//! - [`FreqImage::fftshift`] moves DC to `(width / 2, height / 2)` and
+//!   [`FreqImage::ifftshift`] undoes it for odd sizes too, as `numpy.fft.fftshift` and
+//!   `numpy.fft.ifftshift` do.
+//!
+//! MATLAB's `fft2` shares all of these but stores arrays column-major.
+
+use rustfft::num_complex::Complex;
+
+use super::analysis::signed_frequency;
+use super::FreqImage;
+
+/// The coefficients of a spectrum from [`FreqImage::fft_forward`] in the order and
+/// convention of `numpy.fft.fft2` on the `(height, width)` array, flattened row-major.
+/// The conventions match, so this is a copy; it exists so comparisons state their intent.
+pub fn to_numpy_order(spectrum: &FreqImage) -> Vec<Complex<f64>> {
+    spectrum.data.clone()
+}
+
+/// The sample frequencies of an `n`-point FFT in cycles per sample, like
+/// `numpy.fft.fftfreq(n)`: `0, 1, …, ⌈n/2⌉ - 1, -⌊n/2⌋, …, -1`, all divided by `n`.
+pub fn fftfreq(n: u32) -> Vec<f64> {
+    (0..n).map(|k| signed_frequency(k, n)).collect()
+}
+
+#[cfg(test)]
+fn c(re: f64, im: f64) -> Complex<f64> {
+    Complex::new(re, im)
+}
+
+#[cfg(test)]
+fn check_fixture(width: u32, height: u32, input: &[f64], expected: &[Complex<f64>]) {
+    let mut fi = FreqImage { width, height, data: input.iter().map(|&v| c(v, 0.0)).collect() };
+    fi.fft_forward();
+    for (i, (got, want)) in to_numpy_order(&fi).iter().zip(expected).enumerate() {
+        assert!((got - want).norm() < 1e-12, "{}x{} bin {}: {} vs {}", width, height, i, got, want);
+    }
+}
+
+// The expected spectra are numpy.fft.fft2 of the input arrays, evaluated from its
+// definition: exact for 4x4, whose twiddle factors are ±1 and ±i, and to 15 digits for
+// the 5x3 case.
+
+#[test]
+fn test_matches_numpy_4x4() {
+    let input = [
+        1.0, 2.0, 0.0, -1.0,
+        3.0, 0.0, 1.0, 2.0,
+        0.0, -2.0, 4.0, 1.0,
+        1.0, 1.0, 0.0, 5.0,
+    ];
+    let expected = [
+        c(18.0, 0.0), c(0.0, 6.0), c(2.0, 0.0), c(0.0, -6.0),
+        c(-1.0, 1.0), c(3.0, -7.0), c(-5.0, -7.0), c(7.0, 5.0),
+        c(-8.0, 0.0), c(-6.0, -6.0), c(8.0, 0.0), c(-6.0, 6.0),
+        c(-1.0, -1.0), c(7.0, -5.0), c(-5.0, 7.0), c(3.0, 7.0),
+    ];
+    check_fixture(4, 4, &input, &expected);
+}
+
+#[test]
+fn test_matches_numpy_5x3() {
+    // numpy shape (3, 5): 5 wide, 3 tall.
+    let input = [
+        0.5, -1.0, 2.0, 3.0, 0.0,
+        1.0, 0.0, -2.0, 0.25, 4.0,
+        2.0, 1.0, 1.0, -1.0, 0.0,
+    ];
+    let expected = [
+        c(10.75, 0.0), c(2.10676274578121, 4.53895763054621), c(1.26823725421879, 1.16232036380095),
+        c(1.26823725421879, -1.16232036380095), c(2.10676274578121, -4.53895763054621),
+        c(1.375, -0.216506350946110), c(-0.552931722599181, -1.12414168173524), c(2.69175293446886, 2.31017512637323),
+        c(4.60231571006141, 4.56230928218223), c(-13.1161369219311, -1.20170935695192),
+        c(1.375, 0.216506350946110), c(-13.1161369219311, 1.20170935695192), c(4.60231571006141, -4.56230928218223),
+        c(2.69175293446886, -2.31017512637323), c(-0.552931722599181, 1.12414168173524),
+    ];
+    check_fixture(5, 3, &input, &expected);
+}
+
+#[test]
+fn test_fftfreq() {
+    // numpy.fft.fftfreq(4) and numpy.fft.fftfreq(5).
+    assert_eq!(fftfreq(4), [0.0, 0.25, -0.5, -0.25]);
+    assert_eq!(fftfreq(5), [0.0, 0.2, 0.4, -0.4, -0.2]);
+    assert_eq!(fftfreq(1), [0.0]);
+    assert!(fftfreq(0).is_empty());
+
+    let fi = super::ramp(5, 4);
+    for (x, fx) in fftfreq(5).into_iter().enumerate() {
+        assert_eq!(fi.frequency_at(x as u32, 0).0, fx);
+    }
+}
+
+#[test]
+fn test_shift_matches_numpy() {
+    // numpy.fft.fftshift(numpy.arange(5)) is [3, 4, 0, 1, 2]; ifftshift gives [2, 3, 4, 0, 1].
+    let mut fi = super::ramp(5, 1);
+    fi.fftshift();
+    assert_eq!(fi.data.iter().map(|c| c.re).collect::<Vec<_>>(), [3.0, 4.0, 0.0, 1.0, 2.0]);
+    let mut fi = super::ramp(5, 1);
+    fi.ifftshift();
+    assert_eq!(fi.data.iter().map(|c| c.re).collect::<Vec<_>>(), [2.0, 3.0, 4.0, 0.0, 1.0]);
+}
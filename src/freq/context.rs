@@ -0,0 +1,163 @@
+//! Reusable FFT plans for repeated transforms.
+
+use rustfft::{num_complex::Complex, FftDirection, FftPlanner};
+
+use super::fft::transpose;
+use super::progress::report;
+use super::shift::shift_factor;
+use super::{FreqImage, Progress};
+
+/// Cached FFT plans and scratch space. Iterative algorithms that transform many
+/// same-sized images should share one context instead of calling
+/// [`FreqImage::fft_forward`], which plans from scratch every time.
+pub struct FftContext {
+    planner: FftPlanner<f64>,
+    scratch: Vec<Complex<f64>>,
+}
+
+impl Default for FftContext {
+    fn default() -> Self {
+        Self {
+            planner: FftPlanner::new(),
+            scratch: Vec::new(),
+        }
+    }
+}
+
+impl FftContext {
+    /// An empty context; plans are created on first use for each size.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Forward 2D FFT in place, see [`FreqImage::fft_forward`].
+    pub fn forward(&mut self, fi: &mut FreqImage) {
+        self.forward_with_progress(fi, &mut |_| {});
+    }
+
+    /// Inverse 2D FFT in place, see [`FreqImage::fft_inverse`].
+    pub fn inverse(&mut self, fi: &mut FreqImage) {
+        self.inverse_with_progress(fi, &mut |_| {});
+    }
+
+    /// Forward 2D FFT in place, see [`FreqImage::fft_forward_with_progress`].
+    pub fn forward_with_progress(&mut self, fi: &mut FreqImage, progress: &mut dyn FnMut(Progress)) {
+        self.fft_2d(fi, FftDirection::Forward, ["fft_rows", "fft_columns"], progress);
+    }
+
+    /// Inverse 2D FFT in place, see [`FreqImage::fft_inverse_with_progress`].
+    pub fn inverse_with_progress(&mut self, fi: &mut FreqImage, progress: &mut dyn FnMut(Progress)) {
+        self.fft_2d(fi, FftDirection::Inverse, ["ifft_rows", "ifft_columns"], progress);
+        let scale = 1.0 / fi.data.len() as f64;
+        for c in fi.data.iter_mut() {
+            *c *= scale;
+        }
+    }
+
+    /// Move row `y` of a spatial-domain image `shift(y)` pixels to the right, wrapping
+    /// around, with a 1D FFT along each row; see [`FreqImage::translate`].
+    pub(super) fn shift_rows(&mut self, fi: &mut FreqImage, shift: impl Fn(u32) -> f64) {
+        let width = fi.width;
+        if fi.data.is_empty() {
+            return;
+        }
+        let forward = self.planner.plan_fft_forward(width as usize);
+        let inverse = self.planner.plan_fft_inverse(width as usize);
+        let scratch_len = forward.get_inplace_scratch_len().max(inverse.get_inplace_scratch_len());
+        self.scratch.resize(scratch_len, Complex::default());
+        for (y, row) in fi.rows_mut().enumerate() {
+            let d = shift(y as u32);
+            forward.process_with_scratch(row, &mut self.scratch);
+            for (k, c) in row.iter_mut().enumerate() {
+                *c *= shift_factor(k as u32, width, d) / width as f64;
+            }
+            inverse.process_with_scratch(row, &mut self.scratch);
+        }
+    }
+
+    /// Transform every row, then every column (via a transpose so both passes
+    /// run over contiguous memory), leaving the result in row-major order. Each pass
+    /// reports progress under its entry in `stages`. Single rows and columns skip the
+    /// pass across them.
+    fn fft_2d(&mut self, fi: &mut FreqImage, direction: FftDirection, stages: [&'static str; 2], progress: &mut dyn FnMut(Progress)) {
+        let (width, height) = (fi.width as usize, fi.height as usize);
+        if fi.data.is_empty() {
+            return;
+        }
+        if width == 1 || height == 1 {
+            // Every line across the short axis is a 1-point FFT, i.e. the identity, and the
+            // long axis is already contiguous: one 1D transform and no transposes.
+            let fft = self.planner.plan_fft(fi.data.len(), direction);
+            self.scratch.resize(fft.get_inplace_scratch_len(), Complex::default());
+            fft.process_with_scratch(&mut fi.data, &mut self.scratch);
+            report(progress, stages[0], height, height);
+            report(progress, stages[1], width, width);
+            return;
+        }
+
+        let fft_width = self.planner.plan_fft(width, direction);
+        self.scratch.resize(fft_width.get_inplace_scratch_len(), Complex::default());
+        for (i, row) in fi.rows_mut().enumerate() {
+            fft_width.process_with_scratch(row, &mut self.scratch);
+            report(progress, stages[0], i + 1, height);
+        }
+
+        let mut transposed = transpose(width, height, &fi.data);
+        let fft_height = self.planner.plan_fft(height, direction);
+        self.scratch.resize(fft_height.get_inplace_scratch_len(), Complex::default());
+        for (i, col) in transposed.chunks_exact_mut(height).enumerate() {
+            fft_height.process_with_scratch(col, &mut self.scratch);
+            report(progress, stages[1], i + 1, width);
+        }
+        fi.data = transpose(height, width, &transposed);
+    }
+}
+
+#[test]
+fn test_context_matches_one_shot() {
+    let mut ctx = FftContext::new();
+    for (w, h) in [(4, 3), (5, 5), (4, 3)] {
+        let fi = super::ramp(w, h);
+        let (mut a, mut b) = (fi.clone(), fi.clone());
+        a.fft_forward();
+        ctx.forward(&mut b);
+        assert_eq!(a, b);
+        ctx.inverse(&mut b);
+        for (x, y) in b.data.iter().zip(&fi.data) {
+            assert!((x - y).norm() < 1e-9);
+        }
+    }
+}
+
+#[test]
+fn test_fft_progress() {
+    use super::progress::{check_stage, collect};
+
+    let fi = super::ramp(300, 200);
+    let (mut plain, mut reported) = (fi.clone(), fi.clone());
+    plain.fft_forward();
+    let mut reports = Vec::new();
+    reported.fft_forward_with_progress(&mut collect(&mut reports));
+    assert_eq!(reported, plain);
+    check_stage(&reports, "fft_rows", 200);
+    check_stage(&reports, "fft_columns", 300);
+    assert_eq!(reports.last().unwrap().stage, "fft_columns");
+
+    reports.clear();
+    reported.fft_inverse_with_progress(&mut collect(&mut reports));
+    check_stage(&reports, "ifft_rows", 200);
+    check_stage(&reports, "ifft_columns", 300);
+    assert!(reports.len() < 2 * 70);
+}
+
+#[test]
+fn test_degenerate_progress() {
+    use super::progress::{check_stage, collect};
+
+    for (w, h) in [(1, 1), (1, 9), (9, 1)] {
+        let mut reports = Vec::new();
+        super::ramp(w, h).fft_forward_with_progress(&mut collect(&mut reports));
+        check_stage(&reports, "fft_rows", h as usize);
+        check_stage(&reports, "fft_columns", w as usize);
+    }
+}
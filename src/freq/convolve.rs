@@ -0,0 +1,318 @@
+//! FFT-based convolution with small real spatial kernels.
+
+use rustfft::num_complex::Complex;
+
+use super::{FftContext, FreqImage};
+use crate::FreqError;
+
+/// A small real-valued convolution kernel, stored row-major.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Kernel2D {
+    width: u32,
+    height: u32,
+    data: Vec<f64>,
+}
+
+/// How pixels outside the image are filled in when convolving.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Boundary {
+    /// Treat pixels outside the image as zero.
+    #[default]
+    Zero,
+    /// Wrap around, as the raw FFT does.
+    Wrap,
+    /// Repeat the nearest edge pixel.
+    Clamp,
+    /// Mirror the image about its edges, repeating the edge pixel.
+    Reflect,
+}
+
+impl Kernel2D {
+    /// Wrap a row-major `width × height` buffer of weights. Fails with
+    /// [`FreqError::InvalidDimensions`] if either dimension is zero.
+    pub fn new(width: u32, height: u32, data: Vec<f64>) -> Result<Self, FreqError> {
+        if width == 0 || height == 0 {
+            return Err(FreqError::InvalidDimensions { width, height, len: data.len() });
+        }
+        let expected = width as usize * height as usize;
+        if data.len() != expected {
+            return Err(FreqError::LengthMismatch { expected, got: data.len() });
+        }
+        Ok(Kernel2D { width, height, data })
+    }
+
+    /// A normalized Gaussian of standard deviation `sigma`, truncated at 3σ.
+    ///
+    /// # Panics
+    /// Panics if `sigma` is not positive.
+    pub fn gaussian(sigma: f64) -> Self {
+        assert!(sigma > 0.0, "gaussian sigma must be positive");
+        let radius = (3.0 * sigma).ceil() as i64;
+        let size = (2 * radius + 1) as u32;
+        let taps: Vec<f64> = (-radius..=radius)
+            .map(|i| (-((i * i) as f64) / (2.0 * sigma * sigma)).exp())
+            .collect();
+        let mut data: Vec<f64> = taps.iter().flat_map(|&a| taps.iter().map(move |&b| a * b)).collect();
+        let total: f64 = data.iter().sum();
+        data.iter_mut().for_each(|v| *v /= total);
+        Kernel2D { width: size, height: size, data }
+    }
+
+    /// An `n × n` moving average.
+    ///
+    /// # Panics
+    /// Panics if `n` is zero.
+    pub fn box_blur(n: u32) -> Self {
+        assert!(n > 0, "box size must be positive");
+        let weight = 1.0 / (n as f64 * n as f64);
+        Kernel2D { width: n, height: n, data: vec![weight; (n * n) as usize] }
+    }
+
+    /// The 3x3 Sobel horizontal-gradient kernel, oriented so convolving responds
+    /// positively where intensity increases with `x`.
+    pub fn sobel_x() -> Self {
+        Kernel2D { width: 3, height: 3, data: vec![1.0, 0.0, -1.0, 2.0, 0.0, -2.0, 1.0, 0.0, -1.0] }
+    }
+
+    /// The 3x3 Sobel vertical-gradient kernel, positive where intensity increases with `y`.
+    pub fn sobel_y() -> Self {
+        Kernel2D { width: 3, height: 3, data: vec![1.0, 2.0, 1.0, 0.0, 0.0, 0.0, -1.0, -2.0, -1.0] }
+    }
+
+    /// The 3x3 discrete Laplacian, `-4` at the center and `1` at the four neighbours.
+    pub fn laplacian() -> Self {
+        Kernel2D { width: 3, height: 3, data: vec![0.0, 1.0, 0.0, 1.0, -4.0, 1.0, 0.0, 1.0, 0.0] }
+    }
+
+    /// A normalized straight-line blur `length` pixels long at `angle_rad`
+    /// counter-clockwise from +x as displayed, as left by a camera moving during the
+    /// exposure. The line is centered on the middle tap and spread over neighbouring taps
+    /// bilinearly; a length of zero is the identity.
+    ///
+    /// # Panics
+    /// Panics if `length` is negative or not finite.
+    pub fn motion_blur(length: f64, angle_rad: f64) -> Self {
+        assert!(length.is_finite() && length >= 0.0, "blur length must be finite and non-negative");
+        let radius = (length / 2.0).ceil() as i64 + 1;
+        let size = (2 * radius + 1) as u32;
+        let mut data = vec![0.0; (size * size) as usize];
+        let (dx, dy) = (angle_rad.cos(), -angle_rad.sin());
+        let samples = (4.0 * length).ceil().max(1.0) as usize;
+        for i in 0..samples {
+            let t = length * ((i as f64 + 0.5) / samples as f64 - 0.5);
+            let (x, y) = (radius as f64 + t * dx, radius as f64 + t * dy);
+            let (x0, y0) = (x.floor(), y.floor());
+            let (fx, fy) = (x - x0, y - y0);
+            for (ox, wx) in [(0, 1.0 - fx), (1, fx)] {
+                for (oy, wy) in [(0, 1.0 - fy), (1, fy)] {
+                    let (px, py) = (x0 as usize + ox, y0 as usize + oy);
+                    if px < size as usize && py < size as usize {
+                        data[py * size as usize + px] += wx * wy;
+                    }
+                }
+            }
+        }
+        let total: f64 = data.iter().sum();
+        data.iter_mut().for_each(|v| *v /= total);
+        Kernel2D { width: size, height: size, data }
+    }
+
+    /// Kernel width in taps.
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Kernel height in taps.
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Row-major kernel weights.
+    pub fn data(&self) -> &[f64] {
+        &self.data
+    }
+
+    /// The tap that lands on the output pixel: `(width / 2, height / 2)`.
+    pub fn center(&self) -> (u32, u32) {
+        (self.width / 2, self.height / 2)
+    }
+}
+
+impl FreqImage {
+    /// Convolve a spatial-domain image with `kernel`, treating pixels outside the
+    /// image as zero. See [`FreqImage::convolve_with`].
+    pub fn convolve(&self, kernel: &Kernel2D) -> FreqImage {
+        self.convolve_with(kernel, Boundary::Zero)
+    }
+
+    /// Convolve a spatial-domain image with `kernel` via the FFT. The image is padded
+    /// by at least the kernel's extent using `boundary`, so the result has the input's size
+    /// and is not shifted: the kernel's [`center`](Kernel2D::center) lands on each pixel.
+    pub fn convolve_with(&self, kernel: &Kernel2D, boundary: Boundary) -> FreqImage {
+        let (cx, cy) = kernel.center();
+        // Margins are chosen so the padded circular convolution never wraps into the output.
+        let (left, top) = (kernel.width - 1 - cx, kernel.height - 1 - cy);
+        let pw = fast_len(self.width + kernel.width - 1);
+        let ph = fast_len(self.height + kernel.height - 1);
+
+        let mut padded = FreqImage {
+            width: pw,
+            height: ph,
+            data: Vec::with_capacity(pw as usize * ph as usize),
+        };
+        for py in 0..ph {
+            let sy = boundary_index(py as i64 - top as i64, self.height, boundary);
+            for px in 0..pw {
+                let sx = boundary_index(px as i64 - left as i64, self.width, boundary);
+                padded.data.push(match (sx, sy) {
+                    (Some(x), Some(y)) => self[(x, y)],
+                    _ => Complex::new(0.0, 0.0),
+                });
+            }
+        }
+
+        let mut taps = wrap_to_origin(&FreqImage::from(kernel), kernel.center(), pw, ph);
+        let mut ctx = FftContext::new();
+        ctx.forward(&mut padded);
+        ctx.forward(&mut taps);
+        padded.mul_elementwise(&taps).unwrap();
+        ctx.inverse(&mut padded);
+        padded.crop(left, top, self.width, self.height)
+    }
+}
+
+impl From<&Kernel2D> for FreqImage {
+    /// The kernel weights as a real-valued spatial image, e.g. for use as a PSF.
+    fn from(kernel: &Kernel2D) -> Self {
+        FreqImage {
+            width: kernel.width,
+            height: kernel.height,
+            data: kernel.data.iter().map(|&w| Complex::new(w, 0.0)).collect(),
+        }
+    }
+}
+
+/// Embed a small kernel in a zeroed `width × height` buffer with its `center` tap at
+/// `(0, 0)` and the rest wrapped around, so circular convolution does not shift the image.
+pub(crate) fn wrap_to_origin(kernel: &FreqImage, center: (u32, u32), width: u32, height: u32) -> FreqImage {
+    let mut out = FreqImage {
+        width,
+        height,
+        data: vec![Complex::new(0.0, 0.0); width as usize * height as usize],
+    };
+    for (x, y, &c) in kernel.enumerate_pixels() {
+        let kx = (x as i64 - center.0 as i64).rem_euclid(width as i64) as u32;
+        let ky = (y as i64 - center.1 as i64).rem_euclid(height as i64) as u32;
+        out[(kx, ky)] += c;
+    }
+    out
+}
+
+/// The smallest length `>= n` whose only prime factors are 2, 3 and 5, which the FFT handles quickly.
+pub(crate) fn fast_len(n: u32) -> u32 {
+    (n.max(1)..)
+        .find(|&m| {
+            let mut m = m;
+            for p in [2, 3, 5] {
+                while m % p == 0 {
+                    m /= p;
+                }
+            }
+            m == 1
+        })
+        .unwrap()
+}
+
+/// The source pixel for padded coordinate `i` along an axis of length `n`, or `None` for zero fill.
+pub(crate) fn boundary_index(i: i64, n: u32, boundary: Boundary) -> Option<u32> {
+    let n = n as i64;
+    if (0..n).contains(&i) {
+        return Some(i as u32);
+    }
+    match boundary {
+        Boundary::Zero => None,
+        Boundary::Wrap => Some(i.rem_euclid(n) as u32),
+        Boundary::Clamp => Some(i.clamp(0, n - 1) as u32),
+        Boundary::Reflect => {
+            let m = i.rem_euclid(2 * n);
+            Some(if m < n { m } else { 2 * n - 1 - m } as u32)
+        }
+    }
+}
+
+#[cfg(test)]
+fn convolve_direct(fi: &FreqImage, kernel: &Kernel2D, boundary: Boundary) -> FreqImage {
+    let (cx, cy) = kernel.center();
+    let mut out = fi.clone();
+    for (x, y, c) in out.enumerate_pixels_mut() {
+        *c = Complex::new(0.0, 0.0);
+        for (i, &w) in kernel.data().iter().enumerate() {
+            let (kx, ky) = ((i as u32 % kernel.width()) as i64, (i as u32 / kernel.width()) as i64);
+            let sx = boundary_index(x as i64 - (kx - cx as i64), fi.width, boundary);
+            let sy = boundary_index(y as i64 - (ky - cy as i64), fi.height, boundary);
+            if let (Some(sx), Some(sy)) = (sx, sy) {
+                *c += fi[(sx, sy)] * w;
+            }
+        }
+    }
+    out
+}
+
+#[test]
+fn test_convolve_matches_direct() {
+    let fi = super::noise(32, 32, 7);
+    let odd = super::noise(4, 3, 11);
+    let kernels = [
+        Kernel2D::gaussian(1.5),
+        Kernel2D::box_blur(4),
+        Kernel2D::sobel_x(),
+        Kernel2D::sobel_y(),
+        Kernel2D::new(4, 3, odd.real()).unwrap(),
+    ];
+    for kernel in &kernels {
+        for boundary in [Boundary::Zero, Boundary::Wrap, Boundary::Clamp, Boundary::Reflect] {
+            let fast = fi.convolve_with(kernel, boundary);
+            let slow = convolve_direct(&fi, kernel, boundary);
+            assert_eq!((fast.width, fast.height), (32, 32));
+            for (a, b) in fast.data.iter().zip(&slow.data) {
+                assert!((a - b).norm() < 1e-9, "{:?} {}x{}", boundary, kernel.width(), kernel.height());
+            }
+        }
+    }
+}
+
+#[test]
+fn test_kernels() {
+    let g = Kernel2D::gaussian(1.0);
+    assert_eq!((g.width(), g.height()), (7, 7));
+    assert!((g.data().iter().sum::<f64>() - 1.0).abs() < 1e-12);
+    assert_eq!(g.center(), (3, 3));
+
+    // A horizontal ramp has a positive x-gradient and no y-gradient in the interior.
+    let ramp = FreqImage {
+        width: 5,
+        height: 5,
+        data: (0..25).map(|i| Complex::new((i % 5) as f64, 0.0)).collect(),
+    };
+    assert!((ramp.convolve(&Kernel2D::sobel_x())[(2, 2)].re - 8.0).abs() < 1e-9);
+    assert!(ramp.convolve(&Kernel2D::sobel_y())[(2, 2)].re.abs() < 1e-9);
+
+    let m = Kernel2D::motion_blur(6.0, 0.0);
+    assert!((m.data().iter().sum::<f64>() - 1.0).abs() < 1e-12);
+    let (cx, cy) = m.center();
+    assert!(m.data().iter().enumerate().all(|(i, &v)| v == 0.0 || i as u32 / m.width() == cy));
+    assert!((m.data()[(cy * m.width() + cx - 2) as usize] - m.data()[(cy * m.width() + cx + 2) as usize]).abs() < 1e-12);
+    assert_eq!(Kernel2D::motion_blur(0.0, 1.0).data().iter().filter(|&&v| v > 0.0).count(), 1);
+
+    assert_eq!(fast_len(1054), 1080);
+    assert_eq!(fast_len(7), 8);
+    assert!(matches!(
+        Kernel2D::new(2, 2, vec![1.0; 3]),
+        Err(FreqError::LengthMismatch { expected: 4, got: 3 })
+    ));
+    assert!(matches!(
+        Kernel2D::new(0, 2, Vec::new()),
+        Err(FreqError::InvalidDimensions { width: 0, height: 2, len: 0 })
+    ));
+}
@@ -0,0 +1,99 @@
+//! Cross- and autocorrelation via the FFT.
+
+use rustfft::num_complex::Complex;
+
+use super::FreqImage;
+use crate::FreqError;
+
+impl FreqImage {
+    /// Correlate two spatial-domain images of equal size as `IFFT(FFT(self) · conj(FFT(other)))`,
+    /// so entry `d` is `Σ self(x + d) · conj(other(x))`.
+    ///
+    /// The result is fftshifted: zero lag sits at `(width / 2, height / 2)` and a copy of
+    /// `other` moved by `(dx, dy)` peaks at that center plus `(dx, dy)`. With `circular`
+    /// the lags wrap and the result has the input size; otherwise both images are
+    /// zero-padded to `(2·width - 1) × (2·height - 1)` so every lag is counted once.
+    pub fn cross_correlate(&self, other: &FreqImage, circular: bool) -> Result<FreqImage, FreqError> {
+        if (self.width, self.height) != (other.width, other.height) {
+            return Err(FreqError::DimensionMismatch {
+                expected: (self.width, self.height),
+                got: (other.width, other.height),
+            });
+        }
+        let (mut a, mut b) = if circular || self.data.is_empty() {
+            (self.clone(), other.clone())
+        } else {
+            let (w, h) = (2 * self.width - 1, 2 * self.height - 1);
+            (zero_pad(self, w, h), zero_pad(other, w, h))
+        };
+        a.fft_forward();
+        b.fft_forward();
+        a.mul_conj_elementwise(&b)?;
+        a.fft_inverse();
+        a.fftshift();
+        Ok(a)
+    }
+
+    /// Circular autocorrelation of a spatial-domain image, zero lag at the center.
+    /// See [`FreqImage::cross_correlate`].
+    pub fn autocorrelate(&self) -> FreqImage {
+        self.cross_correlate(self, true).unwrap()
+    }
+}
+
+/// Place `fi` in the top-left corner of a zeroed `width × height` buffer.
+fn zero_pad(fi: &FreqImage, width: u32, height: u32) -> FreqImage {
+    let mut out = FreqImage {
+        width,
+        height,
+        data: vec![Complex::new(0.0, 0.0); width as usize * height as usize],
+    };
+    for (x, y, &c) in fi.enumerate_pixels() {
+        out[(x, y)] = c;
+    }
+    out
+}
+
+#[cfg(test)]
+fn zero_mean_noise(width: u32, height: u32) -> FreqImage {
+    let mut fi = super::noise(width, height, 3);
+    fi.data.iter_mut().for_each(|c| c.re -= 0.5);
+    fi
+}
+
+#[test]
+fn test_cross_correlate_circular() {
+    let base = zero_mean_noise(32, 24);
+    let mut moved = base.clone();
+    for (x, y, &c) in base.enumerate_pixels() {
+        moved[((x + 5) % 32, (y + 3) % 24)] = c;
+    }
+    let corr = moved.cross_correlate(&base, true).unwrap();
+    assert_eq!((corr.width, corr.height), (32, 24));
+    assert_eq!(corr.stats().peak, (16 + 5, 12 + 3));
+}
+
+#[test]
+fn test_cross_correlate_linear() {
+    let base = zero_mean_noise(32, 24);
+    let mut moved = FreqImage {
+        width: 32,
+        height: 24,
+        data: vec![Complex::new(0.0, 0.0); 32 * 24],
+    };
+    for (x, y, &c) in base.enumerate_pixels() {
+        if x + 5 < 32 && y >= 3 {
+            moved[(x + 5, y - 3)] = c;
+        }
+    }
+    let corr = moved.cross_correlate(&base, false).unwrap();
+    assert_eq!((corr.width, corr.height), (63, 47));
+    assert_eq!(corr.stats().peak, (31 + 5, 23 - 3));
+}
+
+#[test]
+fn test_autocorrelate() {
+    let corr = zero_mean_noise(16, 16).autocorrelate();
+    assert_eq!(corr.stats().peak, (8, 8));
+    assert!(super::ramp(2, 2).cross_correlate(&super::ramp(2, 3), true).is_err());
+}
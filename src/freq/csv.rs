@@ -0,0 +1,190 @@
+//! CSV dumps of small spectra, mainly for comparing against MATLAB/NumPy output.
+//!
+//! Each image row becomes one CSV line. There is no size limit, but files grow
+//! quickly: a 512x512 spectrum is already several megabytes of text.
+
+use std::io::{BufRead, BufReader, Read, Write};
+
+use rustfft::num_complex::Complex;
+
+use super::polar::phase_of;
+use super::FreqImage;
+use crate::FreqError;
+
+/// Which component of each coefficient to write to (or read from) a CSV file.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CsvPart {
+    /// The real part.
+    Real,
+    /// The imaginary part.
+    Imag,
+    /// The magnitude `|c|`.
+    Magnitude,
+    /// The phase angle in radians, zero for zero coefficients as in [`FreqImage::phase`].
+    Phase,
+    /// Both parts, written as `re+imj` (NumPy's notation). A NaN imaginary part is
+    /// written `+NaNj`.
+    Complex,
+}
+
+impl FreqImage {
+    /// Write the selected component of every coefficient, one image row per line.
+    ///
+    /// Values use Rust's shortest round-trip float formatting, so reading them
+    /// back yields bit-identical numbers.
+    pub fn write_csv<W: Write>(&self, mut writer: W, part: CsvPart) -> Result<(), FreqError> {
+        for row in self.data.chunks_exact(self.width.max(1) as usize) {
+            let fields: Vec<String> = row
+                .iter()
+                .map(|c| match part {
+                    CsvPart::Real => c.re.to_string(),
+                    CsvPart::Imag => c.im.to_string(),
+                    CsvPart::Magnitude => c.norm().to_string(),
+                    CsvPart::Phase => phase_of(*c).to_string(),
+                    // `{:+}` prints NaN without a sign, which would run into the real part.
+                    CsvPart::Complex if c.im.is_nan() => format!("{}+NaNj", c.re),
+                    CsvPart::Complex => format!("{}{:+}j", c.re, c.im),
+                })
+                .collect();
+            writeln!(writer, "{}", fields.join(","))?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Read a CSV written by [`FreqImage::write_csv`].
+    ///
+    /// Only `Real`, `Imag` and `Complex` can be read back; magnitude or phase
+    /// alone cannot reconstruct the coefficients.
+    pub fn read_csv<R: Read>(reader: R, width: u32, height: u32, part: CsvPart) -> Result<Self, FreqError> {
+        if matches!(part, CsvPart::Magnitude | CsvPart::Phase) {
+            return Err(FreqError::InvalidFormat(format!(
+                "CSV: cannot rebuild coefficients from {:?} alone",
+                part
+            )));
+        }
+        let invalid = |line: usize, msg: String| FreqError::InvalidFormat(format!("CSV line {}: {}", line, msg));
+
+        let mut data = Vec::with_capacity(width as usize * height as usize);
+        let mut rows = 0;
+        for (i, line) in BufReader::new(reader).lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            rows += 1;
+            if rows > height {
+                return Err(invalid(i + 1, format!("expected only {} rows", height)));
+            }
+            let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+            if fields.len() != width as usize {
+                return Err(invalid(i + 1, format!("expected {} values, got {}", width, fields.len())));
+            }
+            for field in fields {
+                let value = match part {
+                    CsvPart::Real => field.parse().map(|re| Complex::new(re, 0.0)).ok(),
+                    CsvPart::Imag => field.parse().map(|im| Complex::new(0.0, im)).ok(),
+                    _ => parse_complex(field),
+                };
+                data.push(value.ok_or_else(|| invalid(i + 1, format!("bad value '{}'", field)))?);
+            }
+        }
+        if rows != height {
+            return Err(FreqError::InvalidFormat(format!("CSV: expected {} rows, got {}", height, rows)));
+        }
+        Ok(FreqImage { width, height, data })
+    }
+}
+
+/// Parse NumPy-style `re+imj` / `re-imj` text.
+fn parse_complex(field: &str) -> Option<Complex<f64>> {
+    let body = field.strip_suffix('j')?;
+    // The imaginary sign is the last '+'/'-' that is neither leading nor part of an exponent.
+    let split = body
+        .char_indices()
+        .filter(|&(i, ch)| (ch == '+' || ch == '-') && i > 0 && !body[..i].ends_with(['e', 'E']))
+        .map(|(i, _)| i)
+        .next_back()?;
+    let re = body[..split].parse().ok()?;
+    let im = body[split..].trim_start_matches('+').parse().ok()?;
+    Some(Complex::new(re, im))
+}
+
+#[cfg(test)]
+fn spectrum_4x4() -> FreqImage {
+    let mut fi = FreqImage {
+        width: 4,
+        height: 4,
+        data: (0..16).map(|i| Complex::new(((i * 7) % 11) as f64 / 10.0, 0.0)).collect(),
+    };
+    fi.fft_forward();
+    fi
+}
+
+#[test]
+fn test_csv_real_round_trip() {
+    let fi = spectrum_4x4();
+    let mut csv = Vec::new();
+    fi.write_csv(&mut csv, CsvPart::Real).unwrap();
+    assert_eq!(String::from_utf8_lossy(&csv).lines().count(), 4);
+
+    let back = FreqImage::read_csv(&csv[..], 4, 4, CsvPart::Real).unwrap();
+    for (a, b) in back.data.iter().zip(&fi.data) {
+        assert_eq!(a.re.to_bits(), b.re.to_bits());
+    }
+}
+
+#[test]
+fn test_csv_complex_round_trip() {
+    let fi = spectrum_4x4();
+    let mut csv = Vec::new();
+    fi.write_csv(&mut csv, CsvPart::Complex).unwrap();
+
+    let back = FreqImage::read_csv(&csv[..], 4, 4, CsvPart::Complex).unwrap();
+    assert_eq!(back, fi);
+    assert_eq!(parse_complex("1e-5-2.5E+3j"), Some(Complex::new(1e-5, -2.5e3)));
+}
+
+#[test]
+fn test_csv_non_finite_complex() {
+    let fi = FreqImage {
+        width: 4,
+        height: 1,
+        data: vec![
+            Complex::new(f64::NAN, f64::NAN),
+            Complex::new(1.5, f64::NAN),
+            Complex::new(f64::INFINITY, f64::NEG_INFINITY),
+            Complex::new(f64::NEG_INFINITY, -0.0),
+        ],
+    };
+    let mut csv = Vec::new();
+    fi.write_csv(&mut csv, CsvPart::Complex).unwrap();
+    assert_eq!(String::from_utf8(csv.clone()).unwrap(), "NaN+NaNj,1.5+NaNj,inf-infj,-inf-0j\n");
+
+    let back = FreqImage::read_csv(&csv[..], 4, 1, CsvPart::Complex).unwrap();
+    assert!(back.data[0].re.is_nan() && back.data[0].im.is_nan());
+    assert!(back.data[1].re == 1.5 && back.data[1].im.is_nan());
+    for (a, b) in back.data[2..].iter().zip(&fi.data[2..]) {
+        assert_eq!((a.re.to_bits(), a.im.to_bits()), (b.re.to_bits(), b.im.to_bits()));
+    }
+}
+
+#[test]
+fn test_csv_phase_of_zero() {
+    let fi = FreqImage {
+        width: 3,
+        height: 1,
+        data: vec![Complex::new(-0.0, 0.0), Complex::new(-0.0, -0.0), Complex::new(-1.0, 0.0)],
+    };
+    let mut csv = Vec::new();
+    fi.write_csv(&mut csv, CsvPart::Phase).unwrap();
+    assert_eq!(String::from_utf8(csv).unwrap(), format!("0,0,{}\n", std::f64::consts::PI));
+}
+
+#[test]
+fn test_csv_rejects_wrong_shape() {
+    let err = FreqImage::read_csv("1,2,3\n4,5\n".as_bytes(), 3, 2, CsvPart::Real).unwrap_err();
+    assert!(err.to_string().contains("line 2"));
+    assert!(FreqImage::read_csv("1,2\n".as_bytes(), 2, 2, CsvPart::Real).is_err());
+}
@@ -0,0 +1,166 @@
+//! Deconvolution: undoing a known blur.
+
+use rustfft::num_complex::Complex;
+
+use super::convolve::wrap_to_origin;
+use super::{FftContext, FreqImage, Kernel2D};
+
+/// Floor for the predicted image in the Richardson–Lucy ratio.
+const RL_EPSILON: f64 = 1e-12;
+
+impl FreqImage {
+    /// Richardson–Lucy deconvolution of a non-negative spatial-domain image blurred by
+    /// `psf`, the maximum-likelihood estimate under Poisson noise.
+    ///
+    /// `psf` is a small real kernel centered at `(width / 2, height / 2)` like a
+    /// [`Kernel2D`](super::Kernel2D); it is normalized to unit sum. Blurring is taken
+    /// to be circular. Each iteration runs four FFTs on a shared [`FftContext`], and
+    /// estimates are clamped to be non-negative. Zero iterations return the input.
+    pub fn richardson_lucy(&self, psf: &FreqImage, iterations: u32) -> FreqImage {
+        if iterations == 0 || self.data.is_empty() {
+            return self.clone();
+        }
+        let mut ctx = FftContext::new();
+        let otf = transfer_function(psf, self.width, self.height, &mut ctx);
+
+        let observed: Vec<f64> = self.data.iter().map(|c| c.re.max(0.0)).collect();
+        let mut estimate = FreqImage {
+            width: self.width,
+            height: self.height,
+            data: observed.iter().map(|&v| Complex::new(v, 0.0)).collect(),
+        };
+        for _ in 0..iterations {
+            let mut ratio = estimate.clone();
+            ctx.forward(&mut ratio);
+            ratio.mul_elementwise(&otf).unwrap();
+            ctx.inverse(&mut ratio);
+            for (r, &o) in ratio.data.iter_mut().zip(&observed) {
+                *r = Complex::new(o / r.re.max(RL_EPSILON), 0.0);
+            }
+
+            // Correlating with the PSF is multiplying by the conjugate of its transfer function.
+            ctx.forward(&mut ratio);
+            ratio.mul_conj_elementwise(&otf).unwrap();
+            ctx.inverse(&mut ratio);
+            for (e, r) in estimate.data.iter_mut().zip(&ratio.data) {
+                *e = Complex::new((e.re * r.re).max(0.0), 0.0);
+            }
+        }
+        estimate
+    }
+
+    /// Constrained least squares restoration of a spatial-domain image blurred by `psf`:
+    /// the spectrum is multiplied by [`FreqImage::cls_transfer`], which damps each
+    /// frequency in proportion to how rough it is rather than by a constant like a plain
+    /// Wiener filter. `psf` follows the conventions of [`FreqImage::richardson_lucy`].
+    ///
+    /// Larger `lambda` trades sharpness for noise suppression; zero is plain inverse
+    /// filtering.
+    pub fn cls_deconvolve(&self, psf: &FreqImage, lambda: f64) -> FreqImage {
+        let mut ctx = FftContext::new();
+        let mut restored = self.clone();
+        ctx.forward(&mut restored);
+        restored.mul_elementwise(&cls_transfer(psf, lambda, self.width, self.height, &mut ctx)).unwrap();
+        ctx.inverse(&mut restored);
+        restored
+    }
+
+    /// The restoration filter [`FreqImage::cls_deconvolve`] applies to an image of this
+    /// size, `H* / (|H|² + λ|L|²)` in unshifted order, where `H` is the transfer function
+    /// of `psf` and `L` that of [`Kernel2D::laplacian`]. Bins where both vanish are zero.
+    pub fn cls_transfer(&self, psf: &FreqImage, lambda: f64) -> FreqImage {
+        cls_transfer(psf, lambda, self.width, self.height, &mut FftContext::new())
+    }
+}
+
+/// The transfer function of `psf` at `width × height`, with the PSF normalized to unit
+/// sum (unless it sums to zero) and its center moved to the origin.
+fn transfer_function(psf: &FreqImage, width: u32, height: u32, ctx: &mut FftContext) -> FreqImage {
+    let total: f64 = psf.data.iter().map(|c| c.re).sum();
+    let mut otf = wrap_to_origin(psf, (psf.width / 2, psf.height / 2), width, height);
+    if total != 0.0 {
+        otf.data.iter_mut().for_each(|c| *c /= total);
+    }
+    ctx.forward(&mut otf);
+    otf
+}
+
+fn cls_transfer(psf: &FreqImage, lambda: f64, width: u32, height: u32, ctx: &mut FftContext) -> FreqImage {
+    let mut transfer = transfer_function(psf, width, height, ctx);
+    let mut laplacian = wrap_to_origin(&FreqImage::from(&Kernel2D::laplacian()), (1, 1), width, height);
+    ctx.forward(&mut laplacian);
+    for (h, l) in transfer.data.iter_mut().zip(&laplacian.data) {
+        let denominator = h.norm_sqr() + lambda * l.norm_sqr();
+        *h = if denominator > 0.0 { h.conj() / denominator } else { Complex::new(0.0, 0.0) };
+    }
+    transfer
+}
+
+#[cfg(test)]
+fn rms_diff(a: &FreqImage, b: &FreqImage) -> f64 {
+    let sum: f64 = a.data.iter().zip(&b.data).map(|(x, y)| (x - y).norm_sqr()).sum();
+    (sum / a.data.len() as f64).sqrt()
+}
+
+#[test]
+fn test_richardson_lucy() {
+    use super::{Boundary, Kernel2D};
+
+    let sharp = squares(64);
+    let kernel = Kernel2D::gaussian(1.5);
+    let blurred = sharp.convolve_with(&kernel, Boundary::Wrap);
+    let restored = blurred.richardson_lucy(&FreqImage::from(&kernel), 20);
+    assert!(rms_diff(&restored, &sharp) < rms_diff(&blurred, &sharp));
+    assert!(restored.data.iter().all(|c| c.re >= 0.0));
+
+    assert_eq!(blurred.richardson_lucy(&FreqImage::from(&kernel), 0), blurred);
+}
+
+/// Bright squares on a dim background.
+#[cfg(test)]
+fn squares(size: u32) -> FreqImage {
+    FreqImage {
+        width: size,
+        height: size,
+        data: (0..size * size)
+            .map(|i| {
+                let (x, y) = (i % size, i / size);
+                Complex::new(if (x / 8 + y / 8) % 3 == 0 { 1.0 } else { 0.1 }, 0.0)
+            })
+            .collect(),
+    }
+}
+
+#[test]
+fn test_cls_without_regularization_inverts_blur() {
+    use super::metrics::max_abs_diff;
+    use super::Boundary;
+
+    // |H| >= 0.2 everywhere, so the inverse is well conditioned.
+    let kernel = Kernel2D::new(3, 3, vec![0.0, 0.1, 0.0, 0.1, 0.6, 0.1, 0.0, 0.1, 0.0]).unwrap();
+    let sharp = squares(48);
+    let blurred = sharp.convolve_with(&kernel, Boundary::Wrap);
+    let psf = FreqImage::from(&kernel);
+    for lambda in [0.0, 1e-10] {
+        assert!(max_abs_diff(&blurred.cls_deconvolve(&psf, lambda), &sharp).unwrap() < 1e-6, "lambda {}", lambda);
+    }
+
+    // The DC bin is untouched: H(0, 0) = 1 and L(0, 0) = 0.
+    let transfer = sharp.cls_transfer(&psf, 0.5);
+    assert!((transfer[(0, 0)] - Complex::new(1.0, 0.0)).norm() < 1e-12);
+}
+
+#[test]
+fn test_cls_regularization_helps_with_noise() {
+    use super::metrics::psnr;
+    use super::Boundary;
+
+    let kernel = Kernel2D::gaussian(1.5);
+    let sharp = squares(64);
+    let mut noisy = sharp.convolve_with(&kernel, Boundary::Wrap);
+    noisy.add_gaussian_noise(0.01, 5);
+    let psf = FreqImage::from(&kernel);
+    let quality = |lambda: f64| psnr(&noisy.cls_deconvolve(&psf, lambda), &sharp, 1.0).unwrap();
+    let (under, middle, over) = (quality(1e-8), quality(1e-3), quality(10.0));
+    assert!(middle > under && middle > over, "{} {} {}", under, middle, over);
+}
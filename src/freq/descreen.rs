@@ -0,0 +1,162 @@
+//! Halftone screen detection and removal.
+
+use super::{FreqImage, SpectralPeak};
+
+/// A periodic screen found by [`FreqImage::detect_screen`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ScreenInfo {
+    /// Screen frequency in cycles per pixel.
+    pub frequency_cpp: f64,
+    /// Screen angle in degrees in `[0, 180)`, counter-clockwise from +x as displayed.
+    pub angle: f64,
+}
+
+/// A peak counts as a screen when it stands this many times above the median magnitude.
+const SCREEN_PROMINENCE: f64 = 20.0;
+
+impl FreqImage {
+    /// Find the dominant periodic screen of a spatial-domain image, if any: the strongest
+    /// off-DC spectral peak, provided it stands well clear of the spectrum's median level.
+    pub fn detect_screen(&self) -> Option<ScreenInfo> {
+        let (_, lattice) = self.screen_lattice()?;
+        let (u, v) = lattice[0];
+        let (fx, fy) = (u / self.width as f64, v / self.height as f64);
+        Some(ScreenInfo {
+            frequency_cpp: fx.hypot(fy),
+            angle: (-fy).atan2(fx).to_degrees().rem_euclid(180.0),
+        })
+    }
+
+    /// Suppress a halftone screen in a spatial-domain image. The two strongest independent
+    /// screen peaks span a lattice; a Gaussian notch of depth `strength` (0 to 1) is placed
+    /// on every lattice point but DC, covering harmonics and mirror peaks. Images without
+    /// a detectable screen are left unchanged.
+    pub fn descreen(&mut self, strength: f64) {
+        let Some((mut spectrum, basis)) = self.screen_lattice() else {
+            return;
+        };
+        let (w, h) = (self.width as f64, self.height as f64);
+        let points = lattice_points(&basis, w, h);
+        let sigma = (0.15 * basis[0].0.hypot(basis[0].1)).max(1.0);
+        let mask: Vec<f64> = spectrum
+            .enumerate_pixels()
+            .map(|(x, y, _)| {
+                let (fx, fy) = spectrum.frequency_at(x, y);
+                let (u, v) = (fx * w, fy * h);
+                points.iter().fold(1.0, |m, &(pu, pv)| {
+                    let du = u - pu - w * ((u - pu) / w).round();
+                    let dv = v - pv - h * ((v - pv) / h).round();
+                    m * (1.0 - strength * (-(du * du + dv * dv) / (2.0 * sigma * sigma)).exp())
+                })
+            })
+            .collect();
+        spectrum.apply_filter(&mask);
+        spectrum.fft_inverse();
+        *self = spectrum;
+    }
+
+    /// The spectrum and up to two lattice basis vectors `(u, v)` in bins, strongest first,
+    /// or `None` when no peak is prominent enough to be a screen.
+    fn screen_lattice(&self) -> Option<(FreqImage, Vec<(f64, f64)>)> {
+        let mut spectrum = self.clone();
+        spectrum.fft_forward();
+        let mut mags = spectrum.magnitude();
+        let mid = mags.len() / 2;
+        let median = *mags.select_nth_unstable_by(mid, f64::total_cmp).1;
+
+        let dc_radius = 0.02 * self.width.min(self.height) as f64;
+        let peaks: Vec<SpectralPeak> = spectrum
+            .find_spectral_peaks(8, 2.0, dc_radius.max(2.0))
+            .into_iter()
+            .filter(|p| p.magnitude > SCREEN_PROMINENCE * median)
+            .collect();
+        let first = peaks.first()?;
+        let mut basis = vec![(first.u.round(), first.v.round())];
+        // The second basis vector is the strongest peak not on the first one's line.
+        let independent = |p: &&SpectralPeak| {
+            let cross = first.u * p.v - first.v * p.u;
+            cross.abs() > 1e-6 * first.u.hypot(first.v) * p.u.hypot(p.v)
+        };
+        if let Some(p) = peaks.iter().skip(1).find(independent) {
+            basis.push((p.u.round(), p.v.round()));
+        }
+        Some((spectrum, basis))
+    }
+}
+
+/// Every non-zero integer combination of the basis vectors inside the spectrum, in bins.
+fn lattice_points(basis: &[(f64, f64)], w: f64, h: f64) -> Vec<(f64, f64)> {
+    let (a, b) = (basis[0], basis.get(1).copied().unwrap_or((0.0, 0.0)));
+    let reach = |(u, v): (f64, f64)| {
+        let len = (u / w).hypot(v / h);
+        if len > 0.0 {
+            (0.75 / len).ceil() as i64
+        } else {
+            0
+        }
+    };
+    let (ma, mb) = (reach(a), reach(b));
+    let mut points = Vec::new();
+    for m in -ma..=ma {
+        for n in -mb..=mb {
+            let (u, v) = (m as f64 * a.0 + n as f64 * b.0, m as f64 * a.1 + n as f64 * b.1);
+            if (m, n) != (0, 0) && (u / w).abs() <= 0.5 && (v / h).abs() <= 0.5 {
+                points.push((u, v));
+            }
+        }
+    }
+    points
+}
+
+/// Threshold a smooth gray ramp against a clustered-dot screen of the given period,
+/// giving a binary halftone with its fundamental at `1 / period` cycles per pixel.
+#[cfg(test)]
+fn halftone(size: u32, period: f64) -> FreqImage {
+    use rustfft::num_complex::Complex;
+    use std::f64::consts::PI;
+
+    FreqImage {
+        width: size,
+        height: size,
+        data: (0..size * size)
+            .map(|i| {
+                let (x, y) = ((i % size) as f64, (i / size) as f64);
+                let gray = 0.5 + 0.3 * (2.0 * PI * x / size as f64).sin() * (PI * y / size as f64).cos();
+                let screen = 0.5 + 0.25 * ((2.0 * PI * x / period).cos() + (2.0 * PI * y / period).cos());
+                Complex::new(if gray > screen { 1.0 } else { 0.0 }, 0.0)
+            })
+            .collect(),
+    }
+}
+
+#[test]
+fn test_detect_screen() {
+    let screen = halftone(128, 8.0).detect_screen().unwrap();
+    assert!((screen.frequency_cpp / 0.125 - 1.0).abs() < 0.05, "{:?}", screen);
+    assert!(screen.angle.rem_euclid(90.0) < 1.0, "{:?}", screen);
+}
+
+#[test]
+fn test_descreen_removes_periodic_energy() {
+    let image = halftone(128, 8.0);
+    let mut cleaned = image.clone();
+    cleaned.descreen(1.0);
+    assert!(cleaned.data.iter().all(|c| c.im.abs() < 1e-9));
+
+    // Energy within two bins of the screen lattice, i.e. multiples of 16 bins on each axis.
+    let band_energy = |fi: &FreqImage| {
+        let mut spectrum = fi.clone();
+        spectrum.fft_forward();
+        spectrum
+            .enumerate_pixels()
+            .filter(|&(x, y, _)| {
+                let near = |k: u32| k % 16 <= 2 || k % 16 >= 14;
+                let near_dc = |k: u32| k <= 2 || k >= 126;
+                near(x) && near(y) && !(near_dc(x) && near_dc(y))
+            })
+            .map(|(_, _, c)| c.norm_sqr())
+            .sum::<f64>()
+    };
+    let (before, after) = (band_energy(&image), band_energy(&cleaned));
+    assert!(after * 10.0 < before, "{} -> {}", before, after);
+}
@@ -0,0 +1,89 @@
+//! The 2D FFT on a [`FreqImage`], all of it routed through [`FftContext`].
+
+use rustfft::num_complex::Complex;
+
+use super::{FftContext, FreqImage, Progress};
+
+impl FreqImage {
+    /// Compute the 2D forward FFT in place. The transform is unnormalized.
+    pub fn fft_forward(&mut self) {
+        FftContext::new().forward(self);
+    }
+
+    /// Compute the 2D inverse FFT in place, scaling by `1 / (width * height)`
+    /// so that `fft_forward` followed by `fft_inverse` is the identity.
+    pub fn fft_inverse(&mut self) {
+        FftContext::new().inverse(self);
+    }
+
+    /// [`FreqImage::fft_forward`], reporting progress through the row pass (stage
+    /// `fft_rows`, out of `height`) and the column pass (`fft_columns`, out of `width`).
+    pub fn fft_forward_with_progress(&mut self, progress: &mut dyn FnMut(Progress)) {
+        FftContext::new().forward_with_progress(self, progress);
+    }
+
+    /// [`FreqImage::fft_inverse`], reporting progress like
+    /// [`FreqImage::fft_forward_with_progress`] under `ifft_rows` and `ifft_columns`.
+    pub fn fft_inverse_with_progress(&mut self, progress: &mut dyn FnMut(Progress)) {
+        FftContext::new().inverse_with_progress(self, progress);
+    }
+}
+
+/// Forward 2D FFT of a row-major `width × height` buffer, leaving the spectrum in
+/// column-major order (`buffer[x * height + y]`) as this function always has.
+///
+/// # Panics
+/// Panics if `buffer` does not hold `width * height` values.
+#[deprecated(note = "use FreqImage::fft_forward or FftContext, which keep row-major order")]
+pub fn fft_forward(width: usize, height: usize, buffer: &mut [Complex<f64>]) {
+    assert_eq!(buffer.len(), width * height, "buffer does not hold {}x{} values", width, height);
+    let mut fi = FreqImage { width: width as u32, height: height as u32, data: buffer.to_vec() };
+    FftContext::new().forward(&mut fi);
+    buffer.copy_from_slice(&transpose(width, height, &fi.data));
+}
+
+/// The `height × width` transpose of a row-major `width × height` matrix.
+pub(super) fn transpose<T: Copy + Default>(width: usize, height: usize, matrix: &[T]) -> Vec<T> {
+    let mut transposed = vec![T::default(); matrix.len()];
+    for (y, row) in matrix.chunks_exact(width.max(1)).enumerate() {
+        for (x, &v) in row.iter().enumerate() {
+            transposed[x * height + y] = v;
+        }
+    }
+    transposed
+}
+
+#[test]
+#[allow(deprecated)]
+fn test_slice_fft_matches_freq_image() {
+    let mut fi = super::ramp(5, 3);
+    let mut buffer = fi.data.clone();
+    fft_forward(5, 3, &mut buffer);
+    fi.fft_forward();
+    assert_eq!(buffer, transpose(5, 3, &fi.data));
+}
+
+#[test]
+fn test_transpose() {
+    let m = [1, 2, 3, 4, 5, 6];
+    assert_eq!(transpose(3, 2, &m), [1, 4, 2, 5, 3, 6]);
+    assert_eq!(transpose(2, 3, &transpose(3, 2, &m)), m);
+}
+
+#[test]
+fn test_degenerate_sizes_match_naive_dft() {
+    use std::f64::consts::PI;
+
+    for (w, h) in [(1, 1), (1, 7), (1, 12), (5, 1), (8, 1)] {
+        let fi = super::ramp(w, h);
+        let n = fi.data.len();
+        let expected: Vec<Complex<f64>> = (0..n)
+            .map(|k| (0..n).map(|j| fi.data[j] * Complex::from_polar(1.0, -2.0 * PI * (j * k) as f64 / n as f64)).sum())
+            .collect();
+        let mut spectrum = fi.clone();
+        spectrum.fft_forward();
+        assert!(spectrum.approx_eq(&FreqImage { data: expected, ..fi.clone() }, 1e-9), "{}x{}", w, h);
+        spectrum.fft_inverse();
+        assert!(spectrum.approx_eq(&fi, 1e-9), "{}x{}", w, h);
+    }
+}
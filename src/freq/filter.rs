@@ -0,0 +1,276 @@
+//! Radial frequency masks for a centered (fftshifted) spectrum.
+
+use rustfft::num_complex::Complex;
+
+use super::{radial, FreqImage};
+use crate::FreqError;
+
+impl FreqImage {
+    /// A low-pass mask for this image's centered spectrum: 1 inside `cutoff`, 0 beyond
+    /// `cutoff + smoothing`, with a smooth roll-off between. Both radii are fractions of
+    /// the image diagonal, measured from `((width-1)/2, (height-1)/2)`.
+    pub fn low_pass_mask(&self, cutoff: f64, smoothing: f64) -> Vec<f64> {
+        self.make_radial_mask(radial::low_pass(cutoff, smoothing))
+    }
+
+    /// The complement of [`FreqImage::low_pass_mask`], so the two always sum to one.
+    pub fn high_pass_mask(&self, cutoff: f64, smoothing: f64) -> Vec<f64> {
+        self.make_radial_mask(radial::high_pass(cutoff, smoothing))
+    }
+
+    /// Keep the annulus between `low` and `high` (fractions of the diagonal): a low-pass
+    /// at `high` minus a low-pass at `low`, each with the given `smoothing`.
+    ///
+    /// # Panics
+    /// Panics if the band is invalid; see [`FreqImage::try_band_pass_mask`].
+    pub fn band_pass_mask(&self, low: f64, high: f64, smoothing: f64) -> Vec<f64> {
+        self.try_band_pass_mask(low, high, smoothing).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// [`FreqImage::band_pass_mask`], or [`FreqError::InvalidCutoff`] unless
+    /// `0 <= low < high` with both finite.
+    pub fn try_band_pass_mask(&self, low: f64, high: f64, smoothing: f64) -> Result<Vec<f64>, FreqError> {
+        radial::check_band(low, high)?;
+        Ok(self.make_radial_mask(radial::band_pass(low, high, smoothing)))
+    }
+
+    /// Multiply every coefficient by the matching mask weight.
+    ///
+    /// # Panics
+    /// Panics if `mask` does not hold `width * height` weights.
+    pub fn apply_filter(&mut self, mask: &[f64]) {
+        if self.try_apply_filter(mask).is_err() {
+            panic!("mask has {} weights for a {}x{} FreqImage", mask.len(), self.width, self.height);
+        }
+    }
+
+    /// [`FreqImage::apply_filter`], or [`FreqError::LengthMismatch`] if `mask` does not
+    /// hold `width * height` weights.
+    pub fn try_apply_filter(&mut self, mask: &[f64]) -> Result<(), FreqError> {
+        if mask.len() != self.data.len() {
+            return Err(FreqError::LengthMismatch { expected: self.data.len(), got: mask.len() });
+        }
+        for (c, &m) in self.data.iter_mut().zip(mask) {
+            *c *= m;
+        }
+        Ok(())
+    }
+
+    /// Multiply coefficient `(x, y)` by `row_profile[x] * col_profile[y]`, the outer
+    /// product of two 1D profiles, without building the `width * height` mask; see
+    /// [`profile`](super::profile) for profiles of a centered spectrum.
+    ///
+    /// Errors with [`FreqError::LengthMismatch`] unless `row_profile` has `width` gains and
+    /// `col_profile` has `height`.
+    pub fn apply_separable_filter(&mut self, row_profile: &[f64], col_profile: &[f64]) -> Result<(), FreqError> {
+        for (profile, expected) in [(row_profile, self.width), (col_profile, self.height)] {
+            if profile.len() != expected as usize {
+                return Err(FreqError::LengthMismatch { expected: expected as usize, got: profile.len() });
+            }
+        }
+        for (row, &gy) in self.rows_mut().zip(col_profile) {
+            for (c, &gx) in row.iter_mut().zip(row_profile) {
+                *c *= gx * gy;
+            }
+        }
+        Ok(())
+    }
+
+    /// Flatten the magnitude spectrum while keeping phase: every coefficient becomes
+    /// `c / (|c| + epsilon)`. Zero and non-finite coefficients become zero.
+    pub fn whiten(&mut self, epsilon: f64) {
+        for c in self.data.iter_mut() {
+            *c = whitened(*c, epsilon);
+        }
+    }
+
+    /// [`FreqImage::whiten`] only the coefficients inside the hard-edged annulus of
+    /// [`FreqImage::band_pass_mask`]`(low, high, 0.0)` of a centered spectrum; the rest
+    /// are left untouched.
+    pub fn whiten_band(&mut self, low: f64, high: f64, epsilon: f64) {
+        let band = self.band_pass_mask(low, high, 0.0);
+        for (c, &inside) in self.data.iter_mut().zip(&band) {
+            if inside > 0.0 {
+                *c = whitened(*c, epsilon);
+            }
+        }
+    }
+
+    /// Multiply every coefficient of a centered spectrum by `profile` of its distance from
+    /// the center, as a fraction of the diagonal; see [`radial`] for ready-made profiles.
+    /// Gives exactly the result of applying the equivalent mask, without building it.
+    pub fn apply_radial_filter(&mut self, profile: impl Fn(f64) -> f64) {
+        let radius = self.radius_fn();
+        for (x, y, c) in self.enumerate_pixels_mut() {
+            *c *= profile(radius(x, y));
+        }
+    }
+
+    /// Evaluate `profile` at each bin's distance from the spectrum center.
+    fn make_radial_mask(&self, profile: impl Fn(f64) -> f64) -> Vec<f64> {
+        let radius = self.radius_fn();
+        self.enumerate_pixels().map(|(x, y, _)| profile(radius(x, y))).collect()
+    }
+
+    /// Distance of bin `(x, y)` from `((width-1)/2, (height-1)/2)` as a fraction of the diagonal.
+    fn radius_fn(&self) -> impl Fn(u32, u32) -> f64 {
+        let center_x = (self.width as f64 - 1.0) / 2.0;
+        let center_y = (self.height as f64 - 1.0) / 2.0;
+        let diagonal = (self.width as f64).hypot(self.height as f64);
+        move |x, y| (center_x - x as f64).hypot(center_y - y as f64) / diagonal
+    }
+}
+
+fn whitened(c: Complex<f64>, epsilon: f64) -> Complex<f64> {
+    let w = c / (c.norm() + epsilon);
+    if w.re.is_finite() && w.im.is_finite() {
+        w
+    } else {
+        Complex::new(0.0, 0.0)
+    }
+}
+
+#[test]
+fn test_masks() {
+    let fi = super::ramp(9, 9);
+    let lp = fi.low_pass_mask(0.1, 0.2);
+    let hp = fi.high_pass_mask(0.1, 0.2);
+    assert_eq!(lp[4 * 9 + 4], 1.0);
+    assert_eq!(lp[0], 0.0);
+    assert!(lp.iter().all(|m| (0.0..=1.0).contains(m)));
+    assert!(lp.iter().zip(&hp).all(|(l, h)| l + h == 1.0));
+
+    // A hard edge at smoothing zero.
+    let hard = fi.low_pass_mask(0.2, 0.0);
+    assert!(hard.iter().all(|&m| m == 0.0 || m == 1.0));
+
+    let band = fi.band_pass_mask(0.1, 0.3, 0.0);
+    assert_eq!(band[4 * 9 + 4], 0.0);
+    assert_eq!(band[4 * 9 + 6], 1.0);
+    assert_eq!(band[0], 0.0);
+}
+
+#[test]
+fn test_radial_filter_matches_mask() {
+    let spectrum = super::ramp(37, 24);
+    let check = |mask: Vec<f64>, profile: &dyn Fn(f64) -> f64| {
+        let mut masked = spectrum.clone();
+        masked.apply_filter(&mask);
+        let mut fused = spectrum.clone();
+        fused.apply_radial_filter(profile);
+        assert_eq!(fused, masked);
+    };
+    check(spectrum.low_pass_mask(0.1, 0.02), &radial::low_pass(0.1, 0.02));
+    check(spectrum.high_pass_mask(0.05, 0.1), &radial::high_pass(0.05, 0.1));
+    check(spectrum.band_pass_mask(0.1, 0.3, 0.05), &radial::band_pass(0.1, 0.3, 0.05));
+}
+
+#[test]
+fn test_apply_filter() {
+    let mut fi = super::ramp(2, 2);
+    fi.apply_filter(&[1.0, 0.0, 0.5, 2.0]);
+    assert_eq!(fi.data, super::ramp(2, 2).data.iter().zip([1.0, 0.0, 0.5, 2.0]).map(|(c, m)| c * m).collect::<Vec<_>>());
+}
+
+#[test]
+fn test_separable_filter_matches_mask() {
+    use super::profile;
+
+    let spectrum = super::ramp(37, 24);
+    let (rows, cols) = (profile::gaussian(37, 0.1), profile::raised_cosine_lowpass(24, 0.1, 0.15));
+    let mask: Vec<f64> = cols.iter().flat_map(|&gy| rows.iter().map(move |&gx| gx * gy)).collect();
+    let mut expected = spectrum.clone();
+    expected.apply_filter(&mask);
+    let mut separable = spectrum.clone();
+    separable.apply_separable_filter(&rows, &cols).unwrap();
+    assert_eq!(separable, expected);
+
+    let mut fi = spectrum.clone();
+    assert!(matches!(fi.apply_separable_filter(&cols, &cols), Err(FreqError::LengthMismatch { expected: 37, got: 24 })));
+    assert!(matches!(fi.apply_separable_filter(&rows, &rows), Err(FreqError::LengthMismatch { expected: 24, got: 37 })));
+    assert_eq!(fi, spectrum);
+}
+
+#[test]
+fn test_degenerate_masks() {
+    let single = super::ramp(1, 1);
+    assert_eq!(single.low_pass_mask(0.1, 0.0), [1.0]);
+    assert_eq!(single.high_pass_mask(0.1, 0.0), [0.0]);
+    assert_eq!(single.band_pass_mask(0.1, 0.3, 0.0), [0.0]);
+
+    for (w, h) in [(1, 9), (9, 1)] {
+        let mut fi = super::ramp(w, h);
+        let lp = fi.low_pass_mask(0.2, 0.1);
+        // Centered on the middle bin and symmetric about it.
+        assert_eq!(lp[4], 1.0);
+        assert!((0..9).all(|i| lp[i] == lp[8 - i]));
+        assert!(lp[0] < 1.0);
+        let band = fi.band_pass_mask(0.1, 0.3, 0.0);
+        assert_eq!((band[4], band[6]), (0.0, 1.0));
+        fi.apply_filter(&lp);
+        assert_eq!(fi[(w / 2, h / 2)], super::ramp(w, h)[(w / 2, h / 2)]);
+    }
+}
+
+#[test]
+#[should_panic(expected = "mask has 3 weights")]
+fn test_apply_filter_length_mismatch() {
+    super::ramp(2, 2).apply_filter(&[1.0; 3]);
+}
+
+#[test]
+fn test_try_variants() {
+    let mut fi = super::ramp(4, 4);
+    assert!(matches!(
+        fi.try_apply_filter(&[1.0; 15]),
+        Err(FreqError::LengthMismatch { expected: 16, got: 15 })
+    ));
+    assert!(fi.try_apply_filter(&[1.0; 16]).is_ok());
+    for (low, high) in [(0.3, 0.1), (0.2, 0.2), (-0.1, 0.2), (0.1, f64::NAN), (f64::NAN, 0.2)] {
+        let err = fi.try_band_pass_mask(low, high, 0.0).unwrap_err();
+        assert!(matches!(err, FreqError::InvalidCutoff { .. }));
+        assert!(err.to_string().contains(&format!("[{}, {}]", low, high)));
+    }
+    assert_eq!(fi.try_band_pass_mask(0.0, 0.2, 0.1).unwrap(), fi.band_pass_mask(0.0, 0.2, 0.1));
+}
+
+#[test]
+#[should_panic(expected = "invalid cutoff")]
+fn test_band_pass_mask_rejects_reversed_band() {
+    super::ramp(4, 4).band_pass_mask(0.3, 0.1, 0.0);
+}
+
+#[test]
+fn test_whiten() {
+    let mut fi = super::ramp(4, 3);
+    fi.data[0] = Complex::new(0.0, 0.0);
+    fi.data[1] = Complex::new(f64::INFINITY, 0.0);
+    let original = fi.clone();
+    fi.whiten(0.0);
+    assert_eq!(fi.data[0], Complex::new(0.0, 0.0));
+    assert_eq!(fi.data[1], Complex::new(0.0, 0.0));
+    for (w, c) in fi.data.iter().zip(&original.data).skip(2) {
+        assert!((w.norm() - 1.0).abs() < 1e-12);
+        assert!((w.arg() - c.arg()).abs() < 1e-12);
+    }
+
+    let mut zeros = FreqImage { width: 2, height: 2, data: vec![Complex::new(0.0, 0.0); 4] };
+    zeros.whiten(0.0);
+    assert!(zeros.data.iter().all(|c| *c == Complex::new(0.0, 0.0)));
+}
+
+#[test]
+fn test_whiten_band() {
+    let mut fi = super::ramp(9, 9);
+    fi.data[0] = Complex::new(3.0, 4.0);
+    let original = fi.clone();
+    let band = fi.band_pass_mask(0.1, 0.3, 0.0);
+    fi.whiten_band(0.1, 0.3, 1e-3);
+    for ((w, c), &b) in fi.data.iter().zip(&original.data).zip(&band) {
+        if b > 0.0 {
+            assert!((w.norm() - c.norm() / (c.norm() + 1e-3)).abs() < 1e-12);
+        } else {
+            assert_eq!(w, c);
+        }
+    }
+}
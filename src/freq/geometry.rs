@@ -0,0 +1,178 @@
+//! Crops, flips and quarter-turn rotations on the complex buffer.
+//!
+//! For a real image, mirroring about the origin (`x → -x mod W`) conjugates the spectrum:
+//! `F(-k) = conj(F(k))`. The flips here mirror about the image center (`x → W-1-x`),
+//! which is the same thing followed by a one-pixel shift, i.e. a linear phase ramp
+//! `exp(2πi·k/W)` on top of the conjugate.
+
+use rustfft::num_complex::Complex;
+
+use super::fft::transpose;
+use super::FreqImage;
+
+impl FreqImage {
+    /// The `width × height` region whose top-left corner is `(x, y)`.
+    ///
+    /// # Panics
+    /// Panics if the region extends past the image bounds.
+    pub fn crop(&self, x: u32, y: u32, width: u32, height: u32) -> FreqImage {
+        assert!(
+            x as u64 + width as u64 <= self.width as u64 && y as u64 + height as u64 <= self.height as u64,
+            "crop {}x{} at ({}, {}) out of bounds for {}x{} FreqImage",
+            width, height, x, y, self.width, self.height
+        );
+        let data = self
+            .rows()
+            .skip(y as usize)
+            .take(height as usize)
+            .flat_map(|row| &row[x as usize..(x + width) as usize])
+            .copied()
+            .collect();
+        FreqImage { width, height, data }
+    }
+
+    /// The central `width × height` region, with the offset rounded down when the size
+    /// difference is odd.
+    ///
+    /// # Panics
+    /// Panics if the region is larger than the image.
+    pub fn center_crop(&self, width: u32, height: u32) -> FreqImage {
+        self.crop(self.width.saturating_sub(width) / 2, self.height.saturating_sub(height) / 2, width, height)
+    }
+
+    /// Mirror left-to-right.
+    pub fn flip_horizontal(&self) -> FreqImage {
+        let mut out = self.clone();
+        for row in out.rows_mut() {
+            row.reverse();
+        }
+        out
+    }
+
+    /// Mirror top-to-bottom.
+    pub fn flip_vertical(&self) -> FreqImage {
+        FreqImage {
+            width: self.width,
+            height: self.height,
+            data: self.data.chunks_exact(self.width.max(1) as usize).rev().flatten().copied().collect(),
+        }
+    }
+
+    /// Rotate a quarter turn clockwise; width and height swap.
+    pub fn rotate90(&self) -> FreqImage {
+        self.transposed().flip_horizontal()
+    }
+
+    /// Rotate a half turn.
+    pub fn rotate180(&self) -> FreqImage {
+        let mut out = self.clone();
+        out.data.reverse();
+        out
+    }
+
+    /// Rotate a quarter turn counter-clockwise; width and height swap.
+    pub fn rotate270(&self) -> FreqImage {
+        self.transposed().flip_vertical()
+    }
+
+    /// Rotate by `angle_deg` degrees counter-clockwise as displayed and scale by `scale`,
+    /// both about the image center `((width-1)/2, (height-1)/2)`, with bilinear
+    /// interpolation. Pixels that map from outside the image are zero.
+    pub fn rotate_scale(&self, angle_deg: f64, scale: f64) -> FreqImage {
+        let (sin, cos) = angle_deg.to_radians().sin_cos();
+        let cx = (self.width as f64 - 1.0) / 2.0;
+        let cy = (self.height as f64 - 1.0) / 2.0;
+        let mut out = self.clone();
+        for (x, y, c) in out.enumerate_pixels_mut() {
+            // Inverse map: undo the scale, then rotate back (y points down).
+            let (u, v) = ((x as f64 - cx) / scale, (y as f64 - cy) / scale);
+            let (sx, sy) = (u * cos - v * sin + cx, u * sin + v * cos + cy);
+            *c = self.sample_bilinear(sx, sy).unwrap_or_default();
+        }
+        out
+    }
+
+    /// Bilinearly interpolate at fractional pixel coordinates, or `None` outside the image.
+    pub(crate) fn sample_bilinear(&self, x: f64, y: f64) -> Option<Complex<f64>> {
+        let (max_x, max_y) = (self.width as f64 - 1.0, self.height as f64 - 1.0);
+        if !(0.0..=max_x).contains(&x) || !(0.0..=max_y).contains(&y) {
+            return None;
+        }
+        let (x0, y0) = (x.floor().min(max_x - 1.0).max(0.0), y.floor().min(max_y - 1.0).max(0.0));
+        let (fx, fy) = (x - x0, y - y0);
+        let (x0, y0) = (x0 as u32, y0 as u32);
+        let (x1, y1) = ((x0 + 1).min(self.width - 1), (y0 + 1).min(self.height - 1));
+        let top = self[(x0, y0)] * (1.0 - fx) + self[(x1, y0)] * fx;
+        let bottom = self[(x0, y1)] * (1.0 - fx) + self[(x1, y1)] * fx;
+        Some(top * (1.0 - fy) + bottom * fy)
+    }
+
+    fn transposed(&self) -> FreqImage {
+        FreqImage {
+            width: self.height,
+            height: self.width,
+            data: transpose(self.width as usize, self.height as usize, &self.data),
+        }
+    }
+}
+
+#[test]
+fn test_rotate() {
+    let fi = super::ramp(3, 2);
+    let r = fi.rotate90();
+    assert_eq!((r.width, r.height), (2, 3));
+    // The bottom-left pixel moves to the top-left.
+    assert_eq!(r[(0, 0)], fi[(0, 1)]);
+    assert_eq!(r[(1, 0)], fi[(0, 0)]);
+    assert_eq!(fi.rotate270()[(0, 0)], fi[(2, 0)]);
+    assert_eq!(fi.rotate180()[(0, 0)], fi[(2, 1)]);
+    assert_eq!(r.rotate90().rotate90().rotate90(), fi);
+    assert_eq!(r.rotate90(), fi.rotate180());
+    assert_eq!(fi.rotate90().rotate270(), fi);
+}
+
+#[test]
+fn test_flip_and_crop() {
+    let fi = super::ramp(3, 2);
+    assert_eq!(fi.flip_horizontal()[(0, 0)], fi[(2, 0)]);
+    assert_eq!(fi.flip_vertical()[(0, 0)], fi[(0, 1)]);
+    assert_eq!(fi.flip_horizontal().flip_horizontal(), fi);
+    assert_eq!(fi.flip_vertical().flip_vertical(), fi);
+    assert_eq!(fi.flip_horizontal().flip_vertical(), fi.rotate180());
+
+    let c = fi.crop(1, 1, 2, 1);
+    assert_eq!((c.width, c.height), (2, 1));
+    assert_eq!(c.data, vec![fi[(1, 1)], fi[(2, 1)]]);
+}
+
+#[test]
+fn test_center_crop() {
+    let fi = super::ramp(5, 4);
+    assert_eq!(fi.center_crop(3, 3), fi.crop(1, 0, 3, 3));
+    assert_eq!(fi.center_crop(2, 2), fi.crop(1, 1, 2, 2));
+    assert_eq!(fi.center_crop(5, 4), fi);
+}
+
+#[test]
+#[should_panic(expected = "out of bounds")]
+fn test_crop_out_of_bounds() {
+    super::ramp(3, 2).crop(2, 0, 2, 1);
+}
+
+#[test]
+fn test_rotate_scale() {
+    let fi = super::ramp(5, 5);
+    let same = fi.rotate_scale(0.0, 1.0);
+    assert_eq!(same, fi);
+
+    // A quarter turn counter-clockwise moves the right-hand middle pixel to the top.
+    let r = fi.rotate_scale(90.0, 1.0);
+    assert!((r[(2, 0)] - fi[(4, 2)]).norm() < 1e-9);
+    assert!((r[(2, 2)] - fi[(2, 2)]).norm() < 1e-9);
+
+    // Doubling the size pulls the corner pixel from halfway in.
+    let z = fi.rotate_scale(0.0, 2.0);
+    assert!((z[(0, 0)] - fi.sample_bilinear(1.0, 1.0).unwrap()).norm() < 1e-9);
+    assert!((fi.sample_bilinear(1.5, 0.0).unwrap() - (fi[(1, 0)] + fi[(2, 0)]) / 2.0).norm() < 1e-9);
+    assert_eq!(fi.sample_bilinear(-0.5, 0.0), None);
+}
@@ -0,0 +1,167 @@
+//! The distribution of coefficient magnitudes, for picking thresholds and clip levels,
+//! and masks that threshold on it.
+
+use image::{GrayImage, Luma};
+
+use super::view::percentile;
+use super::FreqImage;
+
+/// Counts of coefficient magnitudes in equal-width bins, see
+/// [`FreqImage::magnitude_histogram`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct Histogram {
+    /// `counts.len() + 1` ascending bin edges, in the binned quantity: `|c|`, or
+    /// `ln(1 + |c|)` when `log_scale` is set. Each bin includes its lower edge; the last
+    /// also includes its upper one.
+    pub edges: Vec<f64>,
+    /// Number of coefficients in each bin.
+    pub counts: Vec<usize>,
+    /// Whether magnitudes were binned as `ln(1 + |c|)`.
+    pub log_scale: bool,
+}
+
+impl FreqImage {
+    /// Bin the magnitudes of all finite coefficients into `bins` equal-width bins spanning
+    /// their range. With `log_scale`, bin `ln(1 + |c|)` instead, the quantity
+    /// [`FreqImage::view_fft_norm`] displays. If every magnitude is equal they all land
+    /// in the first bin.
+    ///
+    /// # Panics
+    /// Panics if `bins` is zero.
+    pub fn magnitude_histogram(&self, bins: usize, log_scale: bool) -> Histogram {
+        assert!(bins > 0, "a histogram needs at least one bin");
+        let values: Vec<f64> = self
+            .data
+            .iter()
+            .filter(|c| c.is_finite())
+            .map(|c| if log_scale { c.norm().ln_1p() } else { c.norm() })
+            .collect();
+        let min = values.iter().cloned().fold(f64::INFINITY, f64::min);
+        let max = values.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+        let (min, max) = if values.is_empty() { (0.0, 0.0) } else { (min, max) };
+        let width = (max - min) / bins as f64;
+
+        let mut counts = vec![0; bins];
+        for v in &values {
+            let bin = if width > 0.0 { ((v - min) / width) as usize } else { 0 };
+            counts[bin.min(bins - 1)] += 1;
+        }
+        let mut edges: Vec<f64> = (0..bins).map(|i| min + i as f64 * width).collect();
+        edges.push(max);
+        Histogram { edges, counts, log_scale }
+    }
+
+    /// The `p` percentile (0–100, nearest rank) of the finite coefficient magnitudes,
+    /// found by selection rather than a full sort. `p = 0` and `p = 100` give exactly the
+    /// smallest and largest magnitude; with no finite coefficients the result is 0.
+    pub fn magnitude_percentile(&self, p: f64) -> f64 {
+        let mut magnitudes: Vec<f64> = self.data.iter().filter(|c| c.is_finite()).map(|c| c.norm()).collect();
+        percentile(&mut magnitudes, p)
+    }
+
+    /// A 0/1 mask marking the coefficients whose magnitude is at least the `percentile`
+    /// (0–100) of all magnitudes, see [`FreqImage::magnitude_percentile`]: 0 marks every
+    /// finite coefficient, 100 only the largest. Pass it to
+    /// [`FreqImage::apply_filter`] to keep the dominant components, or its complement
+    /// (`1 - m`) to excise them. The mask follows the buffer's current layout.
+    pub fn threshold_mask(&self, percentile: f64) -> Vec<f64> {
+        self.threshold_mask_abs(self.magnitude_percentile(percentile))
+    }
+
+    /// A 0/1 mask marking the coefficients with `|c| >= level`. Non-finite coefficients
+    /// are never marked.
+    pub fn threshold_mask_abs(&self, level: f64) -> Vec<f64> {
+        self.data
+            .iter()
+            .map(|c| if c.is_finite() && c.norm() >= level { 1.0 } else { 0.0 })
+            .collect()
+    }
+}
+
+/// Draw `hist` as a white-on-black bar chart `width` by `height` pixels, with bar heights
+/// relative to the fullest bin. Bins share the columns as evenly as the width allows.
+pub fn render_histogram(hist: &Histogram, width: u32, height: u32) -> GrayImage {
+    let bins = hist.counts.len();
+    let most = hist.counts.iter().cloned().max().unwrap_or(0);
+    GrayImage::from_fn(width, height, |x, y| {
+        if most == 0 {
+            return Luma([0]);
+        }
+        let bin = x as usize * bins / width as usize;
+        let bar = (hist.counts[bin] as f64 / most as f64 * height as f64).round() as u32;
+        Luma([if height - y <= bar { 255 } else { 0 }])
+    })
+}
+
+#[cfg(test)]
+fn known_magnitudes() -> FreqImage {
+    use rustfft::num_complex::Complex;
+
+    // Magnitudes 0..=9 in a scrambled order, with the sign and axis varying too.
+    let mags = [7.0, 0.0, 3.0, 9.0, 1.0, 5.0, 8.0, 2.0, 6.0, 4.0];
+    let data = mags
+        .iter()
+        .enumerate()
+        .map(|(i, &m)| if i % 2 == 0 { Complex::new(-m, 0.0) } else { Complex::new(0.0, m) })
+        .collect();
+    FreqImage { width: 5, height: 2, data }
+}
+
+#[test]
+fn test_magnitude_histogram() {
+    let fi = known_magnitudes();
+    let hist = fi.magnitude_histogram(5, false);
+    assert_eq!(hist.counts, vec![2; 5]);
+    assert_eq!(hist.edges.len(), 6);
+    assert_eq!((hist.edges[0], hist.edges[5]), (0.0, 9.0));
+
+    let three = fi.magnitude_histogram(3, false);
+    assert_eq!(three.counts, vec![3, 3, 4]);
+
+    let log = fi.magnitude_histogram(4, true);
+    assert_eq!(log.counts.iter().sum::<usize>(), 10);
+    assert!((log.edges[4] - 10f64.ln()).abs() < 1e-12);
+
+    let flat = FreqImage { width: 2, height: 2, data: vec![Default::default(); 4] };
+    assert_eq!(flat.magnitude_histogram(3, false).counts, vec![4, 0, 0]);
+}
+
+#[test]
+fn test_magnitude_percentile() {
+    let mut fi = known_magnitudes();
+    fi.data.pop(); // drop the 4, leaving nine values with median 5
+    assert_eq!(fi.magnitude_percentile(50.0), 5.0);
+    assert_eq!(fi.magnitude_percentile(0.0), 0.0);
+    assert_eq!(fi.magnitude_percentile(100.0), 9.0);
+}
+
+#[test]
+fn test_threshold_mask() {
+    let fi = super::synth::spectral_noise(40, 25, 1.0, 7);
+    let n = fi.data.len();
+    assert_eq!(fi.threshold_mask(0.0), vec![1.0; n]);
+
+    let top = fi.threshold_mask(100.0);
+    let max = fi.magnitude_percentile(100.0);
+    for (m, c) in top.iter().zip(&fi.data) {
+        assert_eq!(*m == 1.0, c.norm() == max);
+    }
+    assert!(top.contains(&1.0));
+
+    let marked = fi.threshold_mask(99.0).iter().filter(|&&m| m == 1.0).count() as f64;
+    assert!((marked - 0.01 * n as f64).abs() <= 1.0, "{} of {} marked", marked, n);
+
+    let known = known_magnitudes();
+    assert_eq!(known.threshold_mask_abs(7.0).iter().sum::<f64>(), 3.0);
+}
+
+#[test]
+fn test_render_histogram() {
+    let hist = Histogram { edges: vec![0.0, 1.0, 2.0], counts: vec![4, 2], log_scale: false };
+    let img = render_histogram(&hist, 4, 10);
+    assert_eq!(img.dimensions(), (4, 10));
+    // The first bin fills its columns; the second reaches half way.
+    assert!((0..10).all(|y| img.get_pixel(0, y)[0] == 255));
+    assert_eq!(img.get_pixel(3, 4)[0], 0);
+    assert_eq!(img.get_pixel(3, 5)[0], 255);
+}
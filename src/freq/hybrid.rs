@@ -0,0 +1,44 @@
+//! Hybrid images: the low frequencies of one image over the high frequencies of another.
+
+use super::FreqImage;
+use crate::FreqError;
+
+/// Combine the low frequencies of `a` with the high frequencies of `b` (Oliva and
+/// Torralba's hybrid images): `a` dominates from afar, `b` up close.
+///
+/// `cutoff` and `smoothing` are those of [`FreqImage::low_pass_mask`]; `b` gets the
+/// complementary high-pass, so the masks sum to one. Both inputs are spatial-domain
+/// images of equal size; the result is real and clamped to `[0, 1]`.
+pub fn hybrid(a: &FreqImage, b: &FreqImage, cutoff: f64, smoothing: f64) -> Result<FreqImage, FreqError> {
+    let (mut low, mut high) = (a.clone(), b.clone());
+    let low_pass = low.low_pass_mask(cutoff, smoothing);
+    let high_pass = high.high_pass_mask(cutoff, smoothing);
+    for (fi, mask) in [(&mut low, &low_pass), (&mut high, &high_pass)] {
+        fi.fft_forward();
+        fi.fftshift();
+        fi.apply_filter(mask);
+    }
+    low.add_elementwise(&high)?;
+    low.ifftshift();
+    low.fft_inverse();
+    low.clamp_real(0.0, 1.0);
+    Ok(low)
+}
+
+#[test]
+fn test_hybrid_of_same_image() {
+    let a = FreqImage::open("img/mandrill.jpg").unwrap().crop(0, 0, 64, 48);
+    let out = hybrid(&a, &a, 0.05, 0.02).unwrap();
+    for (x, y) in out.data.iter().zip(&a.data) {
+        assert!((x - y).norm() < 1e-9);
+    }
+}
+
+#[test]
+fn test_hybrid_dimension_mismatch() {
+    let a = super::ramp(4, 4);
+    assert!(matches!(
+        hybrid(&a, &super::ramp(4, 3), 0.1, 0.0),
+        Err(FreqError::DimensionMismatch { .. })
+    ));
+}
@@ -0,0 +1,83 @@
+//! The coefficient buffer viewed as interleaved `[re, im, re, im, ...]` doubles, the
+//! layout C libraries and DSP code usually exchange spectra in.
+
+use std::mem::{align_of, size_of, ManuallyDrop};
+
+use rustfft::num_complex::Complex;
+
+use super::FreqImage;
+use crate::FreqError;
+
+// `Complex<f64>` is `#[repr(C)]` with fields `re` then `im`, so it is exactly two
+// doubles with a double's alignment. Everything below relies on that.
+const _: () = assert!(size_of::<Complex<f64>>() == 2 * size_of::<f64>());
+const _: () = assert!(align_of::<Complex<f64>>() == align_of::<f64>());
+
+impl FreqImage {
+    /// The coefficients as `2 * width * height` doubles, `re` then `im` for each in
+    /// row-major order, without copying.
+    pub fn as_interleaved(&self) -> &[f64] {
+        // Safety: see the layout assertions above; the slice covers exactly the buffer.
+        unsafe { std::slice::from_raw_parts(self.data.as_ptr().cast::<f64>(), 2 * self.data.len()) }
+    }
+
+    /// [`FreqImage::as_interleaved`], writable: changes show up in [`FreqImage::data`].
+    pub fn as_interleaved_mut(&mut self) -> &mut [f64] {
+        // Safety: as for `as_interleaved`, and the borrow of `self` is exclusive.
+        unsafe { std::slice::from_raw_parts_mut(self.data.as_mut_ptr().cast::<f64>(), 2 * self.data.len()) }
+    }
+
+    /// Take ownership of `2 * width * height` interleaved doubles. The allocation is
+    /// reused as is unless its capacity is odd, in which case the values are copied.
+    ///
+    /// Errors with [`FreqError::LengthMismatch`] if `values` is the wrong length.
+    pub fn from_interleaved(width: u32, height: u32, values: Vec<f64>) -> Result<Self, FreqError> {
+        let expected = 2 * width as usize * height as usize;
+        if values.len() != expected {
+            return Err(FreqError::LengthMismatch { expected, got: values.len() });
+        }
+        let data = if values.capacity().is_multiple_of(2) {
+            let mut values = ManuallyDrop::new(values);
+            let (ptr, len, capacity) = (values.as_mut_ptr(), values.len(), values.capacity());
+            // Safety: the allocation came from a `Vec<f64>` of `capacity` doubles, which has
+            // the same size and alignment as `capacity / 2` complex values, and its first
+            // `len / 2` complex values are initialized. `values` is never used or dropped again.
+            unsafe { Vec::from_raw_parts(ptr.cast::<Complex<f64>>(), len / 2, capacity / 2) }
+        } else {
+            values.chunks_exact(2).map(|pair| Complex::new(pair[0], pair[1])).collect()
+        };
+        Ok(FreqImage { width, height, data })
+    }
+}
+
+#[test]
+fn test_interleaved_views() {
+    let mut fi = super::ramp(3, 2);
+    let view = fi.as_interleaved();
+    assert_eq!(view.len(), 12);
+    for (k, c) in fi.data.iter().enumerate() {
+        assert_eq!((view[2 * k], view[2 * k + 1]), (c.re, c.im));
+    }
+
+    fi.as_interleaved_mut()[7] = 42.0;
+    assert_eq!(fi.data[3], Complex::new(3.0, 42.0));
+}
+
+#[test]
+fn test_from_interleaved() {
+    let values: Vec<f64> = (0..12).map(|v| v as f64).collect();
+    let ptr = values.as_ptr();
+    let fi = FreqImage::from_interleaved(3, 2, values).unwrap();
+    assert_eq!(fi.data[4], Complex::new(8.0, 9.0));
+    assert_eq!(fi.as_interleaved().as_ptr(), ptr, "the allocation should be reused");
+
+    let mut odd = Vec::with_capacity(13);
+    odd.extend((0..12).map(|v| v as f64));
+    let copied = FreqImage::from_interleaved(3, 2, odd).unwrap();
+    assert_eq!(copied, fi);
+
+    assert!(matches!(
+        FreqImage::from_interleaved(3, 2, vec![0.0; 11]),
+        Err(FreqError::LengthMismatch { expected: 12, got: 11 })
+    ));
+}
@@ -0,0 +1,382 @@
+//! File formats beyond what the image crate offers.
+//!
+//! PNG export with text metadata, so a saved result records how it was made. The image
+//! crate's PNG encoder can't write text chunks, so these functions use the `png` crate
+//! directly; the files are ordinary 8-bit grayscale PNGs. With the `mmap` feature,
+//! [`open_mapped`] reads huge uncompressed images without decoding them.
+//! [`FreqImage::from_encoded_bytes`] and [`FreqImage::to_png_bytes`] work on encoded
+//! images in memory, for pipes and network buffers. [`stream_png_rows`] decodes a PNG a
+//! row at a time, so [`FreqImage::from_row_source`] can fill the complex buffer without a
+//! full-size decoded copy alongside it.
+
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Cursor};
+use std::path::Path;
+
+use image::{GrayImage, ImageOutputFormat};
+use png::{BitDepth, ColorType, DecodingError, EncodingError, Transformations};
+use rustfft::num_complex::Complex;
+
+use super::FreqImage;
+use crate::FreqError;
+
+#[cfg(feature = "mmap")]
+mod mapped;
+#[cfg(feature = "mmap")]
+pub use mapped::{open_mapped, MappedGray};
+
+impl FreqImage {
+    /// Decode an encoded image file held in memory, in any format the image crate reads,
+    /// into grayscale values in [0, 1] as [`FreqImage::open`] does. The format is sniffed
+    /// from the leading bytes; data that matches no known format fails with
+    /// [`FreqError::InvalidFormat`].
+    pub fn from_encoded_bytes(bytes: &[u8]) -> Result<Self, FreqError> {
+        let format = image::guess_format(bytes)
+            .map_err(|_| FreqError::InvalidFormat("image data in an unrecognized format".to_string()))?;
+        Ok(Self::from_image(image::load_from_memory_with_format(bytes, format)?))
+    }
+
+    /// Fill a `width × height` image from its rows of 8-bit gray pixels, top to bottom,
+    /// scaled into [0, 1] as [`FreqImage::from_image`] does. The complex buffer is the
+    /// only full-size allocation.
+    ///
+    /// Errors with [`FreqError::InvalidArgument`] if a row isn't `width` pixels long or
+    /// there aren't exactly `height` rows, and [`FreqError::TooLarge`] if the pixel count
+    /// doesn't fit in `usize`.
+    pub fn from_row_source(width: u32, height: u32, mut rows: impl Iterator<Item = Vec<u8>>) -> Result<Self, FreqError> {
+        let len = (width as usize).checked_mul(height as usize).ok_or(FreqError::TooLarge {
+            required: Self::estimated_memory(width, height),
+            limit: usize::MAX as u64,
+        })?;
+        let mut data = Vec::with_capacity(len);
+        for y in 0..height {
+            let row = rows
+                .next()
+                .ok_or_else(|| FreqError::InvalidArgument(format!("expected {} rows, got {}", height, y)))?;
+            if row.len() != width as usize {
+                return Err(FreqError::InvalidArgument(format!(
+                    "row {} has {} pixels, expected {}",
+                    y,
+                    row.len(),
+                    width
+                )));
+            }
+            data.extend(row.iter().map(|&pix| Complex::new(pix as f64 / 255.0, 0.0)));
+        }
+        let extra = rows.count();
+        if extra > 0 {
+            return Err(FreqError::InvalidArgument(format!("expected {} rows, got {}", height, height as usize + extra)));
+        }
+        Ok(FreqImage { width, height, data })
+    }
+
+    /// [`FreqImage::open`] for PNG files, decoding one row at a time with
+    /// [`stream_png_rows`] so that peak memory is little more than the complex buffer.
+    pub fn open_png_streaming<P: AsRef<Path>>(path: P) -> Result<Self, FreqError> {
+        let mut rows = stream_png_rows(path)?;
+        let (width, height) = (rows.width(), rows.height());
+        let fi = Self::from_row_source(width, height, rows.by_ref());
+        // A decoding error ends the rows early; report it rather than the short count.
+        match rows.take_error() {
+            Some(err) => Err(err),
+            None => fi,
+        }
+    }
+
+    /// [`FreqImage::to_image`] encoded as an 8-bit grayscale PNG.
+    pub fn to_png_bytes(&self) -> Result<Vec<u8>, FreqError> {
+        let mut bytes = Vec::new();
+        self.try_to_image()?.write_to(&mut Cursor::new(&mut bytes), ImageOutputFormat::Png)?;
+        Ok(bytes)
+    }
+}
+
+/// Write `img` as a grayscale PNG with one text chunk per `(keyword, text)` pair. ASCII
+/// text goes in a `tEXt` chunk and anything else in a UTF-8 `iTXt` chunk.
+///
+/// Keywords must be 1–79 Latin-1 characters, as the PNG format requires; other keywords
+/// fail with [`FreqError::InvalidFormat`].
+pub fn save_png_with_meta<P: AsRef<Path>>(img: &GrayImage, path: P, meta: &[(String, String)]) -> Result<(), FreqError> {
+    let mut encoder = png::Encoder::new(BufWriter::new(File::create(path)?), img.width(), img.height());
+    encoder.set_color(ColorType::Grayscale);
+    encoder.set_depth(BitDepth::Eight);
+    for (keyword, text) in meta {
+        if text.is_ascii() {
+            encoder.add_text_chunk(keyword.clone(), text.clone()).map_err(encoding_error)?;
+        } else {
+            encoder.add_itxt_chunk(keyword.clone(), text.clone()).map_err(encoding_error)?;
+        }
+    }
+    let mut writer = encoder.write_header().map_err(encoding_error)?;
+    writer.write_image_data(img.as_raw()).map_err(encoding_error)?;
+    writer.finish().map_err(encoding_error)
+}
+
+/// Read the text chunks stored before the image data of a PNG: `tEXt` chunks first, then
+/// compressed `zTXt`, then `iTXt`, each group in file order.
+pub fn read_png_meta<P: AsRef<Path>>(path: P) -> Result<Vec<(String, String)>, FreqError> {
+    let reader = png::Decoder::new(BufReader::new(File::open(path)?)).read_info().map_err(decoding_error)?;
+    let info = reader.info();
+    let mut meta: Vec<(String, String)> = info
+        .uncompressed_latin1_text
+        .iter()
+        .map(|chunk| (chunk.keyword.clone(), chunk.text.clone()))
+        .collect();
+    for chunk in &info.compressed_latin1_text {
+        meta.push((chunk.keyword.clone(), chunk.get_text().map_err(decoding_error)?));
+    }
+    for chunk in &info.utf8_text {
+        meta.push((chunk.keyword.clone(), chunk.get_text().map_err(decoding_error)?));
+    }
+    Ok(meta)
+}
+
+/// Open a PNG for decoding row by row. Rows come out as 8-bit gray pixels converted as
+/// the image crate's `into_luma8` does: palettes and low bit depths are expanded, 16-bit
+/// samples keep their high byte, alpha is dropped and color is weighted by Rec. 709
+/// luma. Interlaced PNGs can't be streamed and fail with [`FreqError::InvalidFormat`].
+pub fn stream_png_rows<P: AsRef<Path>>(path: P) -> Result<PngRows, FreqError> {
+    let mut decoder = png::Decoder::new(BufReader::new(File::open(path)?));
+    decoder.set_transformations(Transformations::EXPAND | Transformations::STRIP_16);
+    let reader = decoder.read_info().map_err(decoding_error)?;
+    if reader.info().interlaced {
+        return Err(FreqError::InvalidFormat("PNG: interlaced images can't be streamed".into()));
+    }
+    let color = reader.output_color_type().0;
+    Ok(PngRows { reader, color, error: None })
+}
+
+/// The rows of a PNG opened by [`stream_png_rows`], top to bottom. A decoding error ends
+/// the iteration early and is kept for [`PngRows::take_error`].
+pub struct PngRows {
+    reader: png::Reader<BufReader<File>>,
+    color: ColorType,
+    error: Option<FreqError>,
+}
+
+impl PngRows {
+    /// Image width in pixels.
+    pub fn width(&self) -> u32 {
+        self.reader.info().width
+    }
+
+    /// Image height in pixels.
+    pub fn height(&self) -> u32 {
+        self.reader.info().height
+    }
+
+    /// The error that ended the rows early, if any.
+    pub fn take_error(&mut self) -> Option<FreqError> {
+        self.error.take()
+    }
+}
+
+impl Iterator for PngRows {
+    type Item = Vec<u8>;
+
+    fn next(&mut self) -> Option<Vec<u8>> {
+        if self.error.is_some() {
+            return None;
+        }
+        match self.reader.next_row() {
+            Ok(Some(row)) => Some(gray_row(row.data(), self.color)),
+            Ok(None) => None,
+            Err(err) => {
+                self.error = Some(decoding_error(err));
+                None
+            }
+        }
+    }
+}
+
+/// One decoded 8-bit row as gray pixels, with the image crate's integer Rec. 709 weights.
+fn gray_row(data: &[u8], color: ColorType) -> Vec<u8> {
+    let luma = |p: &[u8]| ((2126 * p[0] as u32 + 7152 * p[1] as u32 + 722 * p[2] as u32) / 10000) as u8;
+    match color {
+        ColorType::Grayscale | ColorType::Indexed => data.to_vec(),
+        ColorType::GrayscaleAlpha => data.iter().step_by(2).copied().collect(),
+        ColorType::Rgb => data.chunks_exact(3).map(luma).collect(),
+        ColorType::Rgba => data.chunks_exact(4).map(luma).collect(),
+    }
+}
+
+/// The processing steps behind a saved image, in a form that round-trips through PNG
+/// text metadata.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FilterRecord {
+    /// Operations in the order they ran, e.g. `"low_pass"`. Names must not contain `;`.
+    pub operations: Vec<String>,
+    /// Cutoffs as fractions of the diagonal, as passed to the masks.
+    pub cutoffs: Vec<f64>,
+    /// Mask roll-off width, as a fraction of the diagonal.
+    pub smoothing: f64,
+    /// Version of freqshow that wrote the image.
+    pub version: String,
+}
+
+const OPERATIONS_KEY: &str = "freqshow:operations";
+const CUTOFFS_KEY: &str = "freqshow:cutoffs";
+const SMOOTHING_KEY: &str = "freqshow:smoothing";
+const VERSION_KEY: &str = "freqshow:version";
+
+impl FilterRecord {
+    /// A record stamped with the running crate version.
+    pub fn new(operations: &[&str], cutoffs: &[f64], smoothing: f64) -> Self {
+        FilterRecord {
+            operations: operations.iter().map(|op| op.to_string()).collect(),
+            cutoffs: cutoffs.to_vec(),
+            smoothing,
+            version: env!("CARGO_PKG_VERSION").to_string(),
+        }
+    }
+
+    /// Key/value pairs for [`save_png_with_meta`], all keyed under `freqshow:`. Numbers
+    /// are written so that they parse back exactly.
+    pub fn to_meta(&self) -> Vec<(String, String)> {
+        let cutoffs: Vec<String> = self.cutoffs.iter().map(|c| c.to_string()).collect();
+        vec![
+            (OPERATIONS_KEY.to_string(), self.operations.join(";")),
+            (CUTOFFS_KEY.to_string(), cutoffs.join(",")),
+            (SMOOTHING_KEY.to_string(), self.smoothing.to_string()),
+            (VERSION_KEY.to_string(), self.version.clone()),
+        ]
+    }
+
+    /// Rebuild a record from metadata such as [`read_png_meta`] returns, ignoring unrelated
+    /// keys. Fails with [`FreqError::InvalidFormat`] if a `freqshow:` key is missing or
+    /// doesn't parse.
+    pub fn from_meta(meta: &[(String, String)]) -> Result<Self, FreqError> {
+        let get = |key: &str| {
+            meta.iter()
+                .find(|(k, _)| k == key)
+                .map(|(_, v)| v.as_str())
+                .ok_or_else(|| FreqError::InvalidFormat(format!("PNG metadata: missing {}", key)))
+        };
+        let number = |text: &str| {
+            text.parse::<f64>()
+                .map_err(|_| FreqError::InvalidFormat(format!("PNG metadata: {:?} is not a number", text)))
+        };
+        let operations = get(OPERATIONS_KEY)?;
+        let cutoffs = get(CUTOFFS_KEY)?;
+        Ok(FilterRecord {
+            operations: operations.split(';').filter(|op| !op.is_empty()).map(str::to_string).collect(),
+            cutoffs: cutoffs.split(',').filter(|c| !c.is_empty()).map(number).collect::<Result<_, _>>()?,
+            smoothing: number(get(SMOOTHING_KEY)?)?,
+            version: get(VERSION_KEY)?.to_string(),
+        })
+    }
+}
+
+fn encoding_error(err: EncodingError) -> FreqError {
+    match err {
+        EncodingError::IoError(err) => FreqError::Io(err),
+        err => FreqError::InvalidFormat(format!("PNG: {}", err)),
+    }
+}
+
+pub(super) fn decoding_error(err: DecodingError) -> FreqError {
+    match err {
+        DecodingError::IoError(err) => FreqError::Io(err),
+        err => FreqError::InvalidFormat(format!("PNG: {}", err)),
+    }
+}
+
+#[cfg(test)]
+fn temp_png(name: &str) -> std::path::PathBuf {
+    std::env::temp_dir().join(format!("freqshow_{}_{}.png", name, std::process::id()))
+}
+
+#[test]
+fn test_png_meta_roundtrip() {
+    let img = GrayImage::from_fn(7, 5, |x, y| image::Luma([(x * 30 + y) as u8]));
+    let meta = vec![
+        ("Software".to_string(), "freqshow".to_string()),
+        ("freqshow:note".to_string(), "cutoff = 0.1; smoothing = 0.02".to_string()),
+        ("Comment".to_string(), "façade — Fourier".to_string()),
+    ];
+    let path = temp_png("meta");
+    save_png_with_meta(&img, &path, &meta).unwrap();
+    let read = read_png_meta(&path).unwrap();
+    let decoded = image::open(&path).unwrap().into_luma8();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(read, meta);
+    assert_eq!(decoded, img);
+}
+
+#[test]
+fn test_filter_record_roundtrip() {
+    let record = FilterRecord::new(&["fft_forward", "low_pass", "fft_inverse"], &[0.1, 1.0 / 3.0], 0.02);
+    assert_eq!(record.version, env!("CARGO_PKG_VERSION"));
+    let path = temp_png("record");
+    save_png_with_meta(&GrayImage::new(4, 4), &path, &record.to_meta()).unwrap();
+    let read = read_png_meta(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(FilterRecord::from_meta(&read).unwrap(), record);
+
+    let empty = FilterRecord::new(&[], &[], 0.0);
+    assert_eq!(FilterRecord::from_meta(&empty.to_meta()).unwrap(), empty);
+    assert!(matches!(FilterRecord::from_meta(&read[1..]), Err(FreqError::InvalidFormat(_))));
+}
+
+#[test]
+fn test_encoded_bytes_roundtrip() {
+    let img = GrayImage::from_fn(9, 4, |x, y| image::Luma([(x * 28 + y * 3) as u8]));
+    let fi = FreqImage::from_image(image::DynamicImage::ImageLuma8(img.clone()));
+    let bytes = fi.to_png_bytes().unwrap();
+    assert!(bytes.starts_with(b"\x89PNG"));
+    let decoded = FreqImage::from_encoded_bytes(&bytes).unwrap();
+    assert_eq!(decoded, fi);
+    assert_eq!(decoded.to_image(), img);
+
+    assert!(matches!(FreqImage::from_encoded_bytes(b"not an image"), Err(FreqError::InvalidFormat(_))));
+    assert!(FreqImage::from_encoded_bytes(&bytes[..bytes.len() / 2]).is_err());
+}
+
+#[test]
+fn test_png_meta_bad_keyword() {
+    let path = temp_png("keyword");
+    let meta = vec![(String::new(), "no keyword".to_string())];
+    assert!(matches!(save_png_with_meta(&GrayImage::new(2, 2), &path, &meta), Err(FreqError::InvalidFormat(_))));
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn test_from_row_source() {
+    let img = GrayImage::from_fn(6, 4, |x, y| image::Luma([(x * 40 + y * 7) as u8]));
+    let rows = || img.rows().map(|row| row.map(|p| p[0]).collect::<Vec<u8>>());
+    let fi = FreqImage::from_row_source(6, 4, rows()).unwrap();
+    assert_eq!(fi, FreqImage::from_image(image::DynamicImage::ImageLuma8(img.clone())));
+
+    let error = |result: Result<FreqImage, FreqError>| match result {
+        Err(FreqError::InvalidArgument(msg)) => msg,
+        other => panic!("{:?}", other),
+    };
+    let mut short_row: Vec<Vec<u8>> = rows().collect();
+    short_row[2].pop();
+    assert_eq!(error(FreqImage::from_row_source(6, 4, short_row.into_iter())), "row 2 has 5 pixels, expected 6");
+    assert_eq!(error(FreqImage::from_row_source(6, 4, rows().take(3))), "expected 4 rows, got 3");
+    assert_eq!(error(FreqImage::from_row_source(6, 4, rows().chain(rows()))), "expected 4 rows, got 8");
+}
+
+#[test]
+fn test_open_png_streaming() {
+    let gray = GrayImage::from_fn(9, 5, |x, y| image::Luma([(x * 25 + y * 9) as u8]));
+    let rgba = image::RgbaImage::from_fn(9, 5, |x, y| image::Rgba([(x * 25) as u8, (y * 50) as u8, 200, 17]));
+    for (name, img) in [("gray", image::DynamicImage::ImageLuma8(gray)), ("rgba", image::DynamicImage::ImageRgba8(rgba))] {
+        let path = temp_png(&format!("stream_{}", name));
+        let mut bytes = Vec::new();
+        img.write_to(&mut Cursor::new(&mut bytes), ImageOutputFormat::Png).unwrap();
+        std::fs::write(&path, &bytes).unwrap();
+        let streamed = FreqImage::open_png_streaming(&path);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(streamed.unwrap(), FreqImage::from_image(img), "{}", name);
+    }
+
+    let path = temp_png("stream_truncated");
+    let bytes = FreqImage::from_image(image::DynamicImage::ImageLuma8(GrayImage::new(40, 30))).to_png_bytes().unwrap();
+    std::fs::write(&path, &bytes[..bytes.len() / 2]).unwrap();
+    let truncated = FreqImage::open_png_streaming(&path);
+    std::fs::remove_file(&path).unwrap();
+    assert!(truncated.is_err());
+}
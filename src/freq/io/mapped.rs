@@ -0,0 +1,323 @@
+//! Memory-mapped access to uncompressed 8-bit grayscale files too large to decode whole.
+
+use std::fs::File;
+use std::ops::Range;
+use std::path::Path;
+
+use memmap2::Mmap;
+use rustfft::num_complex::Complex;
+
+use crate::{FreqError, FreqImage};
+
+/// An 8-bit grayscale image whose pixels stay in a memory-mapped file, see
+/// [`open_mapped`]. Rows are read straight from the mapping, so only the pages touched
+/// are ever loaded.
+pub struct MappedGray {
+    map: Mmap,
+    width: u32,
+    height: u32,
+    /// Byte offset of the first row of each strip; a PGM is a single strip.
+    strips: Vec<usize>,
+    rows_per_strip: u32,
+}
+
+/// Map a binary (P5) PGM or an uncompressed, strip-organized 8-bit grayscale TIFF or
+/// BigTIFF without decoding it. Anything else, including compressed or tiled TIFFs and
+/// 16-bit data, fails with [`FreqError::InvalidFormat`]; decode those with
+/// [`FreqImage::open`] instead.
+pub fn open_mapped<P: AsRef<Path>>(path: P) -> Result<MappedGray, FreqError> {
+    let file = File::open(path)?;
+    // Safety: the mapping is only read. As with any memory map, the file must not be
+    // truncated while it is mapped.
+    let map = unsafe { Mmap::map(&file)? };
+    let (width, height, strips, rows_per_strip) = if map.starts_with(b"P5") {
+        parse_pgm(&map)?
+    } else if map.starts_with(b"II") || map.starts_with(b"MM") {
+        parse_tiff(&map)?
+    } else {
+        return Err(unsupported("not a binary PGM or TIFF file"));
+    };
+
+    let row_bytes = width as usize;
+    for (i, &start) in strips.iter().enumerate() {
+        let rows = rows_per_strip.min(height.saturating_sub(i as u32 * rows_per_strip)) as usize;
+        if start.saturating_add(rows * row_bytes) > map.len() {
+            return Err(FreqError::InvalidFormat("mapped image: pixel data runs past the end of the file".into()));
+        }
+    }
+    if (strips.len() as u64) * (rows_per_strip as u64) < height as u64 {
+        return Err(FreqError::InvalidFormat("mapped image: too few strips for the image height".into()));
+    }
+    Ok(MappedGray { map, width, height, strips, rows_per_strip })
+}
+
+impl MappedGray {
+    /// Image width in pixels.
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Image height in pixels.
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// The pixels of row `y`.
+    ///
+    /// # Panics
+    /// Panics if `y` is not below the height.
+    pub fn row(&self, y: u32) -> &[u8] {
+        assert!(y < self.height, "row {} out of bounds for {} rows", y, self.height);
+        let start = self.strips[(y / self.rows_per_strip) as usize]
+            + (y % self.rows_per_strip) as usize * self.width as usize;
+        &self.map[start..start + self.width as usize]
+    }
+
+    /// The rows in `range`, top to bottom.
+    ///
+    /// # Panics
+    /// Panics if the range extends past the last row.
+    pub fn rows(&self, range: Range<u32>) -> impl Iterator<Item = &[u8]> + '_ {
+        assert!(range.end <= self.height, "rows {:?} out of bounds for {} rows", range, self.height);
+        range.map(move |y| self.row(y))
+    }
+}
+
+impl FreqImage {
+    /// Load the `width × height` region of `mapped` whose top-left corner is `(x, y)`,
+    /// scaled into [0, 1] like [`FreqImage::open`]. Only the rows of the region are read.
+    ///
+    /// # Panics
+    /// Panics if the region extends past the image bounds.
+    pub fn from_mapped_region(mapped: &MappedGray, x: u32, y: u32, width: u32, height: u32) -> FreqImage {
+        assert!(
+            x as u64 + width as u64 <= mapped.width as u64 && y as u64 + height as u64 <= mapped.height as u64,
+            "region {}x{} at ({}, {}) out of bounds for {}x{} mapped image",
+            width, height, x, y, mapped.width, mapped.height
+        );
+        let data = mapped
+            .rows(y..y + height)
+            .flat_map(|row| &row[x as usize..(x + width) as usize])
+            .map(|&pix| Complex::new(pix as f64 / 255.0, 0.0))
+            .collect();
+        FreqImage { width, height, data }
+    }
+}
+
+type Layout = (u32, u32, Vec<usize>, u32);
+
+fn unsupported(why: &str) -> FreqError {
+    FreqError::InvalidFormat(format!("cannot map image: {}; decode it with FreqImage::open instead", why))
+}
+
+/// Header fields are separated by whitespace and `#` comments; a single whitespace
+/// byte follows the maximum value, then the pixels.
+fn parse_pgm(data: &[u8]) -> Result<Layout, FreqError> {
+    let malformed = || FreqError::InvalidFormat("PGM: malformed header".into());
+    let mut pos = 2;
+    let mut fields = [0u32; 3];
+    for field in fields.iter_mut() {
+        loop {
+            match data.get(pos) {
+                Some(b) if b.is_ascii_whitespace() => pos += 1,
+                Some(b'#') => {
+                    while data.get(pos).is_some_and(|&b| b != b'\n') {
+                        pos += 1;
+                    }
+                }
+                _ => break,
+            }
+        }
+        let start = pos;
+        while data.get(pos).is_some_and(u8::is_ascii_digit) {
+            pos += 1;
+        }
+        *field = std::str::from_utf8(&data[start..pos]).ok().and_then(|s| s.parse().ok()).ok_or_else(malformed)?;
+    }
+    if !data.get(pos).is_some_and(u8::is_ascii_whitespace) {
+        return Err(malformed());
+    }
+    let [width, height, max] = fields;
+    if max > 255 {
+        return Err(unsupported("16-bit PGM"));
+    }
+    Ok((width, height, vec![pos + 1], height.max(1)))
+}
+
+/// Byte-order-aware reads from a TIFF or BigTIFF file.
+struct Tiff<'a> {
+    data: &'a [u8],
+    big_endian: bool,
+    big_tiff: bool,
+}
+
+impl Tiff<'_> {
+    fn uint(&self, at: usize, len: usize) -> Result<u64, FreqError> {
+        let bytes = at
+            .checked_add(len)
+            .and_then(|end| self.data.get(at..end))
+            .ok_or_else(|| FreqError::InvalidFormat("TIFF: truncated file".into()))?;
+        let fold = |acc: u64, &b: &u8| (acc << 8) | b as u64;
+        Ok(if self.big_endian { bytes.iter().fold(0, fold) } else { bytes.iter().rev().fold(0, fold) })
+    }
+
+    /// The values of every entry in the IFD at `ifd`, by tag.
+    fn entries(&self, ifd: usize) -> Result<Vec<(u16, Vec<u64>)>, FreqError> {
+        let (count_len, entry_len, field_len) = if self.big_tiff { (8, 20, 8) } else { (2, 12, 4) };
+        let count = self.uint(ifd, count_len)? as usize;
+        let mut entries = Vec::new();
+        for i in 0..count {
+            let entry = ifd + count_len + i * entry_len;
+            let tag = self.uint(entry, 2)? as u16;
+            let size = match self.uint(entry + 2, 2)? {
+                1 => 1,
+                3 => 2,
+                4 => 4,
+                16 => 8,
+                _ => continue,
+            };
+            let n = self.uint(entry + 4, field_len)? as usize;
+            let field = entry + 4 + field_len;
+            let start = if n.saturating_mul(size) <= field_len { field } else { self.uint(field, field_len)? as usize };
+            let values = (0..n).map(|k| self.uint(start + k * size, size)).collect::<Result<_, _>>()?;
+            entries.push((tag, values));
+        }
+        Ok(entries)
+    }
+}
+
+fn parse_tiff(data: &[u8]) -> Result<Layout, FreqError> {
+    let mut tiff = Tiff { data, big_endian: data.starts_with(b"MM"), big_tiff: false };
+    let ifd = match tiff.uint(2, 2)? {
+        42 => tiff.uint(4, 4)?,
+        43 => {
+            tiff.big_tiff = true;
+            tiff.uint(8, 8)?
+        }
+        _ => return Err(FreqError::InvalidFormat("TIFF: bad magic number".into())),
+    };
+    let entries = tiff.entries(ifd as usize)?;
+    let get = |tag: u16| entries.iter().find(|(t, _)| *t == tag).map(|(_, v)| v.as_slice());
+    let single = |tag: u16, default: Option<u64>| match get(tag) {
+        Some([value, ..]) => Ok(*value),
+        _ => default.ok_or_else(|| FreqError::InvalidFormat(format!("TIFF: missing tag {}", tag))),
+    };
+
+    let compression = single(259, Some(1))?;
+    if compression != 1 {
+        return Err(unsupported(&format!("TIFF compression {} is not supported", compression)));
+    }
+    if get(322).is_some() {
+        return Err(unsupported("tiled TIFF"));
+    }
+    if single(277, Some(1))? != 1 || single(258, Some(1))? != 8 || single(262, None)? != 1 {
+        return Err(unsupported("only 8-bit single-channel BlackIsZero TIFFs can be mapped"));
+    }
+    let width = u32::try_from(single(256, None)?).map_err(|_| unsupported("TIFF width out of range"))?;
+    let height = u32::try_from(single(257, None)?).map_err(|_| unsupported("TIFF height out of range"))?;
+    let rows_per_strip = single(278, Some(u32::MAX as u64))?.clamp(1, height.max(1) as u64) as u32;
+    let strips = get(273)
+        .ok_or_else(|| FreqError::InvalidFormat("TIFF: missing strip offsets".into()))?
+        .iter()
+        .map(|&offset| offset as usize)
+        .collect();
+    Ok((width, height, strips, rows_per_strip))
+}
+
+#[cfg(test)]
+fn temp_file(name: &str, bytes: &[u8]) -> std::path::PathBuf {
+    let path = std::env::temp_dir().join(format!("freqshow_mapped_{}_{}", std::process::id(), name));
+    std::fs::write(&path, bytes).unwrap();
+    path
+}
+
+#[cfg(test)]
+fn pixels(width: u32, height: u32) -> Vec<u8> {
+    (0..width * height).map(|i| (i * 7 % 251) as u8).collect()
+}
+
+#[test]
+fn test_mapped_pgm_matches_open() {
+    let (width, height) = (23, 17);
+    let mut bytes = format!("P5\n# generated\n{} {}\n255\n", width, height).into_bytes();
+    bytes.extend(pixels(width, height));
+    let path = temp_file("fixture.pgm", &bytes);
+
+    let mapped = open_mapped(&path).unwrap();
+    let opened = FreqImage::open(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!((mapped.width(), mapped.height()), (width, height));
+    assert_eq!(FreqImage::from_mapped_region(&mapped, 0, 0, width, height), opened);
+    assert_eq!(FreqImage::from_mapped_region(&mapped, 5, 3, 11, 9), opened.crop(5, 3, 11, 9));
+    assert_eq!(mapped.rows(2..4).count(), 2);
+}
+
+/// A little-endian TIFF (or BigTIFF) of `pixels`, with strips of two rows stored in
+/// reverse order so that the offsets matter. `compression` goes in tag 259.
+#[cfg(test)]
+fn strip_tiff(big: bool, width: u32, height: u32, compression: u16) -> Vec<u8> {
+    let pixels = pixels(width, height);
+    let strip_count = height.div_ceil(2) as usize;
+    let (field, entry_len, count_len) = if big { (8, 20, 8) } else { (4, 12, 2) };
+    let entries = [(256, width as u64), (257, height as u64), (258, 8), (259, compression as u64), (262, 1), (273, 0), (277, 1), (278, 2)];
+    let header_len = if big { 16 } else { 8 };
+    let offsets_at = header_len + count_len + entries.len() * entry_len + field;
+    let data_at = offsets_at + strip_count * field;
+
+    let put = |bytes: &mut Vec<u8>, value: u64, len: usize| bytes.extend(&value.to_le_bytes()[..len]);
+    let mut bytes = b"II".to_vec();
+    if big {
+        bytes.extend([43, 0, 8, 0, 0, 0]);
+    } else {
+        bytes.extend([42, 0]);
+    }
+    put(&mut bytes, header_len as u64, field);
+    put(&mut bytes, entries.len() as u64, count_len);
+    for (tag, value) in entries {
+        let (kind, count, value) = if tag == 273 { (if big { 16 } else { 4 }, strip_count, offsets_at as u64) } else { (3, 1, value) };
+        put(&mut bytes, tag, 2);
+        put(&mut bytes, kind, 2);
+        put(&mut bytes, count as u64, field);
+        put(&mut bytes, value, field);
+    }
+    put(&mut bytes, 0, field);
+    let strips: Vec<&[u8]> = pixels.chunks(2 * width as usize).collect();
+    let mut start = data_at + pixels.len();
+    for strip in &strips {
+        start -= strip.len();
+        put(&mut bytes, start as u64, field);
+    }
+    for strip in strips.iter().rev() {
+        bytes.extend(*strip);
+    }
+    bytes
+}
+
+#[test]
+fn test_mapped_tiff_strips() {
+    for big in [false, true] {
+        let path = temp_file(&format!("strips_{}.tif", big), &strip_tiff(big, 6, 5, 1));
+        let mapped = open_mapped(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        let rows: Vec<u8> = mapped.rows(0..5).flatten().copied().collect();
+        assert_eq!(rows, pixels(6, 5), "BigTIFF: {}", big);
+    }
+
+    // LZW-compressed data is rejected with a pointer to open().
+    let path = temp_file("lzw.tif", &strip_tiff(false, 6, 5, 5));
+    let err = open_mapped(&path).err().unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert!(matches!(&err, FreqError::InvalidFormat(msg) if msg.contains("FreqImage::open")), "{}", err);
+}
+
+#[test]
+fn test_mapped_rejects_other_formats() {
+    let path = temp_file("not_mapped.png", b"\x89PNG\r\n\x1a\n");
+    let err = open_mapped(&path).err().unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert!(matches!(err, FreqError::InvalidFormat(_)));
+
+    let path = temp_file("deep.pgm", b"P5 2 2 65535\n\0\0\0\0\0\0\0\0");
+    assert!(open_mapped(&path).is_err());
+    std::fs::remove_file(&path).unwrap();
+}
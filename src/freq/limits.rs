@@ -0,0 +1,118 @@
+//! Loading large images within memory and resolution limits.
+
+use std::path::Path;
+
+use image::imageops::FilterType;
+use image::io::Reader as ImageReader;
+use image::ImageFormat;
+use rustfft::num_complex::Complex;
+
+use super::io::decoding_error;
+use super::FreqImage;
+use crate::FreqError;
+
+/// Bytes per coefficient of the complex buffer.
+const COEFFICIENT_BYTES: u64 = std::mem::size_of::<Complex<f64>>() as u64;
+
+impl FreqImage {
+    /// Peak bytes needed to hold and transform a `width × height` image: the complex
+    /// buffer, the equal-sized transposed copy [`FreqImage::fft_forward`] makes, and FFT
+    /// scratch for one row or column. Decoding needs less than this (at most 5 bytes per
+    /// pixel for 8-bit sources). Saturates at `u64::MAX` rather than overflowing.
+    pub fn estimated_memory(width: u32, height: u32) -> u64 {
+        let pixels = width as u64 * height as u64;
+        let buffers = pixels.saturating_mul(2 * COEFFICIENT_BYTES);
+        buffers.saturating_add(width.max(height) as u64 * COEFFICIENT_BYTES)
+    }
+
+    /// [`FreqImage::open`], but read only the header first and fail with
+    /// [`FreqError::TooLarge`] without decoding if [`FreqImage::estimated_memory`] exceeds
+    /// `max_bytes`, or if the pixel count doesn't fit in `usize` on this target. For PNG
+    /// only the IHDR chunk is read, so a truncated file is still rejected by size.
+    pub fn open_with_limit<P: AsRef<Path>>(path: P, max_bytes: u64) -> Result<Self, FreqError> {
+        let reader = ImageReader::open(&path)?.with_guessed_format()?;
+        let (width, height) = if reader.format() == Some(ImageFormat::Png) {
+            let mut decoder = png::Decoder::new(reader.into_inner());
+            let info = decoder.read_header_info().map_err(decoding_error)?;
+            (info.width, info.height)
+        } else {
+            reader.into_dimensions()?
+        };
+        let required = Self::estimated_memory(width, height);
+        if required > max_bytes || (width as usize).checked_mul(height as usize).is_none() {
+            return Err(FreqError::TooLarge { required, limit: max_bytes });
+        }
+        Ok(Self::open(path)?)
+    }
+
+    /// Open an image, shrinking it with a Catmull-Rom filter first if either side exceeds
+    /// `max_dim` so that the longer side becomes `max_dim`, keeping the aspect ratio.
+    /// Returns the image and the scale applied (1.0 if it already fit). A `max_dim` of
+    /// zero is an [`FreqError::InvalidArgument`].
+    pub fn open_scaled<P: AsRef<Path>>(path: P, max_dim: u32) -> Result<(Self, f64), FreqError> {
+        if max_dim == 0 {
+            return Err(FreqError::InvalidArgument("max_dim must be positive".into()));
+        }
+        let img = image::open(path)?;
+        let (width, height) = (img.width(), img.height());
+        let longest = width.max(height);
+        if longest <= max_dim {
+            return Ok((Self::from_image(img), 1.0));
+        }
+        let scale = max_dim as f64 / longest as f64;
+        let fit = |n: u32| ((n as f64 * scale).round() as u32).clamp(1, max_dim);
+        let resized = img.resize_exact(fit(width), fit(height), FilterType::CatmullRom);
+        Ok((Self::from_image(resized), scale))
+    }
+}
+
+#[test]
+fn test_estimated_memory() {
+    assert_eq!(FreqImage::estimated_memory(0, 0), 0);
+    assert_eq!(FreqImage::estimated_memory(4, 2), 4 * 2 * 32 + 4 * 16);
+    assert_eq!(FreqImage::estimated_memory(u32::MAX, u32::MAX), u64::MAX);
+}
+
+#[test]
+fn test_open_with_limit() {
+    // A PNG signature and IHDR chunk declaring 100000x80000, with no pixel data at all.
+    let mut header = vec![0x89, b'P', b'N', b'G', 0x0d, 0x0a, 0x1a, 0x0a, 0, 0, 0, 13];
+    let mut chunk = b"IHDR".to_vec();
+    chunk.extend(100_000u32.to_be_bytes());
+    chunk.extend(80_000u32.to_be_bytes());
+    chunk.extend([8, 0, 0, 0, 0]);
+    let crc = chunk.iter().fold(!0u32, |crc, &b| {
+        (0..8).fold(crc ^ b as u32, |c, _| if c & 1 == 1 { (c >> 1) ^ 0xedb8_8320 } else { c >> 1 })
+    });
+    header.extend(&chunk);
+    header.extend((!crc).to_be_bytes());
+    let path = std::env::temp_dir().join(format!("freqshow_limits_{}.png", std::process::id()));
+    std::fs::write(&path, &header).unwrap();
+
+    let result = FreqImage::open_with_limit(&path, 1 << 30);
+    std::fs::remove_file(&path).unwrap();
+    match result {
+        Err(FreqError::TooLarge { required, limit }) => {
+            assert_eq!(required, FreqImage::estimated_memory(100_000, 80_000));
+            assert_eq!(limit, 1 << 30);
+        }
+        other => panic!("expected TooLarge, got {:?}", other.map(|fi| (fi.width, fi.height))),
+    }
+
+    let fi = FreqImage::open_with_limit("img/mandrill.jpg", 1 << 30).unwrap();
+    assert_eq!(fi, FreqImage::open("img/mandrill.jpg").unwrap());
+}
+
+#[test]
+fn test_open_scaled() {
+    let path = std::env::temp_dir().join(format!("freqshow_scaled_{}.png", std::process::id()));
+    image::GrayImage::from_fn(512, 256, |x, y| image::Luma([(x ^ y) as u8])).save(&path).unwrap();
+
+    let (small, scale) = FreqImage::open_scaled(&path, 128).unwrap();
+    assert_eq!((small.width, small.height, scale), (128, 64, 0.25));
+    let (full, scale) = FreqImage::open_scaled(&path, 4096).unwrap();
+    assert_eq!((full.width, full.height, scale), (512, 256, 1.0));
+    assert_eq!(full, FreqImage::open(&path).unwrap());
+    assert!(matches!(FreqImage::open_scaled(&path, 0), Err(FreqError::InvalidArgument(_))));
+    std::fs::remove_file(&path).unwrap();
+}
@@ -0,0 +1,100 @@
+//! Log-polar resampling of a centered spectrum.
+
+use std::f64::consts::PI;
+
+use rustfft::num_complex::Complex;
+
+use super::FreqImage;
+
+/// How to sample between grid points.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Interpolation {
+    /// Take the closest sample.
+    Nearest,
+    /// Blend the four surrounding samples.
+    #[default]
+    Bilinear,
+}
+
+impl FreqImage {
+    /// Resample the magnitude of this centered (fftshifted) spectrum onto an
+    /// `angular_bins × radial_bins` log-polar grid, where rotating the image becomes a
+    /// circular shift along x and scaling it a shift along y.
+    ///
+    /// Column `i` is the angle `θ = 360° · i / angular_bins`, counter-clockwise from +u
+    /// as displayed (so `v` grows upward, against the row order). Row `j` is the radius
+    /// `r = r_min · (r_max / r_min)^(j / radial_bins)` in cycles per pixel, with
+    /// `r_min = 1 / min(width, height)` (one bin) and `r_max = 0.5` (Nyquist), so the last
+    /// row stays just inside it. The point `(r, θ)` is bin `(width/2 + r·cos θ · width,
+    /// height/2 - r·sin θ · height)`; points off the spectrum read as zero.
+    pub fn to_log_polar(&self, radial_bins: u32, angular_bins: u32, interp: Interpolation) -> FreqImage {
+        let (w, h) = (self.width as f64, self.height as f64);
+        let (cx, cy) = ((self.width / 2) as f64, (self.height / 2) as f64);
+        let r_min = 1.0 / self.width.min(self.height).max(1) as f64;
+        let growth = (0.5 / r_min).ln() / radial_bins.max(1) as f64;
+        let magnitude = FreqImage {
+            width: self.width,
+            height: self.height,
+            data: self.data.iter().map(|c| Complex::new(c.norm(), 0.0)).collect(),
+        };
+
+        let mut out = FreqImage {
+            width: angular_bins,
+            height: radial_bins,
+            data: Vec::with_capacity(angular_bins as usize * radial_bins as usize),
+        };
+        for j in 0..radial_bins {
+            let r = r_min * (growth * j as f64).exp();
+            for i in 0..angular_bins {
+                let theta = 2.0 * PI * i as f64 / angular_bins as f64;
+                let (kx, ky) = (cx + r * theta.cos() * w, cy - r * theta.sin() * h);
+                let sample = match interp {
+                    Interpolation::Nearest if kx >= -0.5 && ky >= -0.5 => {
+                        magnitude.get(kx.round() as u32, ky.round() as u32)
+                    }
+                    Interpolation::Nearest => None,
+                    Interpolation::Bilinear => magnitude.sample_bilinear(kx, ky),
+                };
+                out.data.push(sample.unwrap_or_default());
+            }
+        }
+        out
+    }
+}
+
+#[test]
+fn test_log_polar_rotation_is_angular_shift() {
+    let image = super::synth::spectral_noise(64, 64, 1.0, 11);
+    let centered = |fi: &FreqImage| {
+        let mut s = fi.clone();
+        s.fft_forward();
+        s.fftshift();
+        s
+    };
+    for interp in [Interpolation::Nearest, Interpolation::Bilinear] {
+        let lp = centered(&image).to_log_polar(32, 64, interp);
+        let turned = centered(&image.rotate90()).to_log_polar(32, 64, interp);
+        assert_eq!((lp.width, lp.height), (64, 32));
+        // A clockwise quarter turn moves every orientation back by 90°, a quarter of the columns.
+        let scale = lp.data.iter().map(|c| c.re).fold(0.0, f64::max);
+        for (x, y, c) in turned.enumerate_pixels() {
+            assert!((c.re - lp[((x + 16) % 64, y)].re).abs() < 1e-6 * scale, "{:?} at ({}, {})", interp, x, y);
+        }
+    }
+}
+
+#[test]
+fn test_log_polar_grid() {
+    let mut spot = FreqImage {
+        width: 16,
+        height: 16,
+        data: vec![Complex::new(0.0, 0.0); 256],
+    };
+    // Two bins right of center: r = 2/16 cycles per pixel, θ = 0.
+    spot[(10, 8)] = Complex::new(0.0, 3.0);
+    let lp = spot.to_log_polar(3, 8, Interpolation::Nearest);
+    // r_j = (1/16) · 8^(j/3), so r = 1/8 is row 1.
+    assert_eq!(lp[(0, 1)].re, 3.0);
+    assert_eq!(lp.data.iter().filter(|c| c.re != 0.0).count(), 1);
+}
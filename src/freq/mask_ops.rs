@@ -0,0 +1,120 @@
+//! Morphology and blurring for masks, such as growing the blocked spots of a notch mask.
+//!
+//! Masks are row-major `width × height` weight buffers as passed to
+//! [`FreqImage::apply_filter`]. Pixels beyond the edges repeat the nearest edge pixel.
+
+use rustfft::num_complex::Complex;
+
+use super::{Boundary, FreqImage, Kernel2D};
+
+/// Grayscale dilation: every weight becomes the largest within `radius` pixels (a disc),
+/// growing the regions where the mask is high. Radius 0 returns the mask unchanged.
+///
+/// # Panics
+/// Panics if `mask` does not hold `width * height` weights.
+pub fn dilate(mask: &[f64], width: usize, height: usize, radius: u32) -> Vec<f64> {
+    morph(mask, width, height, radius, f64::max)
+}
+
+/// Grayscale erosion: every weight becomes the smallest within `radius` pixels (a disc).
+/// For a pass/block mask this grows the blocked (low) regions, e.g. widening notches.
+///
+/// # Panics
+/// Panics if `mask` does not hold `width * height` weights.
+pub fn erode(mask: &[f64], width: usize, height: usize, radius: u32) -> Vec<f64> {
+    morph(mask, width, height, radius, f64::min)
+}
+
+/// Blur the mask with a Gaussian of standard deviation `sigma` pixels to soften hard edges,
+/// which reduces ringing when the mask is applied.
+///
+/// # Panics
+/// Panics if `mask` does not hold `width * height` weights, or if `sigma` is not positive.
+pub fn feather(mask: &[f64], width: usize, height: usize, sigma: f64) -> Vec<f64> {
+    check_len(mask, width, height);
+    let fi = FreqImage {
+        width: width as u32,
+        height: height as u32,
+        data: mask.iter().map(|&m| Complex::new(m, 0.0)).collect(),
+    };
+    fi.convolve_with(&Kernel2D::gaussian(sigma), Boundary::Clamp)
+        .data
+        .iter()
+        .map(|c| c.re)
+        .collect()
+}
+
+/// Combine every weight with its neighbours inside a disc of `radius` using `pick`.
+/// Clamping out-of-range neighbours to the edge only revisits pixels already in the disc,
+/// so they are simply skipped.
+fn morph(mask: &[f64], width: usize, height: usize, radius: u32, pick: fn(f64, f64) -> f64) -> Vec<f64> {
+    check_len(mask, width, height);
+    let r = radius as i64;
+    let offsets: Vec<(i64, i64)> = (-r..=r)
+        .flat_map(|dy| (-r..=r).map(move |dx| (dx, dy)))
+        .filter(|(dx, dy)| dx * dx + dy * dy <= r * r)
+        .collect();
+    let (w, h) = (width as i64, height as i64);
+    let mut out = Vec::with_capacity(mask.len());
+    for y in 0..h {
+        for x in 0..w {
+            let value = offsets
+                .iter()
+                .map(|(dx, dy)| (x + dx, y + dy))
+                .filter(|(nx, ny)| (0..w).contains(nx) && (0..h).contains(ny))
+                .map(|(nx, ny)| mask[(ny * w + nx) as usize])
+                .fold(mask[(y * w + x) as usize], pick);
+            out.push(value);
+        }
+    }
+    out
+}
+
+fn check_len(mask: &[f64], width: usize, height: usize) {
+    if mask.len() != width * height {
+        panic!("mask has {} weights for a {}x{} image", mask.len(), width, height);
+    }
+}
+
+#[test]
+fn test_erode_grows_notch() {
+    let (w, h) = (21, 17);
+    let mut mask = vec![1.0; w * h];
+    mask[8 * w + 10] = 0.0;
+    let eroded = erode(&mask, w, h, 3);
+    for y in 0..h {
+        for x in 0..w {
+            let (dx, dy) = (x as i64 - 10, y as i64 - 8);
+            let expected = if dx * dx + dy * dy <= 9 { 0.0 } else { 1.0 };
+            assert_eq!(eroded[y * w + x], expected, "({}, {})", x, y);
+        }
+    }
+    // Dilating the same mask closes the notch again, and radius 0 is a no-op.
+    assert_eq!(dilate(&mask, w, h, 1), vec![1.0; w * h]);
+    assert_eq!(erode(&mask, w, h, 0), mask);
+
+    // A notch on the edge is cut off by the border rather than wrapping.
+    let mut corner = vec![1.0; w * h];
+    corner[0] = 0.0;
+    let eroded = erode(&corner, w, h, 2);
+    assert_eq!(eroded.iter().filter(|&&m| m == 0.0).count(), 6);
+    assert_eq!(eroded[w * h - 1], 1.0);
+}
+
+#[test]
+fn test_feather_preserves_mean() {
+    let fi = FreqImage { width: 64, height: 48, data: vec![Complex::new(0.0, 0.0); 64 * 48] };
+    let mask = fi.low_pass_mask(0.15, 0.0);
+    let mean = |m: &[f64]| m.iter().sum::<f64>() / m.len() as f64;
+    let soft = feather(&mask, 64, 48, 2.0);
+    assert!((mean(&soft) - mean(&mask)).abs() < 0.01 * mean(&mask));
+    // The hard edge now has intermediate values.
+    assert!(soft.iter().any(|&m| m > 0.1 && m < 0.9));
+    assert!(soft.iter().all(|&m| (-1e-9..=1.0 + 1e-9).contains(&m)));
+}
+
+#[test]
+#[should_panic(expected = "mask has 5 weights for a 2x2 image")]
+fn test_mask_ops_length() {
+    dilate(&[1.0; 5], 2, 2, 1);
+}
@@ -0,0 +1,129 @@
+//! Similarity measures between two images or spectra of the same size.
+
+use image::{DynamicImage, GrayImage};
+
+use super::{Boundary, FreqImage, Kernel2D};
+use crate::FreqError;
+
+/// The mean of `|a - b|²` over all coefficients.
+pub fn mse(a: &FreqImage, b: &FreqImage) -> Result<f64, FreqError> {
+    check_dimensions(a, b)?;
+    let sum: f64 = a.data.iter().zip(&b.data).map(|(p, q)| (p - q).norm_sqr()).sum();
+    Ok(sum / a.data.len().max(1) as f64)
+}
+
+/// Peak signal-to-noise ratio in decibels, `10 · log10(peak² / mse)`. Identical inputs
+/// give `f64::INFINITY`.
+pub fn psnr(a: &FreqImage, b: &FreqImage, peak: f64) -> Result<f64, FreqError> {
+    let mse = mse(a, b)?;
+    Ok(if mse == 0.0 { f64::INFINITY } else { 10.0 * (peak * peak / mse).log10() })
+}
+
+/// The largest `|a - b|` over all coefficients.
+pub fn max_abs_diff(a: &FreqImage, b: &FreqImage) -> Result<f64, FreqError> {
+    check_dimensions(a, b)?;
+    Ok(a.data.iter().zip(&b.data).map(|(p, q)| (p - q).norm()).fold(0.0, f64::max))
+}
+
+impl FreqImage {
+    /// Whether `other` has the same size and every coefficient is within `tol` of this
+    /// one's, measured as `|a - b|`. NaN coefficients are never close.
+    pub fn approx_eq(&self, other: &FreqImage, tol: f64) -> bool {
+        check_dimensions(self, other).is_ok()
+            && self.data.len() == other.data.len()
+            && self.data.iter().zip(&other.data).all(|(p, q)| (p - q).norm() <= tol)
+    }
+}
+
+/// [`psnr`] between two 8-bit grayscale images with a peak of 255.
+pub fn psnr_images(a: &GrayImage, b: &GrayImage) -> Result<f64, FreqError> {
+    psnr(&gray(a), &gray(b), 1.0)
+}
+
+/// Mean structural similarity (Wang et al. 2004) with the standard 11×11 Gaussian window
+/// of σ = 1.5, `K1 = 0.01` and `K2 = 0.03`, reflecting at the borders. 1 for identical
+/// images, lower as structure diverges.
+pub fn ssim(a: &GrayImage, b: &GrayImage) -> Result<f64, FreqError> {
+    let (x, y) = (gray(a), gray(b));
+    check_dimensions(&x, &y)?;
+    let window = Kernel2D::gaussian(1.5);
+    let blur = |fi: &FreqImage| fi.convolve_with(&window, Boundary::Reflect);
+    let product = |p: &FreqImage, q: &FreqImage| FreqImage {
+        width: p.width,
+        height: p.height,
+        data: p.data.iter().zip(&q.data).map(|(u, v)| u * v).collect(),
+    };
+
+    let (mu_x, mu_y) = (blur(&x), blur(&y));
+    let (xx, yy, xy) = (blur(&product(&x, &x)), blur(&product(&y, &y)), blur(&product(&x, &y)));
+    let (c1, c2) = (0.01f64.powi(2), 0.03f64.powi(2));
+    let total: f64 = (0..x.data.len())
+        .map(|i| {
+            let (mx, my) = (mu_x.data[i].re, mu_y.data[i].re);
+            let var_x = xx.data[i].re - mx * mx;
+            let var_y = yy.data[i].re - my * my;
+            let cov = xy.data[i].re - mx * my;
+            ((2.0 * mx * my + c1) * (2.0 * cov + c2)) / ((mx * mx + my * my + c1) * (var_x + var_y + c2))
+        })
+        .sum();
+    Ok(total / x.data.len().max(1) as f64)
+}
+
+fn gray(img: &GrayImage) -> FreqImage {
+    FreqImage::from_image(DynamicImage::ImageLuma8(img.clone()))
+}
+
+fn check_dimensions(a: &FreqImage, b: &FreqImage) -> Result<(), FreqError> {
+    if (a.width, a.height) == (b.width, b.height) {
+        Ok(())
+    } else {
+        Err(FreqError::DimensionMismatch { expected: (a.width, a.height), got: (b.width, b.height) })
+    }
+}
+
+#[test]
+fn test_identical_inputs() {
+    let fi = super::ramp(5, 4);
+    assert_eq!(mse(&fi, &fi).unwrap(), 0.0);
+    assert_eq!(psnr(&fi, &fi, 1.0).unwrap(), f64::INFINITY);
+    assert_eq!(max_abs_diff(&fi, &fi).unwrap(), 0.0);
+
+    let img = FreqImage::open("img/mandrill.jpg").unwrap().to_image();
+    assert_eq!(psnr_images(&img, &img).unwrap(), f64::INFINITY);
+    assert!((ssim(&img, &img).unwrap() - 1.0).abs() < 1e-9);
+}
+
+#[test]
+fn test_spectrum_metrics() {
+    let a = super::ramp(4, 3);
+    let mut b = a.clone();
+    b.data[5].re += 3.0;
+    b.data[7].im -= 4.0;
+    assert!((mse(&a, &b).unwrap() - 25.0 / 12.0).abs() < 1e-12);
+    assert_eq!(max_abs_diff(&a, &b).unwrap(), 4.0);
+    assert!(a.approx_eq(&b, 4.0) && !a.approx_eq(&b, 3.9));
+    assert!(!a.approx_eq(&super::ramp(3, 4), 1e9));
+    assert!((psnr(&a, &b, 10.0).unwrap() - 10.0 * (100.0f64 / (25.0 / 12.0)).log10()).abs() < 1e-9);
+    assert!(matches!(
+        mse(&a, &super::ramp(3, 4)),
+        Err(FreqError::DimensionMismatch { expected: (4, 3), got: (3, 4) })
+    ));
+    b.data[0].re = f64::NAN;
+    assert!(!b.approx_eq(&b, f64::INFINITY));
+}
+
+#[test]
+fn test_ssim_orders_degradations() {
+    let fi = FreqImage::open("img/mandrill.jpg").unwrap();
+    let original = fi.to_image();
+    let blurred = fi.convolve_with(&Kernel2D::gaussian(2.0), Boundary::Reflect).to_image();
+    let mut noise = fi.clone();
+    noise.data.iter_mut().for_each(|c| *c = 0.5.into());
+    noise.add_gaussian_noise(0.25, 4);
+
+    let blur_score = ssim(&original, &blurred).unwrap();
+    let noise_score = ssim(&original, &noise.to_image()).unwrap();
+    assert!(blur_score < 1.0);
+    assert!(blur_score > noise_score, "{} vs {}", blur_score, noise_score);
+    assert!(ssim(&original, &GrayImage::new(3, 3)).is_err());
+}
@@ -0,0 +1,109 @@
+//! Estimating straight-line motion blur from the image itself.
+
+use rustfft::num_complex::Complex;
+
+use super::{FreqImage, WindowKind};
+
+/// A linear motion blur found by [`FreqImage::estimate_motion_blur`], in the terms of
+/// [`Kernel2D::motion_blur`](super::Kernel2D::motion_blur).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MotionBlurEstimate {
+    /// Blur length in pixels.
+    pub length_px: f64,
+    /// Blur direction in radians in `[0, π)`, counter-clockwise from +x as displayed.
+    pub angle_rad: f64,
+    /// How clearly the blur stands out, from 0 (not at all) to 1.
+    pub confidence: f64,
+}
+
+/// Blurs shorter than this many pixels are not looked for.
+const MIN_BLUR_LENGTH: f64 = 3.0;
+
+/// Cepstral dip depths, in standard deviations, mapped to confidence 0 and 1.
+const DEPTH_FOR_NO_CONFIDENCE: f64 = 4.0;
+const DEPTH_FOR_FULL_CONFIDENCE: f64 = 8.0;
+
+impl FreqImage {
+    /// Estimate the length and direction of a straight-line motion blur in a
+    /// spatial-domain image from its cepstrum, the inverse transform of the log
+    /// magnitude spectrum. A blur of length `L` puts evenly spaced dark stripes across
+    /// the spectrum, which the cepstrum turns into a sharp dip `L` pixels from the
+    /// origin along the blur direction.
+    ///
+    /// The deepest dip between 3 pixels and a quarter of the smaller side is taken, and
+    /// `confidence` grows with how far it sinks below the rest of that annulus, so images
+    /// that are not motion blurred get a low score. `None` for images too small to search
+    /// or without any variation.
+    pub fn estimate_motion_blur(&self) -> Option<MotionBlurEstimate> {
+        let (w, h) = (self.width as i64, self.height as i64);
+        let max_length = (w.min(h) / 4) as f64;
+        if max_length <= MIN_BLUR_LENGTH + 1.0 {
+            return None;
+        }
+        let mut cepstrum = self.clone();
+        let mean = cepstrum.mean_value();
+        cepstrum.data.iter_mut().for_each(|c| *c -= mean);
+        cepstrum.apply_window(WindowKind::Hann);
+        cepstrum.fft_forward();
+        let peak = cepstrum.data.iter().map(|c| c.norm()).fold(0.0, f64::max);
+        if peak == 0.0 {
+            return None;
+        }
+        let floor = 1e-9 * peak;
+        cepstrum.data.iter_mut().for_each(|c| *c = Complex::new((c.norm() + floor).ln(), 0.0));
+        cepstrum.fft_inverse();
+
+        // Signed offset of a bin from the origin along an axis of length `n`.
+        let signed = |i: u32, n: i64| if 2 * (i as i64) < n { i as i64 } else { i as i64 - n };
+        let value = |dx: i64, dy: i64| cepstrum[(dx.rem_euclid(w) as u32, dy.rem_euclid(h) as u32)].re;
+        let annulus: Vec<(i64, i64, f64)> = cepstrum
+            .enumerate_pixels()
+            .map(|(x, y, c)| (signed(x, w), signed(y, h), c.re))
+            .filter(|&(dx, dy, _)| (MIN_BLUR_LENGTH..=max_length).contains(&(dx as f64).hypot(dy as f64)))
+            .collect();
+        let n = annulus.len() as f64;
+        let mean = annulus.iter().map(|&(_, _, v)| v).sum::<f64>() / n;
+        let sigma = (annulus.iter().map(|&(_, _, v)| (v - mean).powi(2)).sum::<f64>() / n).sqrt();
+        let &(dx, dy, lowest) = annulus.iter().min_by(|a, b| a.2.total_cmp(&b.2))?;
+        if sigma == 0.0 {
+            return None;
+        }
+
+        // Refine the dip to a fraction of a pixel with a parabola through its neighbours.
+        let vertex = |before: f64, after: f64| {
+            let curvature = before - 2.0 * lowest + after;
+            if curvature > 0.0 { (0.5 * (before - after) / curvature).clamp(-0.5, 0.5) } else { 0.0 }
+        };
+        let ox = dx as f64 + vertex(value(dx - 1, dy), value(dx + 1, dy));
+        let oy = dy as f64 + vertex(value(dx, dy - 1), value(dx, dy + 1));
+        let depth = (mean - lowest) / sigma;
+        Some(MotionBlurEstimate {
+            length_px: ox.hypot(oy),
+            angle_rad: (-oy).atan2(ox).rem_euclid(std::f64::consts::PI),
+            confidence: ((depth - DEPTH_FOR_NO_CONFIDENCE) / (DEPTH_FOR_FULL_CONFIDENCE - DEPTH_FOR_NO_CONFIDENCE))
+                .clamp(0.0, 1.0),
+        })
+    }
+}
+
+#[test]
+fn test_estimate_motion_blur() {
+    use super::{Boundary, Kernel2D};
+    use std::f64::consts::PI;
+
+    let sharp = super::synth::spectral_noise(128, 128, 2.0, 9);
+    for (length, angle) in [(15.0, 0.6), (9.0, 0.0), (12.0, 2.0)] {
+        let blurred = sharp.convolve_with(&Kernel2D::motion_blur(length, angle), Boundary::Reflect);
+        let estimate = blurred.estimate_motion_blur().unwrap();
+        // Directions are only defined modulo π, so 179.9° is as good as 0°.
+        let angle_error = (estimate.angle_rad - angle).rem_euclid(PI).min((angle - estimate.angle_rad).rem_euclid(PI));
+        assert!((estimate.length_px - length).abs() < 2.0, "{:?}", estimate);
+        assert!(angle_error < 5f64.to_radians(), "{:?}", estimate);
+        assert!(estimate.confidence > 0.9, "{:?}", estimate);
+    }
+
+    assert!(sharp.estimate_motion_blur().unwrap().confidence < 0.2);
+    assert!(super::synth::spectral_noise(128, 128, 1.0, 3).estimate_motion_blur().unwrap().confidence < 0.2);
+    assert_eq!(super::ramp(16, 16).estimate_motion_blur(), None);
+    assert_eq!(FreqImage { width: 64, height: 64, data: vec![Complex::new(0.5, 0.0); 64 * 64] }.estimate_motion_blur(), None);
+}
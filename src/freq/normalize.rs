@@ -0,0 +1,349 @@
+//! Conversion of the real part back into an 8-bit image.
+
+use image::{GrayImage, Rgb, RgbImage};
+use rustfft::num_complex::Complex;
+
+use super::view::{check_percentiles, percentile};
+use super::FreqImage;
+use crate::FreqError;
+
+/// How [`FreqImage::to_image_with`] maps real parts onto gray levels.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Normalize {
+    /// Clamp to `[0, 1]` and scale to `[0, 255]` (what [`FreqImage::to_image`] does).
+    Clamp,
+    /// Stretch the actual `[min, max]` range of the real parts to `[0, 255]`.
+    MinMax,
+    /// Map `[-1, 1]` to `[0, 255]` with zero at mid-gray, for signed edge/derivative output.
+    Centered,
+    /// Clip to the given lower and upper percentiles, `0 <= lo <= hi <= 100`, then
+    /// stretch that range.
+    Percentile(f64, f64),
+    /// Clamp to `[0, 1]`, then apply `v.powf(gamma)`; `gamma < 1` brightens.
+    Gamma(f64),
+}
+
+impl Normalize {
+    /// [`FreqError::InvalidArgument`] for a [`Normalize::Percentile`] with bounds that
+    /// [`check_percentiles`] rejects.
+    pub(crate) fn check(self) -> Result<(), FreqError> {
+        match self {
+            Normalize::Percentile(lo_pct, hi_pct) => check_percentiles(lo_pct, hi_pct),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// What [`FreqImage::finalize_real`] does to the real parts.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ClampPolicy {
+    /// Clamp to `[0, 1]`, as [`Normalize::Clamp`] does.
+    Clamp01,
+    /// Stretch the actual range onto `[0, 1]`, as [`Normalize::MinMax`] does.
+    Rescale,
+    /// Leave them alone, e.g. for signed output rendered with [`Normalize::Centered`].
+    None,
+}
+
+/// What [`FreqImage::finalize_real`] found before cleaning up. A large imaginary residue
+/// after an inverse FFT of a real image means the filter broke the spectrum's Hermitian
+/// symmetry; many clamped pixels mean it produced energy outside the displayable range.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FinalizeReport {
+    /// The largest `|im|` that was discarded.
+    pub max_imaginary: f64,
+    /// How many real parts were outside `[0, 1]` or not finite, the pixels
+    /// [`ClampPolicy::Clamp01`] changes. Counted under every policy.
+    pub clamped: usize,
+    /// The smallest finite real part, or 0 if there are none.
+    pub min: f64,
+    /// The largest finite real part, or 0 if there are none.
+    pub max: f64,
+}
+
+impl FreqImage {
+    /// Turn the result of an inverse FFT into a clean real image: zero every imaginary
+    /// part and map the real parts as `policy` says, with NaN and infinite values
+    /// becoming 0 unless the policy is [`ClampPolicy::None`]. The report describes the
+    /// buffer as it was before.
+    pub fn finalize_real(&mut self, policy: ClampPolicy) -> FinalizeReport {
+        let finite = self.data.iter().map(|c| c.re).filter(|v| v.is_finite());
+        let (min, max) = finite.fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), v| (lo.min(v), hi.max(v)));
+        let report = FinalizeReport {
+            max_imaginary: self.data.iter().map(|c| c.im.abs()).fold(0.0, f64::max),
+            clamped: self.data.iter().filter(|c| !(0.0..=1.0).contains(&c.re)).count(),
+            min: if min <= max { min } else { 0.0 },
+            max: if min <= max { max } else { 0.0 },
+        };
+        let real = match policy {
+            ClampPolicy::Clamp01 => self.normalized_real(Normalize::Clamp),
+            ClampPolicy::Rescale => self.normalized_real(Normalize::MinMax),
+            ClampPolicy::None => self.data.iter().map(|c| c.re).collect(),
+        };
+        for (c, v) in self.data.iter_mut().zip(real) {
+            *c = Complex::new(v, 0.0);
+        }
+        report
+    }
+
+    /// Convert the real parts back into a grayscale image, clamping to `[0, 1]`.
+    /// Imaginary parts are ignored, and NaN or infinite real parts render black.
+    ///
+    /// # Panics
+    /// Panics if the dimensions do not match the buffer; see [`FreqImage::try_to_image`].
+    pub fn to_image(&self) -> GrayImage {
+        self.to_image_with(Normalize::Clamp)
+    }
+
+    /// [`FreqImage::to_image`], or [`FreqError::InvalidDimensions`] if `width × height`
+    /// does not match the number of coefficients.
+    pub fn try_to_image(&self) -> Result<GrayImage, FreqError> {
+        self.try_to_image_with(Normalize::Clamp)
+    }
+
+    /// Convert the real parts back into a grayscale image using the given normalization.
+    /// NaN and infinite real parts render black and are left out of the ranges that
+    /// `MinMax` and `Percentile` measure.
+    ///
+    /// # Panics
+    /// Panics if the dimensions do not match the buffer or `mode` holds invalid
+    /// percentiles; see [`FreqImage::try_to_image_with`].
+    pub fn to_image_with(&self, mode: Normalize) -> GrayImage {
+        self.try_to_image_with(mode).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// [`FreqImage::to_image_with`], or [`FreqError::InvalidDimensions`] if
+    /// `width × height` does not match the number of coefficients and
+    /// [`FreqError::InvalidArgument`] if `mode` is a [`Normalize::Percentile`] whose
+    /// bounds are reversed or outside `[0, 100]`.
+    pub fn try_to_image_with(&self, mode: Normalize) -> Result<GrayImage, FreqError> {
+        mode.check()?;
+        if self.width as usize * self.height as usize != self.data.len() {
+            return Err(FreqError::InvalidDimensions { width: self.width, height: self.height, len: self.data.len() });
+        }
+        let pixels = self.normalized_real(mode).iter().map(|v| (v * 255.0).round() as u8).collect();
+        Ok(GrayImage::from_raw(self.width, self.height, pixels).unwrap())
+    }
+
+    /// The real parts mapped onto `[0, 1]` by `mode`, as [`FreqImage::to_image_with`]
+    /// quantizes them. NaN and infinite real parts map to 0.
+    pub(crate) fn normalized_real(&self, mode: Normalize) -> Vec<f64> {
+        let real: Vec<f64> = self.data.iter().map(|c| c.re).filter(|v| v.is_finite()).collect();
+        let stretch = |lo: f64, hi: f64| {
+            let scale = if hi > lo { 1.0 / (hi - lo) } else { 0.0 };
+            move |v: f64| (v.clamp(lo, hi) - lo) * scale
+        };
+        let unit: Box<dyn Fn(f64) -> f64> = match mode {
+            Normalize::Clamp => Box::new(|v: f64| v.clamp(0.0, 1.0)),
+            Normalize::MinMax => {
+                let min = real.iter().cloned().fold(f64::INFINITY, f64::min);
+                let max = real.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+                Box::new(stretch(min, max))
+            }
+            Normalize::Centered => Box::new(|v: f64| (v.clamp(-1.0, 1.0) + 1.0) / 2.0),
+            Normalize::Percentile(lo_pct, hi_pct) => {
+                let mut scratch = real.clone();
+                let lo = percentile(&mut scratch, lo_pct);
+                let hi = percentile(&mut scratch, hi_pct);
+                Box::new(stretch(lo, hi))
+            }
+            Normalize::Gamma(gamma) => Box::new(move |v: f64| v.clamp(0.0, 1.0).powf(gamma)),
+        };
+        self.data.iter().map(|c| if c.re.is_finite() { unit(c.re) } else { 0.0 }).collect()
+    }
+
+    /// [`FreqImage::to_image`], but fail with [`FreqError::NonFinite`] if any real part
+    /// is NaN or infinite instead of rendering it black.
+    pub fn to_image_checked(&self) -> Result<GrayImage, FreqError> {
+        check_finite(self.data.iter().map(|c| c.re.is_finite()))?;
+        self.try_to_image()
+    }
+
+    /// Render signed real parts on a blue-white-red ramp: `-limit` is blue, zero is
+    /// white and `+limit` is red, with values beyond the limit clamped.
+    /// A `limit <= 0` uses the largest finite absolute value in the buffer.
+    /// NaN and infinite values render black, which the ramp never produces.
+    pub fn to_image_diverging(&self, limit: f64) -> RgbImage {
+        let limit = if limit > 0.0 {
+            limit
+        } else {
+            self.data.iter().filter(|c| c.re.is_finite()).map(|c| c.re.abs()).fold(0.0, f64::max)
+        };
+        let mut out = RgbImage::new(self.width, self.height);
+        for (pix, c) in out.pixels_mut().zip(&self.data) {
+            if !c.re.is_finite() {
+                *pix = Rgb([0, 0, 0]);
+                continue;
+            }
+            let t = if limit > 0.0 { (c.re / limit).clamp(-1.0, 1.0) } else { 0.0 };
+            let fade = ((1.0 - t.abs()) * 255.0).round() as u8;
+            *pix = if t < 0.0 { Rgb([fade, fade, 255]) } else { Rgb([255, fade, fade]) };
+        }
+        out
+    }
+}
+
+/// [`FreqError::NonFinite`] if any flag is false.
+pub(crate) fn check_finite(flags: impl Iterator<Item = bool>) -> Result<(), FreqError> {
+    let (mut count, mut first_index) = (0, 0);
+    for (i, finite) in flags.enumerate() {
+        if !finite {
+            if count == 0 {
+                first_index = i;
+            }
+            count += 1;
+        }
+    }
+    if count == 0 {
+        Ok(())
+    } else {
+        Err(FreqError::NonFinite { count, first_index })
+    }
+}
+
+#[test]
+fn test_finalize_real() {
+    let values = [(0.5, 0.0), (1.5, 1e-3), (-0.25, -0.2), (1.0, 0.0), (f64::NAN, 0.0), (0.0, 1e-12)];
+    let fi = FreqImage { width: 3, height: 2, data: values.iter().map(|&(re, im)| Complex::new(re, im)).collect() };
+    let expected = FinalizeReport { max_imaginary: 0.2, clamped: 3, min: -0.25, max: 1.5 };
+
+    let mut clamped = fi.clone();
+    assert_eq!(clamped.finalize_real(ClampPolicy::Clamp01), expected);
+    assert_eq!(clamped.real(), [0.5, 1.0, 0.0, 1.0, 0.0, 0.0]);
+    assert!(clamped.data.iter().all(|c| c.im == 0.0));
+
+    let mut rescaled = fi.clone();
+    assert_eq!(rescaled.finalize_real(ClampPolicy::Rescale), expected);
+    for (got, want) in rescaled.real().into_iter().zip([0.75 / 1.75, 1.0, 0.0, 1.25 / 1.75, 0.0, 0.25 / 1.75]) {
+        assert!((got - want).abs() < 1e-15, "{} vs {}", got, want);
+    }
+
+    let mut untouched = fi.clone();
+    assert_eq!(untouched.finalize_real(ClampPolicy::None), expected);
+    assert_eq!(untouched.real()[..4], [0.5, 1.5, -0.25, 1.0]);
+    assert!(untouched.data[4].re.is_nan() && untouched.data.iter().all(|c| c.im == 0.0));
+
+    // Finalizing twice finds nothing more to clean up.
+    assert_eq!(clamped.finalize_real(ClampPolicy::Clamp01), FinalizeReport { max_imaginary: 0.0, clamped: 0, min: 0.0, max: 1.0 });
+    let mut empty = FreqImage { width: 0, height: 0, data: vec![] };
+    assert_eq!(empty.finalize_real(ClampPolicy::Rescale), FinalizeReport { max_imaginary: 0.0, clamped: 0, min: 0.0, max: 0.0 });
+}
+
+#[test]
+fn test_to_image_with_modes() {
+    let fi = FreqImage {
+        width: 2,
+        height: 2,
+        data: [-0.5, 0.0, 0.5, 2.0]
+            .iter()
+            .map(|&v| rustfft::num_complex::Complex::new(v, 3.0))
+            .collect(),
+    };
+    let pixels = |mode| fi.to_image_with(mode).into_raw();
+
+    assert_eq!(pixels(Normalize::Clamp), [0, 0, 128, 255]);
+    assert_eq!(fi.to_image().into_raw(), [0, 0, 128, 255]);
+    assert_eq!(pixels(Normalize::MinMax), [0, 51, 102, 255]);
+    assert_eq!(pixels(Normalize::Centered), [64, 128, 191, 255]);
+    assert_eq!(pixels(Normalize::Percentile(0.0, 100.0)), [0, 51, 102, 255]);
+    assert_eq!(pixels(Normalize::Percentile(25.0, 75.0)), [0, 0, 255, 255]);
+    assert_eq!(pixels(Normalize::Gamma(2.0)), [0, 0, 64, 255]);
+
+    for bad in [Normalize::Percentile(90.0, 10.0), Normalize::Percentile(-1.0, 50.0), Normalize::Percentile(5.0, f64::NAN)] {
+        assert!(matches!(fi.try_to_image_with(bad), Err(FreqError::InvalidArgument(_))));
+    }
+    let run = super::pipeline::FilterPipeline::new().normalize(Normalize::Percentile(90.0, 10.0)).run(&fi);
+    assert!(matches!(run, Err(FreqError::InvalidArgument(_))));
+}
+
+#[test]
+#[should_panic(expected = "percentiles must satisfy")]
+fn test_to_image_with_reversed_percentiles() {
+    super::ramp(2, 2).to_image_with(Normalize::Percentile(90.0, 10.0));
+}
+
+#[test]
+fn test_to_image_round_trips_from_image() {
+    let img = image::open("img/mandrill.jpg").unwrap();
+    let fi = FreqImage::from_image(img.clone());
+    assert_eq!(fi.to_image(), img.into_luma8());
+}
+
+#[test]
+fn test_degenerate_to_image() {
+    use rustfft::num_complex::Complex;
+
+    for (w, h) in [(1, 1), (1, 6), (6, 1)] {
+        let fi = FreqImage { width: w, height: h, data: (0..w * h).map(|i| Complex::new(i as f64 / 5.0, 0.0)).collect() };
+        let img = fi.to_image();
+        assert_eq!(img.dimensions(), (w, h));
+        assert_eq!(img.as_raw().last(), Some(&if w * h == 1 { 0 } else { 255 }));
+        assert_eq!(fi.to_image_with(Normalize::MinMax).as_raw()[0], 0);
+    }
+}
+
+#[test]
+fn test_try_to_image() {
+    let mut fi = super::ramp(3, 2);
+    assert_eq!(fi.try_to_image().unwrap(), fi.to_image());
+    fi.data.pop();
+    let err = fi.try_to_image().unwrap_err();
+    assert!(matches!(err, FreqError::InvalidDimensions { width: 3, height: 2, len: 5 }));
+    fi.data.extend([rustfft::num_complex::Complex::new(0.0, 0.0); 2]);
+    assert!(fi.try_to_image_with(Normalize::MinMax).is_err());
+}
+
+#[test]
+fn test_to_image_diverging() {
+    let fi = FreqImage {
+        width: 4,
+        height: 1,
+        data: [-2.0, 0.0, 1.0, 0.5]
+            .iter()
+            .map(|&v| rustfft::num_complex::Complex::new(v, 0.0))
+            .collect(),
+    };
+
+    let auto = fi.to_image_diverging(0.0);
+    assert_eq!(auto.get_pixel(0, 0), &Rgb([0, 0, 255]));
+    assert_eq!(auto.get_pixel(1, 0), &Rgb([255, 255, 255]));
+    assert_eq!(auto.get_pixel(2, 0), &Rgb([255, 128, 128]));
+
+    let fixed = fi.to_image_diverging(1.0);
+    assert_eq!(fixed.get_pixel(0, 0), &Rgb([0, 0, 255]));
+    assert_eq!(fixed.get_pixel(2, 0), &Rgb([255, 0, 0]));
+    assert_eq!(fixed.get_pixel(3, 0), &Rgb([255, 128, 128]));
+
+    let mut non_finite = fi.clone();
+    non_finite.data[1].re = f64::NAN;
+    non_finite.data[3].re = f64::INFINITY;
+    let auto = non_finite.to_image_diverging(0.0);
+    assert_eq!(auto.get_pixel(0, 0), &Rgb([0, 0, 255]));
+    assert_eq!(auto.get_pixel(1, 0), &Rgb([0, 0, 0]));
+    assert_eq!(auto.get_pixel(3, 0), &Rgb([0, 0, 0]));
+}
+
+#[test]
+fn test_to_image_non_finite() {
+    let mut fi = FreqImage {
+        width: 3,
+        height: 2,
+        data: [0.0, 0.5, 1.0, 0.25, 0.75, 0.5].iter().map(|&v| v.into()).collect(),
+    };
+    fi.data[1].re = f64::NAN;
+    fi.data[3].re = f64::INFINITY;
+    fi.data[4].re = f64::NEG_INFINITY;
+
+    assert_eq!(fi.to_image().into_raw(), [0, 0, 255, 0, 0, 128]);
+    // MinMax stretches the finite values only.
+    assert_eq!(fi.to_image_with(Normalize::MinMax).into_raw(), [0, 0, 255, 0, 0, 128]);
+    assert!(matches!(fi.to_image_checked(), Err(FreqError::NonFinite { count: 3, first_index: 1 })));
+
+    fi.data[1].re = 0.5;
+    fi.data[3].re = 0.0;
+    fi.data[4].re = 0.0;
+    fi.data[5].im = f64::NAN;
+    assert_eq!(fi.to_image_checked().unwrap(), fi.to_image());
+}
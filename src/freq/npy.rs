@@ -0,0 +1,198 @@
+//! NumPy `.npy` import/export of the complex buffer.
+//!
+//! Files hold a C-order `complex128` (`'<c16'`) array of shape `(height, width)`,
+//! which is what `np.save(path, np.fft.fft2(img))` produces on little-endian hosts.
+
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use rustfft::num_complex::Complex;
+
+use super::FreqImage;
+use crate::FreqError;
+
+const NPY_MAGIC: &[u8] = b"\x93NUMPY";
+const NPY_ALIGN: usize = 64;
+
+impl FreqImage {
+    /// Write the buffer as a `.npy` v1.0 file of dtype `complex128` and shape `(height, width)`.
+    pub fn save_npy<P: AsRef<Path>>(&self, path: P) -> Result<(), FreqError> {
+        let mut out = BufWriter::new(File::create(path)?);
+        out.write_all(&npy_header(self.height as usize, self.width as usize))?;
+        for c in &self.data {
+            out.write_all(&c.re.to_le_bytes())?;
+            out.write_all(&c.im.to_le_bytes())?;
+        }
+        out.flush()?;
+        Ok(())
+    }
+
+    /// Read a `.npy` file holding a 2D C-order `complex128` array.
+    ///
+    /// Fortran-order arrays are rejected; convert them with `np.ascontiguousarray` first.
+    pub fn load_npy<P: AsRef<Path>>(path: P) -> Result<Self, FreqError> {
+        parse_npy(&std::fs::read(path)?)
+    }
+}
+
+fn npy_header(height: usize, width: usize) -> Vec<u8> {
+    let dict = format!(
+        "{{'descr': '<c16', 'fortran_order': False, 'shape': ({}, {}), }}",
+        height, width
+    );
+    // Pad with spaces so the data starts on an aligned offset, as numpy does.
+    let unpadded = NPY_MAGIC.len() + 4 + dict.len() + 1;
+    let pad = NPY_ALIGN - unpadded % NPY_ALIGN;
+
+    let mut header = NPY_MAGIC.to_vec();
+    header.extend_from_slice(&[1, 0]);
+    header.extend_from_slice(&((dict.len() + pad + 1) as u16).to_le_bytes());
+    header.extend_from_slice(dict.as_bytes());
+    header.resize(header.len() + pad, b' ');
+    header.push(b'\n');
+    header
+}
+
+fn parse_npy(bytes: &[u8]) -> Result<FreqImage, FreqError> {
+    let invalid = |msg: &str| FreqError::InvalidFormat(format!(".npy: {}", msg));
+
+    if bytes.len() < 10 || &bytes[..6] != NPY_MAGIC {
+        return Err(invalid("missing \\x93NUMPY magic"));
+    }
+    let (header_len, data_start) = match bytes[6] {
+        1 => (u16::from_le_bytes([bytes[8], bytes[9]]) as usize, 10),
+        2 | 3 if bytes.len() >= 12 => (
+            u32::from_le_bytes([bytes[8], bytes[9], bytes[10], bytes[11]]) as usize,
+            12,
+        ),
+        major => return Err(invalid(&format!("unsupported format version {}", major))),
+    };
+    let data_offset = data_start + header_len;
+    let header = bytes
+        .get(data_start..data_offset)
+        .ok_or_else(|| invalid("header is truncated"))?;
+    let header = String::from_utf8_lossy(header);
+
+    let descr = header_value(&header, "descr")
+        .and_then(|v| v.strip_prefix('\''))
+        .and_then(|v| v.split('\'').next())
+        .ok_or_else(|| invalid("header has no 'descr'"))?;
+    if descr != "<c16" {
+        return Err(invalid(&format!("dtype '{}' is not supported, expected '<c16' (complex128)", descr)));
+    }
+
+    let fortran_order = header_value(&header, "fortran_order")
+        .ok_or_else(|| invalid("header has no 'fortran_order'"))?;
+    if fortran_order.starts_with("True") {
+        return Err(invalid("Fortran-order arrays are not supported, save a C-order array instead"));
+    }
+
+    let shape = header_value(&header, "shape")
+        .and_then(|v| v.strip_prefix('('))
+        .and_then(|v| v.split(')').next())
+        .ok_or_else(|| invalid("header has no 'shape'"))?;
+    let dims = shape
+        .split(',')
+        .map(str::trim)
+        .filter(|d| !d.is_empty())
+        .map(|d| d.parse::<u32>())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| invalid(&format!("bad shape ({})", shape)))?;
+    let (height, width) = match dims[..] {
+        [height, width] => (height, width),
+        _ => return Err(invalid(&format!("expected a 2D array, got shape ({})", shape))),
+    };
+
+    let len = (width as usize)
+        .checked_mul(height as usize)
+        .filter(|len| len.checked_mul(16).is_some())
+        .ok_or_else(|| invalid(&format!("shape ({}) is too large", shape)))?;
+    let payload = &bytes[data_offset..];
+    if payload.len() < len * 16 {
+        return Err(invalid(&format!(
+            "data is truncated: expected {} bytes, got {}",
+            len * 16,
+            payload.len()
+        )));
+    }
+    let data = payload
+        .chunks_exact(16)
+        .take(len)
+        .map(|c| {
+            let re = f64::from_le_bytes(c[..8].try_into().unwrap());
+            let im = f64::from_le_bytes(c[8..].try_into().unwrap());
+            Complex::new(re, im)
+        })
+        .collect();
+
+    Ok(FreqImage { width, height, data })
+}
+
+/// Return the text following `'key':` in a .npy header dict.
+fn header_value<'a>(header: &'a str, key: &str) -> Option<&'a str> {
+    let pattern = format!("'{}':", key);
+    let start = header.find(&pattern)? + pattern.len();
+    Some(header[start..].trim_start())
+}
+
+#[test]
+fn test_npy_round_trip() {
+    let fi = FreqImage {
+        width: 3,
+        height: 2,
+        data: (0..6).map(|i| Complex::new(i as f64 * 0.5, -(i as f64))).collect(),
+    };
+    let path = std::env::temp_dir().join(format!("freqshow_npy_{}.npy", std::process::id()));
+    fi.save_npy(&path).unwrap();
+
+    let bytes = std::fs::read(&path).unwrap();
+    assert_eq!(bytes.len(), 128 + 6 * 16);
+    assert_eq!(FreqImage::load_npy(&path).unwrap(), fi);
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn test_npy_fixture() {
+    // np.save("complex_2x3.npy", np.array([[1+2j, -3.5, 0.25-1j], [0, 1e6+1e-6j, -0.5-0.5j]]))
+    let fi = FreqImage::load_npy("tests/data/complex_2x3.npy").unwrap();
+    assert_eq!((fi.width, fi.height), (3, 2));
+    assert_eq!(
+        fi.data,
+        vec![
+            Complex::new(1.0, 2.0),
+            Complex::new(-3.5, 0.0),
+            Complex::new(0.25, -1.0),
+            Complex::new(0.0, 0.0),
+            Complex::new(1e6, 1e-6),
+            Complex::new(-0.5, -0.5),
+        ]
+    );
+}
+
+#[test]
+fn test_npy_rejects_fortran_order() {
+    let mut bytes = npy_header(2, 2);
+    let pos = bytes.windows(5).position(|w| w == b"False").unwrap();
+    bytes.splice(pos..pos + 5, b"True ".iter().cloned());
+    bytes.resize(bytes.len() + 4 * 16, 0);
+
+    let err = parse_npy(&bytes).unwrap_err();
+    assert!(err.to_string().contains("Fortran-order"));
+}
+
+#[test]
+fn test_npy_rejects_truncated_data() {
+    let mut bytes = npy_header(2, 2);
+    bytes.resize(bytes.len() + 3 * 16, 0);
+    let err = parse_npy(&bytes).unwrap_err();
+    assert!(err.to_string().contains("truncated"));
+}
+
+#[test]
+fn test_npy_rejects_oversized_shape() {
+    let mut bytes = npy_header(u32::MAX as usize, u32::MAX as usize);
+    bytes.resize(bytes.len() + 16, 0);
+    let err = parse_npy(&bytes).unwrap_err();
+    assert!(err.to_string().contains("too large"), "{}", err);
+}
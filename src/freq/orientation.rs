@@ -0,0 +1,88 @@
+//! Honouring the EXIF orientation tag when loading photos.
+
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+
+use image::DynamicImage;
+
+use super::FreqImage;
+
+impl FreqImage {
+    /// [`FreqImage::open`], but first turn and flip the decoded image as its EXIF
+    /// orientation tag says, so phone photos come out upright. Files without EXIF data,
+    /// or without a valid orientation in it, load unchanged.
+    pub fn open_oriented<P: AsRef<Path>>(path: P) -> image::ImageResult<Self> {
+        let img = image::open(&path)?;
+        let orientation = exif_orientation(path.as_ref()).unwrap_or(1);
+        Ok(Self::from_image(apply_orientation(img, orientation)))
+    }
+}
+
+/// The primary image's orientation tag (1–8), if the file has one.
+fn exif_orientation(path: &Path) -> Option<u32> {
+    let mut reader = BufReader::new(File::open(path).ok()?);
+    let exif = exif::Reader::new().read_from_container(&mut reader).ok()?;
+    exif.get_field(exif::Tag::Orientation, exif::In::PRIMARY)?.value.get_uint(0)
+}
+
+/// Undo an EXIF orientation: 2–4 are mirrors and a half turn, 5–8 are stored sideways.
+fn apply_orientation(img: DynamicImage, orientation: u32) -> DynamicImage {
+    match orientation {
+        2 => img.fliph(),
+        3 => img.rotate180(),
+        4 => img.flipv(),
+        5 => img.rotate90().fliph(),
+        6 => img.rotate90(),
+        7 => img.rotate270().fliph(),
+        8 => img.rotate270(),
+        _ => img,
+    }
+}
+
+#[test]
+fn test_apply_orientation() {
+    // 3x2, numbered in reading order as displayed upright.
+    let upright = image::GrayImage::from_raw(3, 2, vec![1, 2, 3, 4, 5, 6]).unwrap();
+    let stored: [(u32, u32, u32, [u8; 6]); 8] = [
+        (1, 3, 2, [1, 2, 3, 4, 5, 6]),
+        (2, 3, 2, [3, 2, 1, 6, 5, 4]),
+        (3, 3, 2, [6, 5, 4, 3, 2, 1]),
+        (4, 3, 2, [4, 5, 6, 1, 2, 3]),
+        (5, 2, 3, [1, 4, 2, 5, 3, 6]),
+        (6, 2, 3, [3, 6, 2, 5, 1, 4]),
+        (7, 2, 3, [6, 3, 5, 2, 4, 1]),
+        (8, 2, 3, [4, 1, 5, 2, 6, 3]),
+    ];
+    for (orientation, w, h, pixels) in stored {
+        let img = DynamicImage::ImageLuma8(image::GrayImage::from_raw(w, h, pixels.to_vec()).unwrap());
+        assert_eq!(apply_orientation(img, orientation).to_luma8(), upright, "orientation {}", orientation);
+    }
+}
+
+#[test]
+fn test_exif_orientation() {
+    for orientation in [1, 3, 6, 8] {
+        let path = format!("tests/data/orientation_{}.jpg", orientation);
+        assert_eq!(exif_orientation(Path::new(&path)), Some(orientation));
+    }
+    assert_eq!(exif_orientation(Path::new("tests/data/complex_2x3.npy")), None);
+    assert_eq!(exif_orientation(Path::new("tests/data/missing.jpg")), None);
+}
+
+#[test]
+fn test_open_oriented_fixtures() {
+    let reference = FreqImage::open("tests/data/orientation_1.jpg").unwrap();
+    assert_eq!((reference.width, reference.height), (16, 8));
+    assert_eq!(FreqImage::open_oriented("tests/data/orientation_1.jpg").unwrap(), reference);
+    for orientation in [3, 6, 8] {
+        let path = format!("tests/data/orientation_{}.jpg", orientation);
+        let fi = FreqImage::open_oriented(&path).unwrap();
+        assert_eq!((fi.width, fi.height), (16, 8), "{}", path);
+        // The fixtures are the same ramp stored turned; allow for JPEG rounding.
+        assert!(fi.data.iter().zip(&reference.data).all(|(a, b)| (a - b).norm() <= 2.0 / 255.0), "{}", path);
+    }
+    // No EXIF at all: loads like open.
+    let plain = FreqImage::open_oriented("img/mandrill.jpg").unwrap();
+    assert_eq!(plain, FreqImage::open("img/mandrill.jpg").unwrap());
+}
@@ -0,0 +1,150 @@
+//! Locating strong off-DC peaks in a spectrum.
+
+use super::FreqImage;
+
+/// A spectral peak found by [`FreqImage::find_spectral_peaks`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SpectralPeak {
+    /// Horizontal frequency in bins (cycles per image width), signed.
+    pub u: f64,
+    /// Vertical frequency in bins (cycles per image height), signed, counting rows downward.
+    pub v: f64,
+    /// Magnitude of the peak bin.
+    pub magnitude: f64,
+}
+
+impl FreqImage {
+    /// The `n` strongest local maxima of the magnitude of a spectrum in natural
+    /// (unshifted) layout, strongest first.
+    ///
+    /// Bins within `exclude_dc_radius` bins of DC are ignored, and a peak closer than
+    /// `min_distance` bins to a stronger one is suppressed. Positions are refined by a
+    /// least-squares quadratic fit over the 3x3 neighbourhood. Peaks of real images come in
+    /// mirror pairs `±(u, v)`; only the one with `v > 0`, or `v == 0` and `u > 0`, is reported.
+    pub fn find_spectral_peaks(&self, n: usize, min_distance: f64, exclude_dc_radius: f64) -> Vec<SpectralPeak> {
+        let (w, h) = (self.width, self.height);
+        if n == 0 || w < 3 || h < 3 {
+            return Vec::new();
+        }
+        let mag = |x: u32, y: u32| self.data[(y * w + x) as usize].norm();
+        let bins = |x: u32, y: u32| {
+            let (fx, fy) = self.frequency_at(x, y);
+            (fx * w as f64, fy * h as f64)
+        };
+
+        let mut candidates = Vec::new();
+        for (x, y, c) in self.enumerate_pixels() {
+            let (u, v) = bins(x, y);
+            if u.hypot(v) < exclude_dc_radius || !(v > 0.0 || (v == 0.0 && u > 0.0)) {
+                continue;
+            }
+            let m = c.norm();
+            let neighbours = neighbourhood(x, y, w, h);
+            if m > 0.0 && neighbours.iter().all(|&(nx, ny)| mag(nx, ny) <= m) {
+                candidates.push((x, y, m));
+            }
+        }
+        candidates.sort_by(|a, b| b.2.total_cmp(&a.2));
+
+        let mut peaks: Vec<SpectralPeak> = Vec::new();
+        for (x, y, m) in candidates {
+            let (u, v) = bins(x, y);
+            let too_close = peaks.iter().any(|p| {
+                let du = wrapped(u - p.u, w as f64);
+                let dv = wrapped(v - p.v, h as f64);
+                du.hypot(dv) < min_distance
+            });
+            if too_close {
+                continue;
+            }
+            let mut z = [0.0; 9];
+            for (i, &(nx, ny)) in neighbourhood(x, y, w, h).iter().enumerate() {
+                z[i] = mag(nx, ny);
+            }
+            let (du, dv) = quadratic_offset(&z);
+            peaks.push(SpectralPeak { u: u + du, v: v + dv, magnitude: m });
+            if peaks.len() == n {
+                break;
+            }
+        }
+        peaks
+    }
+}
+
+/// The 3x3 neighbourhood of `(x, y)`, row by row from `(x-1, y-1)`, wrapping around.
+fn neighbourhood(x: u32, y: u32, w: u32, h: u32) -> [(u32, u32); 9] {
+    let mut out = [(0, 0); 9];
+    for (i, (dx, dy)) in [(w - 1, h - 1), (0, h - 1), (1, h - 1), (w - 1, 0), (0, 0), (1, 0), (w - 1, 1), (0, 1), (1, 1)]
+        .into_iter()
+        .enumerate()
+    {
+        out[i] = ((x + dx) % w, (y + dy) % h);
+    }
+    out
+}
+
+/// Distance along a periodic axis of length `n`, in `[-n/2, n/2]`.
+fn wrapped(d: f64, n: f64) -> f64 {
+    d - n * (d / n).round()
+}
+
+/// The stationary point of `z ≈ a + bx + cy + dx² + exy + fy²` fitted by least squares to
+/// a 3x3 patch (row-major, `x` and `y` in `-1..=1`), clamped to half a bin. Zero when the
+/// fit is not a maximum.
+fn quadratic_offset(z: &[f64; 9]) -> (f64, f64) {
+    let (mut b, mut c, mut d, mut e, mut f) = (0.0, 0.0, 0.0, 0.0, 0.0);
+    for (i, &zi) in z.iter().enumerate() {
+        let (x, y) = ((i % 3) as f64 - 1.0, (i / 3) as f64 - 1.0);
+        b += x * zi / 6.0;
+        c += y * zi / 6.0;
+        d += (x * x - 2.0 / 3.0) * zi / 2.0;
+        e += x * y * zi / 4.0;
+        f += (y * y - 2.0 / 3.0) * zi / 2.0;
+    }
+    // Solve [2d e; e 2f]·[x y] = -[b c].
+    let det = 4.0 * d * f - e * e;
+    if d >= 0.0 || det <= 0.0 {
+        return (0.0, 0.0);
+    }
+    let x = (-b * 2.0 * f + c * e) / det;
+    let y = (-c * 2.0 * d + b * e) / det;
+    (x.clamp(-0.5, 0.5), y.clamp(-0.5, 0.5))
+}
+
+#[test]
+fn test_find_spectral_peaks_gratings() {
+    use rustfft::num_complex::Complex;
+    use std::f64::consts::PI;
+
+    let (w, h) = (96u32, 64u32);
+    let mut fi = FreqImage {
+        width: w,
+        height: h,
+        data: (0..w * h)
+            .map(|i| {
+                let (x, y) = ((i % w) as f64, (i / w) as f64);
+                let a = (2.0 * PI * (7.0 * x / w as f64 + 3.0 * y / h as f64)).cos();
+                let b = 0.5 * (2.0 * PI * (-12.0 * x / w as f64 + 9.0 * y / h as f64)).cos();
+                Complex::new(0.5 + 0.2 * a + 0.2 * b, 0.0)
+            })
+            .collect(),
+    };
+    fi.fft_forward();
+    let peaks = fi.find_spectral_peaks(2, 3.0, 2.0);
+    assert_eq!(peaks.len(), 2);
+    assert!((peaks[0].u - 7.0).abs() < 0.1 && (peaks[0].v - 3.0).abs() < 0.1, "{:?}", peaks);
+    assert!((peaks[1].u + 12.0).abs() < 0.1 && (peaks[1].v - 9.0).abs() < 0.1, "{:?}", peaks);
+    assert!(peaks[0].magnitude > peaks[1].magnitude);
+}
+
+#[test]
+fn test_quadratic_offset() {
+    // Samples of -(x - 0.25)² - (y + 0.1)² recover the vertex exactly.
+    let mut z = [0.0; 9];
+    for (i, zi) in z.iter_mut().enumerate() {
+        let (x, y) = ((i % 3) as f64 - 1.0, (i / 3) as f64 - 1.0);
+        *zi = -(x - 0.25f64).powi(2) - (y + 0.1f64).powi(2);
+    }
+    let (x, y) = quadratic_offset(&z);
+    assert!((x - 0.25).abs() < 1e-12 && (y + 0.1).abs() < 1e-12);
+}
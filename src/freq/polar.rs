@@ -0,0 +1,103 @@
+//! Component extraction and polar (magnitude/phase) conversions.
+
+use rustfft::num_complex::Complex;
+
+use super::FreqImage;
+use crate::FreqError;
+
+impl FreqImage {
+    /// The magnitude `|c|` of every coefficient.
+    pub fn magnitude(&self) -> Vec<f64> {
+        self.data.iter().map(|c| c.norm()).collect()
+    }
+
+    /// The phase of every coefficient in radians, in `(-π, π]`.
+    /// Zero-magnitude bins have phase zero.
+    pub fn phase(&self) -> Vec<f64> {
+        self.data.iter().map(|&c| phase_of(c)).collect()
+    }
+
+    /// The real part of every coefficient.
+    pub fn real(&self) -> Vec<f64> {
+        self.data.iter().map(|c| c.re).collect()
+    }
+
+    /// The imaginary part of every coefficient.
+    pub fn imag(&self) -> Vec<f64> {
+        self.data.iter().map(|c| c.im).collect()
+    }
+
+    /// Magnitude and phase of every coefficient, see [`FreqImage::magnitude`] and [`FreqImage::phase`].
+    pub fn to_polar(&self) -> (Vec<f64>, Vec<f64>) {
+        (self.magnitude(), self.phase())
+    }
+
+    /// Rebuild a FreqImage from magnitude and phase buffers of length `width * height`.
+    pub fn from_polar(width: u32, height: u32, mag: &[f64], phase: &[f64]) -> Result<Self, FreqError> {
+        let expected = width as usize * height as usize;
+        for got in [mag.len(), phase.len()] {
+            if got != expected {
+                return Err(FreqError::LengthMismatch { expected, got });
+            }
+        }
+        Ok(FreqImage {
+            width,
+            height,
+            data: mag
+                .iter()
+                .zip(phase)
+                .map(|(&r, &theta)| Complex::from_polar(r, theta))
+                .collect(),
+        })
+    }
+}
+
+/// `arg(c)`, but zero rather than `±π` for (signed) zero coefficients.
+pub(crate) fn phase_of(c: Complex<f64>) -> f64 {
+    if c.re == 0.0 && c.im == 0.0 {
+        0.0
+    } else {
+        c.arg()
+    }
+}
+
+#[test]
+fn test_polar_round_trip() {
+    let mut fi = FreqImage {
+        width: 5,
+        height: 3,
+        data: (0..15).map(|i| Complex::new((i % 4) as f64 * 0.3, (i % 3) as f64 * 0.1)).collect(),
+    };
+    fi.fft_forward();
+
+    let (mag, phase) = fi.to_polar();
+    let back = FreqImage::from_polar(5, 3, &mag, &phase).unwrap();
+    for (a, b) in back.data.iter().zip(&fi.data) {
+        assert!((a - b).norm() < 1e-12);
+    }
+    assert_eq!(fi.real()[7], fi.data[7].re);
+    assert_eq!(fi.imag()[7], fi.data[7].im);
+}
+
+#[test]
+fn test_phase_of_real_positive_is_zero() {
+    let fi = FreqImage {
+        width: 3,
+        height: 2,
+        data: vec![
+            Complex::new(1.0, 0.0),
+            Complex::new(0.5, 0.0),
+            Complex::new(0.0, 0.0),
+            Complex::new(-0.0, -0.0),
+            Complex::new(2.0, -0.0),
+            Complex::new(1e-300, 0.0),
+        ],
+    };
+    assert!(fi.phase().iter().all(|&p| p == 0.0));
+}
+
+#[test]
+fn test_from_polar_rejects_bad_lengths() {
+    let err = FreqImage::from_polar(2, 2, &[1.0; 4], &[0.0; 3]).unwrap_err();
+    assert!(matches!(err, FreqError::LengthMismatch { expected: 4, got: 3 }));
+}
@@ -0,0 +1,56 @@
+//! One-dimensional gain profiles for
+//! [`apply_separable_filter`](crate::FreqImage::apply_separable_filter).
+//!
+//! A profile of length `n` holds one gain per bin along an axis of a centered spectrum,
+//! indexed like the spectrum: bin `i` is `(i - n/2) / n` cycles per pixel from DC at
+//! `n / 2`. Frequencies below are those distances, in cycles per pixel.
+
+/// The signed frequency of bin `i` of a centered axis of length `n`, in cycles per pixel.
+fn centered_frequency(i: usize, n: usize) -> f64 {
+    (i as f64 - (n / 2) as f64) / n as f64
+}
+
+/// A Gaussian `exp(-f² / 2σ²)` of standard deviation `sigma` cycles per pixel.
+///
+/// # Panics
+/// Panics if `sigma` is not positive.
+pub fn gaussian(n: usize, sigma: f64) -> Vec<f64> {
+    assert!(sigma > 0.0, "gaussian sigma must be positive");
+    (0..n).map(|i| (-centered_frequency(i, n).powi(2) / (2.0 * sigma * sigma)).exp()).collect()
+}
+
+/// 1 up to `cutoff` cycles per pixel and 0 from `cutoff + smoothing` on, falling along
+/// half a cosine period in between. A `smoothing` of zero gives a hard edge.
+///
+/// # Panics
+/// Panics if `cutoff` or `smoothing` is negative.
+pub fn raised_cosine_lowpass(n: usize, cutoff: f64, smoothing: f64) -> Vec<f64> {
+    assert!(cutoff >= 0.0 && smoothing >= 0.0, "cutoff and smoothing must be non-negative");
+    (0..n)
+        .map(|i| {
+            let f = centered_frequency(i, n).abs();
+            if f <= cutoff {
+                1.0
+            } else if f >= cutoff + smoothing {
+                0.0
+            } else {
+                0.5 * (1.0 + (std::f64::consts::PI * (f - cutoff) / smoothing).cos())
+            }
+        })
+        .collect()
+}
+
+#[test]
+fn test_profiles() {
+    let g = gaussian(8, 0.1);
+    assert_eq!(g[4], 1.0);
+    assert!((g[3] - g[5]).abs() < 1e-15 && g[3] < 1.0);
+    assert!((g[2] - (-0.0625f64 / 0.02).exp()).abs() < 1e-15);
+
+    let lp = raised_cosine_lowpass(9, 0.3, 0.1);
+    assert_eq!(&lp[3..6], [1.0, 1.0, 1.0]);
+    assert_eq!(lp[0], 0.0);
+    // 3/9 cycles per pixel is a third of the way down the roll-off.
+    assert!((lp[1] - 0.75).abs() < 1e-12 && lp[1] == lp[7]);
+    assert_eq!(raised_cosine_lowpass(4, 0.25, 0.0), [0.0, 1.0, 1.0, 1.0]);
+}
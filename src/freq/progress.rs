@@ -0,0 +1,59 @@
+//! Progress reports from long-running operations.
+
+/// How far one stage of an operation has got: `completed` of `total` units, such as
+/// rows, columns, tile rows or files. Within a stage `completed` only grows, and the
+/// last report has `completed == total`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Progress {
+    /// What is being done, such as `fft_rows`.
+    pub stage: &'static str,
+    /// Units finished so far, at least 1.
+    pub completed: usize,
+    /// Units in the whole stage.
+    pub total: usize,
+}
+
+/// Roughly how many reports a stage makes, so the callback costs nothing next to the
+/// work however large the image.
+const REPORTS_PER_STAGE: usize = 64;
+
+/// Pass `completed` of `total` to `progress` if it falls on a reporting step or finishes
+/// the stage. Call it after each unit with `completed` counting from 1.
+pub(crate) fn report(progress: &mut dyn FnMut(Progress), stage: &'static str, completed: usize, total: usize) {
+    let step = total.div_ceil(REPORTS_PER_STAGE).max(1);
+    if completed == total || completed.is_multiple_of(step) {
+        progress(Progress { stage, completed, total });
+    }
+}
+
+#[cfg(test)]
+pub(crate) fn collect(reports: &mut Vec<Progress>) -> impl FnMut(Progress) + '_ {
+    |p| reports.push(p)
+}
+
+/// Every report of `stage`, checking they count up to the stage's total.
+#[cfg(test)]
+pub(crate) fn check_stage(reports: &[Progress], stage: &str, total: usize) -> usize {
+    let counts: Vec<usize> = reports.iter().filter(|p| p.stage == stage).map(|p| p.completed).collect();
+    assert!(!counts.is_empty(), "no reports for {}", stage);
+    assert!(counts.windows(2).all(|w| w[0] < w[1]), "{}: {:?}", stage, counts);
+    assert!(reports.iter().filter(|p| p.stage == stage).all(|p| p.total == total));
+    assert_eq!(counts.last(), Some(&total), "{}", stage);
+    counts.len()
+}
+
+#[test]
+fn test_report_steps() {
+    let mut reports = Vec::new();
+    for i in 1..=1000 {
+        report(&mut collect(&mut reports), "rows", i, 1000);
+    }
+    // Steps of 16 rows, then the last row.
+    assert_eq!(check_stage(&reports, "rows", 1000), 1000 / 16 + 1);
+
+    reports.clear();
+    for i in 1..=5 {
+        report(&mut collect(&mut reports), "rows", i, 5);
+    }
+    assert_eq!(check_stage(&reports, "rows", 5), 5);
+}
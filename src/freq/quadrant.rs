@@ -0,0 +1,104 @@
+//! Half-planes and quadrants of a centered (fftshifted) spectrum.
+//!
+//! After [`FreqImage::fftshift`] the DC bin sits at `(width / 2, height / 2)`, so row `y`
+//! holds vertical frequency `y - height / 2` and column `x` horizontal frequency
+//! `x - width / 2`. For even sizes the first row (or column) is the Nyquist frequency,
+//! which has no positive counterpart. The DC row and column belong to the top half and
+//! the left half, so a height of `h` splits into `h / 2 + 1` top rows and `(h - 1) / 2`
+//! bottom rows: 4 and 3 for `h = 7`, 5 and 3 for `h = 8`.
+
+use super::FreqImage;
+
+/// One quarter of a centered spectrum. Top and left include the DC row and column, so
+/// the DC bin is the bottom-right corner of [`Quadrant::TopLeft`]; see the module docs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Quadrant {
+    /// Rows `0..=h/2`, columns `0..=w/2`.
+    TopLeft,
+    /// Rows `0..=h/2`, columns `w/2+1..w`.
+    TopRight,
+    /// Rows `h/2+1..h`, columns `0..=w/2`.
+    BottomLeft,
+    /// Rows `h/2+1..h`, columns `w/2+1..w`.
+    BottomRight,
+}
+
+impl FreqImage {
+    /// Rows `0..=height/2` of this centered spectrum: every vertical frequency up to and
+    /// including zero. For a real image the rest is the conjugate mirror of these; see
+    /// [`mirror_from_top_half`].
+    pub fn top_half(&self) -> FreqImage {
+        self.crop(0, 0, self.width, (self.height / 2 + 1).min(self.height))
+    }
+
+    /// The quadrant `q` of this centered spectrum. Quadrants on the right or bottom are
+    /// empty along an axis of length 1.
+    pub fn quadrant(&self, q: Quadrant) -> FreqImage {
+        let (left, top) = ((self.width / 2 + 1).min(self.width), (self.height / 2 + 1).min(self.height));
+        let (right, bottom) = (self.width - left, self.height - top);
+        match q {
+            Quadrant::TopLeft => self.crop(0, 0, left, top),
+            Quadrant::TopRight => self.crop(left, 0, right, top),
+            Quadrant::BottomLeft => self.crop(0, top, left, bottom),
+            Quadrant::BottomRight => self.crop(left, top, right, bottom),
+        }
+    }
+}
+
+/// Rebuild the full centered spectrum of a real image, `full_height` rows tall, from
+/// its [`FreqImage::top_half`]: each bottom row is the conjugate of the point-mirrored
+/// top row, `X[-u, -v] = conj(X[u, v])`. The top half is copied unchanged.
+///
+/// # Panics
+/// Panics unless `half` has `full_height / 2 + 1` rows.
+pub fn mirror_from_top_half(half: &FreqImage, full_height: u32) -> FreqImage {
+    assert_eq!(
+        half.height,
+        full_height / 2 + 1,
+        "a top half of a {}-row spectrum has {} rows",
+        full_height,
+        full_height / 2 + 1
+    );
+    let (w, cx, cy) = (half.width, half.width / 2, full_height / 2);
+    let mut data = half.data.clone();
+    for y in half.height..full_height {
+        let mirror_y = 2 * cy - y;
+        data.extend((0..w).map(|x| half[((2 * cx + w - x) % w, mirror_y)].conj()));
+    }
+    FreqImage { width: w, height: full_height, data }
+}
+
+#[test]
+fn test_mirror_from_top_half() {
+    let fi = FreqImage::open("img/mandrill.jpg").unwrap();
+    for (w, h) in [(fi.width, fi.height), (fi.width - 1, fi.height - 1), (fi.width, fi.height - 1)] {
+        let mut spectrum = fi.crop(0, 0, w, h);
+        spectrum.fft_forward();
+        spectrum.fftshift();
+        let half = spectrum.top_half();
+        assert_eq!(half.height, h / 2 + 1);
+        let rebuilt = mirror_from_top_half(&half, h);
+        assert!(rebuilt.approx_eq(&spectrum, 1e-9), "{}x{}", w, h);
+    }
+}
+
+#[test]
+fn test_quadrants() {
+    for (w, h) in [(6, 4), (5, 7), (1, 3)] {
+        let fi = super::ramp(w, h);
+        let quads = [Quadrant::TopLeft, Quadrant::TopRight, Quadrant::BottomLeft, Quadrant::BottomRight].map(|q| fi.quadrant(q));
+        assert_eq!(quads.iter().map(|q| q.data.len()).sum::<usize>(), fi.data.len());
+        assert_eq!((quads[0].width, quads[0].height), (w / 2 + 1, h / 2 + 1));
+        // DC is the last bin of the top-left quadrant.
+        assert_eq!(quads[0].data.last(), Some(&fi[(w / 2, h / 2)]));
+        assert_eq!(quads[3].data.first(), fi.get(w / 2 + 1, h / 2 + 1).as_ref());
+        assert_eq!(fi.top_half().data.len(), quads[0].data.len() + quads[1].data.len());
+    }
+}
+
+#[test]
+#[should_panic(expected = "a top half of a 8-row spectrum has 5 rows")]
+fn test_mirror_from_top_half_wrong_height() {
+    mirror_from_top_half(&super::ramp(4, 4), 8);
+}
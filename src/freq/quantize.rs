@@ -0,0 +1,127 @@
+//! Simulated quantization of spectral coefficients, as a lossy codec would apply it.
+
+use rustfft::num_complex::Complex;
+
+use super::FreqImage;
+
+/// Which part of each coefficient [`FreqImage::quantize_spectrum`] rounds.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum QuantizeMode {
+    /// Round the real and imaginary parts independently.
+    #[default]
+    Components,
+    /// Round the magnitude and keep the phase.
+    Magnitude,
+}
+
+impl FreqImage {
+    /// Round each coefficient of a spectrum, as chosen by `mode`, to the nearest multiple
+    /// of `step`, and set values within `dead_zone` of zero to zero outright, as coders do
+    /// to drop weak coefficients cheaply. A `step` of zero only applies the dead zone.
+    /// Returns how many non-zero coefficients became zero.
+    ///
+    /// # Panics
+    /// Panics if `step` or `dead_zone` is negative or not finite.
+    pub fn quantize_spectrum(&mut self, step: f64, dead_zone: f64, mode: QuantizeMode) -> usize {
+        assert!(step.is_finite() && step >= 0.0, "quantization step must be finite and non-negative");
+        assert!(dead_zone.is_finite() && dead_zone >= 0.0, "dead zone must be finite and non-negative");
+        let quantize = |v: f64| {
+            if v.abs() < dead_zone {
+                0.0
+            } else if step > 0.0 {
+                (v / step).round() * step
+            } else {
+                v
+            }
+        };
+        let mut zeroed = 0;
+        for c in self.data.iter_mut() {
+            let before = *c;
+            *c = match mode {
+                QuantizeMode::Components => Complex::new(quantize(c.re), quantize(c.im)),
+                QuantizeMode::Magnitude => {
+                    let r = c.norm();
+                    match quantize(r) {
+                        q if q == r => *c,
+                        0.0 => Complex::new(0.0, 0.0),
+                        q => *c * (q / r),
+                    }
+                }
+            };
+            if before != Complex::new(0.0, 0.0) && *c == Complex::new(0.0, 0.0) {
+                zeroed += 1;
+            }
+        }
+        zeroed
+    }
+
+    /// Shannon entropy in bits of the coefficient magnitudes binned as by
+    /// [`FreqImage::magnitude_histogram`], a rough measure of how many bits per
+    /// coefficient a coder would need. 0 when every magnitude falls in one bin.
+    ///
+    /// # Panics
+    /// Panics if `bins` is zero.
+    pub fn spectral_entropy(&self, bins: usize) -> f64 {
+        let histogram = self.magnitude_histogram(bins, false);
+        let total: usize = histogram.counts.iter().sum();
+        histogram
+            .counts
+            .iter()
+            .filter(|&&n| n > 0)
+            .map(|&n| {
+                let p = n as f64 / total as f64;
+                -p * p.log2()
+            })
+            .sum()
+    }
+}
+
+#[test]
+fn test_quantize_spectrum() {
+    let mut spectrum = FreqImage::open("img/mandrill.jpg").unwrap();
+    spectrum.fft_forward();
+    for mode in [QuantizeMode::Components, QuantizeMode::Magnitude] {
+        let mut same = spectrum.clone();
+        assert_eq!(same.quantize_spectrum(0.0, 0.0, mode), 0);
+        assert_eq!(same, spectrum);
+
+        // Only DC is at least half the step.
+        let mut coarse = spectrum.clone();
+        let step = spectrum.data[0].norm();
+        let nonzero = spectrum.data.iter().filter(|c| c.norm() > 0.0).count();
+        assert_eq!(coarse.quantize_spectrum(step, 0.0, mode), nonzero - 1, "{:?}", mode);
+        assert!((coarse.data[0] - spectrum.data[0]).norm() < 1e-6 * step);
+    }
+
+    let mut dead = spectrum.clone();
+    let zeroed = dead.quantize_spectrum(0.0, 1.0, QuantizeMode::Components);
+    assert_eq!(zeroed, spectrum.data.iter().filter(|c| c.norm() > 0.0 && c.re.abs() < 1.0 && c.im.abs() < 1.0).count());
+}
+
+#[test]
+fn test_quantization_degrades_quality() {
+    use super::metrics::psnr;
+
+    let original = FreqImage::open("img/mandrill.jpg").unwrap();
+    let mut spectrum = original.clone();
+    spectrum.fft_forward();
+    let mut previous = f64::INFINITY;
+    for step in [1.0, 10.0, 100.0] {
+        let mut quantized = spectrum.clone();
+        quantized.quantize_spectrum(step, 0.0, QuantizeMode::Components);
+        quantized.fft_inverse();
+        let quality = psnr(&quantized, &original, 1.0).unwrap();
+        assert!(quality < previous, "step {}: {} dB", step, quality);
+        previous = quality;
+    }
+}
+
+#[test]
+fn test_spectral_entropy() {
+    let flat = FreqImage { width: 4, height: 4, data: vec![Complex::new(1.0, 0.0); 16] };
+    assert_eq!(flat.spectral_entropy(8), 0.0);
+    // Half the magnitudes at 0 and half at 1: one bit.
+    let halves = FreqImage { data: (0..16).map(|i| Complex::new((i % 2) as f64, 0.0)).collect(), ..flat };
+    assert!((halves.spectral_entropy(8) - 1.0).abs() < 1e-12);
+}
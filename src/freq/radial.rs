@@ -0,0 +1,66 @@
+//! Radial gain profiles for [`apply_radial_filter`](crate::FreqImage::apply_radial_filter).
+//!
+//! Each profile maps a distance from the spectrum center, as a fraction of the image
+//! diagonal, to a gain. They are the profiles behind
+//! [`low_pass_mask`](crate::FreqImage::low_pass_mask) and friends, so filtering with a profile gives exactly the same result as building the
+//! mask and applying it.
+
+use crate::FreqError;
+
+/// 1 inside `cutoff`, 0 from `cutoff + smoothing` on, with a smooth roll-off between.
+pub fn low_pass(cutoff: f64, smoothing: f64) -> impl Fn(f64) -> f64 + Copy {
+    let inner_sqr = cutoff.powi(2);
+    let outer_sqr = (cutoff + smoothing).powi(2);
+    move |r| {
+        let r_sqr = r * r;
+        if r_sqr < inner_sqr {
+            1.0
+        } else if r_sqr >= outer_sqr {
+            0.0
+        } else {
+            ((outer_sqr - r_sqr) / (outer_sqr - inner_sqr)).powi(2)
+        }
+    }
+}
+
+/// The complement of [`low_pass`], so the two always sum to one.
+pub fn high_pass(cutoff: f64, smoothing: f64) -> impl Fn(f64) -> f64 + Copy {
+    let low = low_pass(cutoff, smoothing);
+    move |r| 1.0 - low(r)
+}
+
+/// The annulus between `low` and `high`: a [`low_pass`] at `high` minus one at `low`.
+///
+/// # Panics
+/// Panics unless `0 <= low < high` with both finite.
+pub fn band_pass(low: f64, high: f64, smoothing: f64) -> impl Fn(f64) -> f64 + Copy {
+    check_band(low, high).unwrap_or_else(|err| panic!("{}", err));
+    let (inner, outer) = (low_pass(low, smoothing), low_pass(high, smoothing));
+    move |r| (outer(r) - inner(r)).max(0.0)
+}
+
+/// [`FreqError::InvalidCutoff`] unless `0 <= low < high` with both finite.
+pub(crate) fn check_band(low: f64, high: f64) -> Result<(), FreqError> {
+    if low >= 0.0 && low < high && high.is_finite() {
+        Ok(())
+    } else {
+        Err(FreqError::InvalidCutoff { low, high })
+    }
+}
+
+#[test]
+fn test_profiles() {
+    let lp = low_pass(0.1, 0.1);
+    assert_eq!(lp(0.0), 1.0);
+    assert_eq!(lp(0.2), 0.0);
+    assert!(lp(0.15) > 0.0 && lp(0.15) < 1.0);
+    assert_eq!(high_pass(0.1, 0.1)(0.15) + lp(0.15), 1.0);
+    let bp = band_pass(0.1, 0.3, 0.0);
+    assert_eq!((bp(0.05), bp(0.2), bp(0.3)), (0.0, 1.0, 0.0));
+}
+
+#[test]
+#[should_panic(expected = "invalid cutoff")]
+fn test_band_pass_invalid() {
+    let _ = band_pass(0.3, 0.1, 0.0);
+}
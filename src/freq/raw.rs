@@ -0,0 +1,174 @@
+//! A minimal raw binary format for checkpointing spectra.
+//!
+//! Layout (all integers and floats little-endian):
+//!
+//! | offset | size | field                                  |
+//! |--------|------|----------------------------------------|
+//! | 0      | 4    | magic `b"FQSP"`                        |
+//! | 4      | 1    | format version (currently 1)           |
+//! | 5      | 1    | bytes per float: 8 (f64) or 4 (f32)    |
+//! | 6      | 4    | width                                  |
+//! | 10     | 4    | height                                 |
+//! | 14     | ...  | `width * height` interleaved `re, im`  |
+
+use std::path::Path;
+
+use rustfft::num_complex::Complex;
+
+use super::FreqImage;
+use crate::FreqError;
+
+const RAW_MAGIC: &[u8; 4] = b"FQSP";
+const RAW_VERSION: u8 = 1;
+const RAW_HEADER_LEN: usize = 14;
+
+impl FreqImage {
+    /// Write the buffer to `path` in the raw format at full f64 precision.
+    pub fn save_raw<P: AsRef<Path>>(&self, path: P) -> Result<(), FreqError> {
+        std::fs::write(path, self.to_bytes())?;
+        Ok(())
+    }
+
+    /// Read a buffer written by [`FreqImage::save_raw`].
+    pub fn load_raw<P: AsRef<Path>>(path: P) -> Result<Self, FreqError> {
+        Self::from_bytes(&std::fs::read(path)?)
+    }
+
+    /// Serialize the buffer in the raw format at full f64 precision.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.raw_header(8);
+        for c in &self.data {
+            bytes.extend_from_slice(&c.re.to_le_bytes());
+            bytes.extend_from_slice(&c.im.to_le_bytes());
+        }
+        bytes
+    }
+
+    /// Serialize the buffer in the raw format, rounding every value to f32.
+    pub fn to_bytes_f32(&self) -> Vec<u8> {
+        let mut bytes = self.raw_header(4);
+        for c in &self.data {
+            bytes.extend_from_slice(&(c.re as f32).to_le_bytes());
+            bytes.extend_from_slice(&(c.im as f32).to_le_bytes());
+        }
+        bytes
+    }
+
+    /// Parse a buffer produced by [`FreqImage::to_bytes`] or [`FreqImage::to_bytes_f32`].
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, FreqError> {
+        let invalid = |msg: String| FreqError::InvalidFormat(format!("raw spectrum: {}", msg));
+
+        if bytes.len() < RAW_HEADER_LEN {
+            return Err(invalid(format!(
+                "truncated header: expected {} bytes, got {}",
+                RAW_HEADER_LEN,
+                bytes.len()
+            )));
+        }
+        if &bytes[..4] != RAW_MAGIC {
+            return Err(invalid(format!("bad magic {:?}, expected {:?}", &bytes[..4], RAW_MAGIC)));
+        }
+        if bytes[4] != RAW_VERSION {
+            return Err(invalid(format!("unsupported version {}", bytes[4])));
+        }
+        let float_size = bytes[5] as usize;
+        if float_size != 8 && float_size != 4 {
+            return Err(invalid(format!("bad float size {}, expected 4 or 8", float_size)));
+        }
+        let width = u32::from_le_bytes(bytes[6..10].try_into().unwrap());
+        let height = u32::from_le_bytes(bytes[10..14].try_into().unwrap());
+
+        let expected = (width as usize)
+            .checked_mul(height as usize)
+            .and_then(|n| n.checked_mul(2 * float_size))
+            .ok_or_else(|| invalid(format!("dimensions {}x{} are too large", width, height)))?;
+        let payload = &bytes[RAW_HEADER_LEN..];
+        if payload.len() != expected {
+            return Err(invalid(format!(
+                "{}x{} payload should be {} bytes, got {}",
+                width,
+                height,
+                expected,
+                payload.len()
+            )));
+        }
+
+        let data = if float_size == 8 {
+            payload
+                .chunks_exact(16)
+                .map(|c| {
+                    let re = f64::from_le_bytes(c[..8].try_into().unwrap());
+                    let im = f64::from_le_bytes(c[8..].try_into().unwrap());
+                    Complex::new(re, im)
+                })
+                .collect()
+        } else {
+            payload
+                .chunks_exact(8)
+                .map(|c| {
+                    let re = f32::from_le_bytes(c[..4].try_into().unwrap());
+                    let im = f32::from_le_bytes(c[4..].try_into().unwrap());
+                    Complex::new(re as f64, im as f64)
+                })
+                .collect()
+        };
+        Ok(FreqImage { width, height, data })
+    }
+
+    fn raw_header(&self, float_size: u8) -> Vec<u8> {
+        let mut header = Vec::with_capacity(RAW_HEADER_LEN + self.data.len() * 2 * float_size as usize);
+        header.extend_from_slice(RAW_MAGIC);
+        header.push(RAW_VERSION);
+        header.push(float_size);
+        header.extend_from_slice(&self.width.to_le_bytes());
+        header.extend_from_slice(&self.height.to_le_bytes());
+        header
+    }
+}
+
+#[cfg(test)]
+fn mandrill_spectrum() -> FreqImage {
+    let mut fi = FreqImage::open("img/mandrill.jpg").unwrap();
+    fi.fft_forward();
+    fi
+}
+
+#[test]
+fn test_raw_round_trip() {
+    let fi = mandrill_spectrum();
+    let path = std::env::temp_dir().join(format!("freqshow_raw_{}.fqsp", std::process::id()));
+    fi.save_raw(&path).unwrap();
+    let back = FreqImage::load_raw(&path).unwrap();
+    std::fs::remove_file(path).unwrap();
+
+    assert_eq!(back, fi);
+}
+
+#[test]
+fn test_raw_f32_round_trip() {
+    let fi = mandrill_spectrum();
+    let back = FreqImage::from_bytes(&fi.to_bytes_f32()).unwrap();
+
+    assert_eq!((back.width, back.height), (fi.width, fi.height));
+    for (a, b) in back.data.iter().zip(&fi.data) {
+        assert!((a - b).norm() <= 1e-6 * b.norm().max(1.0));
+    }
+}
+
+#[test]
+fn test_raw_rejects_truncation() {
+    let bytes = mandrill_spectrum().to_bytes();
+    let err = FreqImage::from_bytes(&bytes[..bytes.len() - 3]).unwrap_err();
+    assert!(err.to_string().contains("payload should be"));
+
+    let err = FreqImage::from_bytes(&bytes[..6]).unwrap_err();
+    assert!(err.to_string().contains("truncated header"));
+}
+
+#[test]
+fn test_raw_rejects_bad_magic() {
+    let mut bytes = mandrill_spectrum().to_bytes();
+    bytes[0] = b'X';
+    let err = FreqImage::from_bytes(&bytes).unwrap_err();
+    assert!(err.to_string().contains("bad magic"));
+}
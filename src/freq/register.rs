@@ -0,0 +1,282 @@
+//! Image registration by phase correlation.
+
+use std::f64::consts::PI;
+
+use rustfft::num_complex::Complex;
+
+use super::{FreqImage, WindowKind};
+use crate::FreqError;
+
+/// The translation recovered by [`phase_correlate`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Registration {
+    /// Horizontal shift in pixels, positive to the right.
+    pub dx: f64,
+    /// Vertical shift in pixels, positive downward.
+    pub dy: f64,
+    /// Height of the correlation peak: 1 for an exact integer shift, lower as the
+    /// images decorrelate.
+    pub peak_value: f64,
+}
+
+impl Registration {
+    /// Shift `moving` into line with the reference it was registered against: if
+    /// `reg = phase_correlate(reference, moving)`, then `reg.apply(moving) ≈ reference`.
+    pub fn apply(&self, moving: &FreqImage) -> FreqImage {
+        moving.translate(self.dx, self.dy)
+    }
+}
+
+/// Estimate the shift that moves `b` onto `a`, i.e. `a ≈ b.translate(dx, dy)`, from
+/// two spatial-domain images of equal size.
+///
+/// The cross-power spectrum is normalized to unit magnitude, so its inverse is a sharp
+/// peak at the shift. The integer peak is refined to sub-pixel precision from its
+/// neighbours using the sinc-ratio estimator of Foroosh et al., and shifts past half
+/// the image size are reported as negative.
+pub fn phase_correlate(a: &FreqImage, b: &FreqImage) -> Result<Registration, FreqError> {
+    if (a.width, a.height) != (b.width, b.height) {
+        return Err(FreqError::DimensionMismatch {
+            expected: (a.width, a.height),
+            got: (b.width, b.height),
+        });
+    }
+    let (mut cross, mut fb) = (a.clone(), b.clone());
+    cross.fft_forward();
+    fb.fft_forward();
+    cross.mul_conj_elementwise(&fb)?;
+    for c in cross.data.iter_mut() {
+        let norm = c.norm();
+        *c = if norm > 1e-12 { *c / norm } else { Complex::new(0.0, 0.0) };
+    }
+    cross.fft_inverse();
+
+    let (w, h) = (a.width, a.height);
+    let (mut px, mut py, mut peak) = (0, 0, f64::NEG_INFINITY);
+    for (x, y, c) in cross.enumerate_pixels() {
+        if c.re > peak {
+            (px, py, peak) = (x, y, c.re);
+        }
+    }
+    let at = |x: u32, y: u32| cross[(x % w, y % h)].re;
+    let fx = subpixel(at(px + w - 1, py), peak, at(px + 1, py));
+    let fy = subpixel(at(px, py + h - 1), peak, at(px, py + 1));
+    Ok(Registration {
+        dx: unwrap(px, w) + fx,
+        dy: unwrap(py, h) + fy,
+        peak_value: peak,
+    })
+}
+
+/// A similarity transform recovered by [`register_full`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FullRegistration {
+    /// Rotation in degrees, counter-clockwise as displayed.
+    pub angle: f64,
+    /// Scale factor.
+    pub scale: f64,
+    /// Translation left after undoing the rotation and scale.
+    pub translation: Registration,
+}
+
+impl FullRegistration {
+    /// Rotate, scale and shift `moving` into line with the reference it was registered
+    /// against, in the order [`register_full`] measured them.
+    pub fn apply(&self, moving: &FreqImage) -> FreqImage {
+        self.translation.apply(&moving.rotate_scale(self.angle, self.scale))
+    }
+}
+
+/// Angle samples of the log-polar grid, spanning `[0°, 180°)`.
+const ANGLE_BINS: u32 = 512;
+/// Log-radius samples of the log-polar grid.
+const RADIUS_BINS: u32 = 256;
+/// Log-polar radius limits in cycles per pixel.
+const RHO_MIN: f64 = 0.01;
+const RHO_MAX: f64 = 0.45;
+
+/// Estimate the rotation (degrees, counter-clockwise as displayed) and scale that take
+/// `b` to `a`, i.e. `a ≈ b.rotate_scale(angle, scale)` up to translation.
+///
+/// The Hann-windowed magnitude spectra are resampled onto a log-radius × angle grid,
+/// where rotation and scale become shifts, and phase-correlated there. Magnitude
+/// spectra are symmetric, so the angle is only known modulo 180° and is reported in
+/// `(-90°, 90°]`; [`register_full`] resolves the ambiguity. Sizes need not match.
+pub fn estimate_rotation_scale(a: &FreqImage, b: &FreqImage) -> (f64, f64) {
+    let shift = phase_correlate(&log_polar_spectrum(a), &log_polar_spectrum(b)).unwrap();
+    let angle = shift.dx * 180.0 / ANGLE_BINS as f64;
+    let scale = (-shift.dy * (RHO_MAX / RHO_MIN).ln() / RADIUS_BINS as f64).exp();
+    (angle, scale)
+}
+
+/// Recover rotation, scale and translation taking `b` to `a`: `b` is rotated and scaled
+/// into line with `a` using [`estimate_rotation_scale`], trying both angles the magnitude
+/// spectrum allows, and the remaining shift is found with [`phase_correlate`].
+pub fn register_full(a: &FreqImage, b: &FreqImage) -> Result<FullRegistration, FreqError> {
+    let (angle, scale) = estimate_rotation_scale(a, b);
+    let mut best: Option<FullRegistration> = None;
+    for angle in [angle, angle - 180.0] {
+        let translation = phase_correlate(a, &b.rotate_scale(angle, scale))?;
+        if best.is_none_or(|r| translation.peak_value > r.translation.peak_value) {
+            best = Some(FullRegistration { angle, scale, translation });
+        }
+    }
+    let mut best = best.unwrap();
+    if best.angle <= -180.0 {
+        best.angle += 360.0;
+    }
+    Ok(best)
+}
+
+/// The log magnitude spectrum of a Hann-windowed image sampled on the log-polar grid:
+/// column `i` is angle `i·180°/ANGLE_BINS`, row `j` is radius
+/// `RHO_MIN·(RHO_MAX/RHO_MIN)^(j/RADIUS_BINS)` cycles per pixel.
+fn log_polar_spectrum(fi: &FreqImage) -> FreqImage {
+    let (w, h) = (fi.width as f64, fi.height as f64);
+    let mut spectrum = fi.clone();
+    spectrum.apply_window(WindowKind::Hann);
+    spectrum.fft_forward();
+    spectrum.fftshift();
+    for c in spectrum.data.iter_mut() {
+        *c = Complex::new(c.norm().ln_1p(), 0.0);
+    }
+
+    let (cx, cy) = ((w / 2.0).floor(), (h / 2.0).floor());
+    let growth = (RHO_MAX / RHO_MIN).ln() / RADIUS_BINS as f64;
+    let mut out = FreqImage {
+        width: ANGLE_BINS,
+        height: RADIUS_BINS,
+        data: Vec::with_capacity((ANGLE_BINS * RADIUS_BINS) as usize),
+    };
+    for j in 0..RADIUS_BINS {
+        let rho = RHO_MIN * (growth * j as f64).exp();
+        for i in 0..ANGLE_BINS {
+            let theta = PI * i as f64 / ANGLE_BINS as f64;
+            // Spectrum rows run opposite to displayed "up", hence the minus sign.
+            let (kx, ky) = (cx + rho * theta.cos() * w, cy - rho * theta.sin() * h);
+            out.data.push(spectrum.sample_bilinear(kx, ky).unwrap_or_default());
+        }
+    }
+    out
+}
+
+/// Offset of the true peak from the sampled maximum `c`, given its left and right neighbours.
+fn subpixel(left: f64, c: f64, right: f64) -> f64 {
+    if right > left && right > 0.0 {
+        right / (right + c)
+    } else if left > 0.0 {
+        -left / (left + c)
+    } else {
+        0.0
+    }
+}
+
+/// Map a circular index to a signed shift in `(-n/2, n/2]`.
+fn unwrap(i: u32, n: u32) -> f64 {
+    if 2 * i > n {
+        i as f64 - n as f64
+    } else {
+        i as f64
+    }
+}
+
+#[cfg(test)]
+fn texture(width: u32, height: u32) -> FreqImage {
+    super::noise(width, height, 5)
+}
+
+#[test]
+fn test_phase_correlate_integer() {
+    let b = texture(64, 48);
+    let a = b.translate(12.0, -7.0);
+    let reg = phase_correlate(&a, &b).unwrap();
+    assert!((reg.dx - 12.0).abs() < 0.05, "{:?}", reg);
+    assert!((reg.dy + 7.0).abs() < 0.05, "{:?}", reg);
+    assert!((reg.peak_value - 1.0).abs() < 1e-6);
+}
+
+#[test]
+fn test_phase_correlate_fractional() {
+    let b = texture(64, 48);
+    let a = b.translate(3.25, -1.75);
+    let reg = phase_correlate(&a, &b).unwrap();
+    assert!((reg.dx - 3.25).abs() < 0.05, "{:?}", reg);
+    assert!((reg.dy + 1.75).abs() < 0.05, "{:?}", reg);
+}
+
+#[test]
+fn test_apply_cancels_shift() {
+    let reference = texture(64, 48);
+    let moving = reference.translate(-7.0, 4.0);
+    let reg = phase_correlate(&reference, &moving).unwrap();
+    let aligned = reg.apply(&moving);
+    let err = aligned.data.iter().zip(&reference.data).map(|(a, b)| (a - b).norm()).fold(0.0, f64::max);
+    assert!(err < 1e-6, "max error {}", err);
+
+    let moving = reference.translate(-5.5, 3.25);
+    let reg = phase_correlate(&reference, &moving).unwrap();
+    assert!((reg.dx - 5.5).abs() < 0.05 && (reg.dy + 3.25).abs() < 0.05, "{:?}", reg);
+    let residual = phase_correlate(&reference, &reg.apply(&moving)).unwrap();
+    assert!(residual.dx.abs() < 0.05 && residual.dy.abs() < 0.05, "{:?}", residual);
+
+    let full = FullRegistration { angle: 0.0, scale: 1.0, translation: reg };
+    assert_eq!(full.apply(&moving), reg.apply(&moving));
+}
+
+#[test]
+fn test_phase_correlate_dimension_mismatch() {
+    assert!(matches!(
+        phase_correlate(&texture(8, 8), &texture(8, 4)),
+        Err(FreqError::DimensionMismatch { .. })
+    ));
+}
+
+#[cfg(test)]
+fn scene(angle_deg: f64, scale: f64) -> FreqImage {
+    // A fixed set of elliptical blobs evaluated analytically under the inverse of
+    // `rotate_scale(angle_deg, scale)`, so no resampling error enters the fixture.
+    let size = 128u32;
+    let c = (size as f64 - 1.0) / 2.0;
+    let (sin, cos) = angle_deg.to_radians().sin_cos();
+    let mut rng = super::rng::SplitMix64(9);
+    let mut rand = || rng.next_f64();
+    let blobs: Vec<[f64; 3]> = (0..60).map(|_| [rand() * 100.0 - 50.0, rand() * 100.0 - 50.0, rand()]).collect();
+    let mut fi = FreqImage {
+        width: size,
+        height: size,
+        data: vec![Complex::new(0.0, 0.0); (size * size) as usize],
+    };
+    for (x, y, px) in fi.enumerate_pixels_mut() {
+        let (u, v) = ((x as f64 - c) / scale, (y as f64 - c) / scale);
+        let (sx, sy) = (u * cos - v * sin, u * sin + v * cos);
+        for &[bx, by, orient] in &blobs {
+            let (s, co) = (orient * PI).sin_cos();
+            let (dx, dy) = (sx - bx, sy - by);
+            let (along, across) = (dx * co + dy * s, -dx * s + dy * co);
+            px.re += (-(along * along) / 18.0 - (across * across) / 2.0).exp();
+        }
+    }
+    fi
+}
+
+#[test]
+fn test_estimate_rotation_scale() {
+    let b = scene(0.0, 1.0);
+    for (angle, scale) in [(5.0, 1.0), (15.0, 1.0), (0.0, 1.1), (-10.0, 1.1)] {
+        let a = scene(angle, scale);
+        let (got_angle, got_scale) = estimate_rotation_scale(&a, &b);
+        assert!((got_angle - angle).abs() < 0.5, "angle {} got {}", angle, got_angle);
+        assert!((got_scale / scale - 1.0).abs() < 0.02, "scale {} got {}", scale, got_scale);
+    }
+}
+
+#[test]
+fn test_register_full() {
+    let b = scene(0.0, 1.0);
+    let a = scene(15.0, 1.0).translate(6.0, -4.0);
+    let reg = register_full(&a, &b).unwrap();
+    assert!((reg.angle - 15.0).abs() < 0.5, "{:?}", reg);
+    assert!((reg.scale - 1.0).abs() < 0.02, "{:?}", reg);
+    assert!((reg.translation.dx - 6.0).abs() < 0.5, "{:?}", reg);
+    assert!((reg.translation.dy + 4.0).abs() < 0.5, "{:?}", reg);
+}
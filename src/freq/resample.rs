@@ -0,0 +1,199 @@
+//! Band-limited resampling by resizing the spectrum.
+
+use rustfft::num_complex::Complex;
+
+use super::FreqImage;
+
+impl FreqImage {
+    /// Sinc-interpolate a spatial-domain image to `factor·width × factor·height`
+    /// by zero-padding its spectrum. Brightness is preserved.
+    ///
+    /// # Panics
+    /// Panics if `factor` is zero.
+    pub fn upscale(&self, factor: u32) -> FreqImage {
+        assert!(factor > 0, "upscale factor must be positive");
+        self.resize_spectral(self.width * factor, self.height * factor)
+    }
+
+    /// Band-limit and subsample a spatial-domain image to `width/factor × height/factor`
+    /// (rounded down, at least 1) by truncating its spectrum. Brightness is preserved.
+    ///
+    /// # Panics
+    /// Panics if `factor` is zero.
+    pub fn downscale(&self, factor: u32) -> FreqImage {
+        assert!(factor > 0, "downscale factor must be positive");
+        self.resize_spectral((self.width / factor).max(1), (self.height / factor).max(1))
+    }
+
+    /// Resample a spatial-domain image to `new_width × new_height` by zero-padding or
+    /// truncating its centered spectrum, so the result is band-limited and free of aliasing.
+    ///
+    /// Even-length axes get the usual Nyquist treatment: growing splits the old Nyquist
+    /// bin across both signs, shrinking folds `±new/2` into the new one. Brightness is preserved.
+    pub fn resize_spectral(&self, new_width: u32, new_height: u32) -> FreqImage {
+        let mut spectrum = self.clone();
+        spectrum.fft_forward();
+
+        let cols = axis_map(self.width as usize, new_width as usize);
+        let rows = axis_map(self.height as usize, new_height as usize);
+        // The inverse FFT divides by the new pixel count; undo that relative to the old one.
+        let gain = (new_width as f64 * new_height as f64) / (self.width as f64 * self.height as f64);
+        let mut out = FreqImage {
+            width: new_width,
+            height: new_height,
+            data: vec![Complex::new(0.0, 0.0); new_width as usize * new_height as usize],
+        };
+        for (x, y, &c) in spectrum.enumerate_pixels() {
+            for &(dy, wy) in &rows[y as usize] {
+                for &(dx, wx) in &cols[x as usize] {
+                    out.data[dy * new_width as usize + dx] += c * (wx * wy * gain);
+                }
+            }
+        }
+        out.fft_inverse();
+        out
+    }
+
+    /// Resize with [`FreqImage::resize_spectral`], keeping the aspect ratio, until the
+    /// image just covers `width × height`, then take the central `width × height`
+    /// region. The image is unchanged if it is already that size.
+    pub fn resize_to_cover(&self, width: u32, height: u32) -> FreqImage {
+        if (self.width, self.height) == (width, height) {
+            return self.clone();
+        }
+        let scale = (width as f64 / self.width as f64).max(height as f64 / self.height as f64);
+        // Rounding up guards against e.g. 12 × (2/3) landing just below 8.
+        let fit = |n: u32, min: u32| ((n as f64 * scale - 1e-9).ceil() as u32).max(min);
+        self.resize_spectral(fit(self.width, width), fit(self.height, height)).center_crop(width, height)
+    }
+}
+
+/// Bring two spatial-domain images to the same size: the one with more pixels is
+/// resized to cover the other with [`FreqImage::resize_to_cover`] and center-cropped to
+/// match, and the smaller one is returned as is. Images of equal size are unchanged.
+pub fn resize_to_smaller(a: &FreqImage, b: &FreqImage) -> (FreqImage, FreqImage) {
+    let pixels = |fi: &FreqImage| fi.width as u64 * fi.height as u64;
+    if pixels(a) <= pixels(b) {
+        (a.clone(), b.resize_to_cover(a.width, a.height))
+    } else {
+        (a.resize_to_cover(b.width, b.height), b.clone())
+    }
+}
+
+/// Where each of the `n` bins of an unshifted spectrum axis lands on an axis of
+/// `m` bins, with weights. Bins outside the new band map nowhere.
+fn axis_map(n: usize, m: usize) -> Vec<Vec<(usize, f64)>> {
+    (0..n)
+        .map(|k| {
+            let f = if k <= n / 2 { k as isize } else { k as isize - n as isize };
+            let band = 2 * f.unsigned_abs();
+            if m == n {
+                vec![(k, 1.0)]
+            } else if m > n && band == n {
+                // Growing: split the old Nyquist bin so real signals stay real.
+                vec![(k, 0.5), (m - k, 0.5)]
+            } else if band < m {
+                vec![(f.rem_euclid(m as isize) as usize, 1.0)]
+            } else if band == m {
+                // Shrinking onto an even axis: both signs fold into the new Nyquist bin.
+                vec![(m / 2, 1.0)]
+            } else {
+                Vec::new()
+            }
+        })
+        .collect()
+}
+
+#[test]
+fn test_upscale_sinusoid() {
+    use std::f64::consts::PI;
+
+    let (w, h) = (16u32, 8u32);
+    let wave = |x: f64, period: f64| 0.5 + 0.25 * (2.0 * PI * 3.0 * x / period).cos();
+    let fi = FreqImage {
+        width: w,
+        height: h,
+        data: (0..w * h).map(|i| Complex::new(wave((i % w) as f64, w as f64), 0.0)).collect(),
+    };
+
+    let up = fi.upscale(2);
+    assert_eq!((up.width, up.height), (32, 16));
+    for (x, _, c) in up.enumerate_pixels() {
+        assert!((c.re - wave(x as f64, 32.0)).abs() < 1e-6, "x = {}", x);
+        assert!(c.im.abs() < 1e-6);
+    }
+}
+
+#[test]
+fn test_upscale_preserves_mean() {
+    let fi = FreqImage::open("img/mandrill.jpg").unwrap();
+    let up = fi.upscale(2);
+    assert_eq!((up.width, up.height), (2 * fi.width, 2 * fi.height));
+    assert!((up.mean_value() - fi.mean_value()).norm() < 1e-6);
+}
+
+#[test]
+fn test_downscale_zone_plate() {
+    let n = 64u32;
+    let fi = super::synth::zone_plate(n, n);
+    let down = fi.downscale(2);
+    assert_eq!((down.width, down.height), (32, 32));
+    assert!((down.mean_value() - fi.mean_value()).norm() < 1e-6);
+
+    // Every non-Nyquist bin of the result is the matching in-band bin of the original.
+    let mut full = fi.clone();
+    full.fft_forward();
+    let mut small = down.clone();
+    small.fft_forward();
+    let gain = 0.25;
+    let signed = |k: u32, len: u32| if k <= len / 2 { k as i64 } else { k as i64 - len as i64 };
+    for (x, y, c) in small.enumerate_pixels() {
+        if x == 16 || y == 16 {
+            continue;
+        }
+        let (fx, fy) = (signed(x, 32), signed(y, 32));
+        let src = full[(fx.rem_euclid(64) as u32, fy.rem_euclid(64) as u32)];
+        assert!((c - src * gain).norm() < 1e-6);
+    }
+
+    // Naive subsampling folds the outer rings back in and carries far more AC energy.
+    let naive = FreqImage {
+        width: 32,
+        height: 32,
+        data: (0..32 * 32).map(|i| fi[(2 * (i % 32), 2 * (i / 32))]).collect(),
+    };
+    let ac = |img: &FreqImage| {
+        let mean = img.mean_value();
+        img.data.iter().map(|c| (c - mean).norm_sqr()).sum::<f64>()
+    };
+    assert!(ac(&down) < 0.5 * ac(&naive));
+}
+
+#[test]
+fn test_resize_spectral_identity() {
+    let fi = super::synth::zone_plate(8, 8);
+    let same = fi.resize_spectral(8, 8);
+    for (a, b) in fi.data.iter().zip(&same.data) {
+        assert!((a - b).norm() < 1e-9);
+    }
+}
+
+#[test]
+fn test_resize_to_smaller() {
+    let big = super::synth::zone_plate(16, 12);
+    let small = super::ramp(8, 8);
+    let (a, b) = resize_to_smaller(&big, &small);
+    assert_eq!((a.width, a.height), (8, 8));
+    assert_eq!(b, small);
+    // 16×12 scales by 2/3 to 11×8 and loses a column and a half from each side.
+    assert_eq!(a, big.resize_spectral(11, 8).center_crop(8, 8));
+
+    let (a, b) = resize_to_smaller(&small, &super::ramp(8, 6).upscale(2));
+    assert_eq!((a, (b.width, b.height)), (small.clone(), (8, 8)));
+    assert_eq!(resize_to_smaller(&small, &small), (small.clone(), small.clone()));
+
+    let flat = FreqImage { width: 10, height: 7, data: vec![Complex::new(0.25, 0.0); 70] };
+    for c in flat.resize_to_cover(4, 5).data {
+        assert!((c.re - 0.25).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,57 @@
+//! Spectral residual saliency (Hou & Zhang, CVPR 2007).
+
+use rustfft::num_complex::Complex;
+
+use super::{Boundary, FreqImage, Kernel2D};
+
+impl FreqImage {
+    /// A saliency map of this spatial-domain image in `[0, 1]`, highest where the image
+    /// departs from its statistically expected content.
+    ///
+    /// The log-magnitude spectrum minus its 3×3 local average (the "spectral residual") is
+    /// recombined with the original phase and transformed back; the squared result is
+    /// smoothed with a Gaussian of 2% of the shorter side (at least one pixel) and
+    /// stretched to `[0, 1]`.
+    pub fn saliency_map(&self) -> FreqImage {
+        let mut spectrum = self.clone();
+        spectrum.fft_forward();
+
+        let log_magnitude = FreqImage {
+            width: self.width,
+            height: self.height,
+            data: spectrum.data.iter().map(|c| Complex::new(c.norm().max(f64::MIN_POSITIVE).ln(), 0.0)).collect(),
+        };
+        let average = log_magnitude.convolve_with(&Kernel2D::box_blur(3), Boundary::Wrap);
+        for ((c, l), a) in spectrum.data.iter_mut().zip(&log_magnitude.data).zip(&average.data) {
+            *c = Complex::from_polar((l.re - a.re).exp(), c.arg());
+        }
+        spectrum.fft_inverse();
+        spectrum.data.iter_mut().for_each(|c| *c = Complex::new(c.norm_sqr(), 0.0));
+
+        let sigma = (0.02 * self.width.min(self.height) as f64).max(1.0);
+        let mut map = spectrum.convolve_with(&Kernel2D::gaussian(sigma), Boundary::Reflect);
+        let (lo, hi) = map
+            .data
+            .iter()
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), c| (lo.min(c.re), hi.max(c.re)));
+        let scale = if hi > lo { 1.0 / (hi - lo) } else { 0.0 };
+        map.data.iter_mut().for_each(|c| *c = Complex::new((c.re - lo) * scale, 0.0));
+        map
+    }
+}
+
+#[test]
+fn test_saliency_finds_odd_patch() {
+    let mut fi = super::synth::grating(128, 128, 16.0, 16.0);
+    fi.add_gaussian_noise(0.1, 5);
+    for (x, y, c) in fi.enumerate_pixels_mut() {
+        c.re *= 0.5;
+        if (88..96).contains(&x) && (30..38).contains(&y) {
+            c.re = 1.0;
+        }
+    }
+    let map = fi.saliency_map();
+    assert!(map.data.iter().all(|c| (0.0..=1.0).contains(&c.re)));
+    let (x, y, _) = map.enumerate_pixels().max_by(|a, b| a.2.re.total_cmp(&b.2.re)).unwrap();
+    assert!((88..96).contains(&x) && (30..38).contains(&y), "peak at ({}, {})", x, y);
+}
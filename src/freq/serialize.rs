@@ -0,0 +1,123 @@
+//! Serde support for [`FreqImage`]. The option enums such as
+//! [`Normalize`](super::Normalize) and [`WindowKind`](super::WindowKind) derive it directly.
+//!
+//! Complex values are written as `(re, im)` pairs. Deserialization goes through
+//! [`RawFreqImage`] so that payloads whose buffer length disagrees with their
+//! dimensions are rejected up front.
+//!
+//! JSON has no NaN or infinity: serde_json writes them as `null`, which then fails to
+//! deserialize as a number. Spectra that may hold non-finite values (see
+//! [`FreqImage::to_image_checked`]) should go through a binary format such as bincode,
+//! which keeps them bit for bit.
+
+use rustfft::num_complex::Complex;
+use serde::{Deserialize, Serialize};
+
+use super::FreqImage;
+use crate::FreqError;
+
+/// Unvalidated wire representation of a [`FreqImage`].
+#[derive(Serialize, Deserialize)]
+pub(super) struct RawFreqImage {
+    width: u32,
+    height: u32,
+    data: Vec<(f64, f64)>,
+}
+
+impl From<FreqImage> for RawFreqImage {
+    fn from(fi: FreqImage) -> Self {
+        RawFreqImage {
+            width: fi.width,
+            height: fi.height,
+            data: fi.data.iter().map(|c| (c.re, c.im)).collect(),
+        }
+    }
+}
+
+impl TryFrom<RawFreqImage> for FreqImage {
+    type Error = FreqError;
+
+    fn try_from(raw: RawFreqImage) -> Result<Self, FreqError> {
+        let expected = (raw.width as usize).saturating_mul(raw.height as usize);
+        if raw.data.len() != expected {
+            return Err(FreqError::LengthMismatch { expected, got: raw.data.len() });
+        }
+        Ok(FreqImage {
+            width: raw.width,
+            height: raw.height,
+            data: raw.data.into_iter().map(|(re, im)| Complex::new(re, im)).collect(),
+        })
+    }
+}
+
+#[cfg(test)]
+fn sample() -> FreqImage {
+    // A small 3x2 spectrum-like buffer with non-trivial imaginary parts.
+    FreqImage {
+        width: 3,
+        height: 2,
+        data: vec![
+            Complex::new(3.5, 0.0),
+            Complex::new(-0.25, 1.125),
+            Complex::new(-0.25, -1.125),
+            Complex::new(0.5, 0.0),
+            Complex::new(1e-9, -7.0),
+            Complex::new(f64::MIN_POSITIVE, 2.0),
+        ],
+    }
+}
+
+#[test]
+fn test_serde_json_round_trip() {
+    let fi = sample();
+    let json = serde_json::to_string(&fi).unwrap();
+    let back: FreqImage = serde_json::from_str(&json).unwrap();
+    assert_eq!(back, fi);
+}
+
+#[test]
+fn test_bincode_round_trip() {
+    let fi = sample();
+    let bytes = bincode::serialize(&fi).unwrap();
+    let back: FreqImage = bincode::deserialize(&bytes).unwrap();
+    assert_eq!(back, fi);
+}
+
+#[test]
+fn test_deserialize_rejects_bad_length() {
+    let json = r#"{"width":2,"height":2,"data":[[0.0,0.0],[1.0,0.0],[2.0,0.0]]}"#;
+    let err = serde_json::from_str::<FreqImage>(json).unwrap_err();
+    assert!(err.to_string().contains("expected 4 elements, got 3"));
+}
+
+#[test]
+fn test_non_finite_values() {
+    let mut fi = sample();
+    fi.data[1] = Complex::new(f64::NAN, f64::INFINITY);
+    fi.data[4] = Complex::new(f64::NEG_INFINITY, -0.0);
+    let bytes = bincode::serialize(&fi).unwrap();
+    let back: FreqImage = bincode::deserialize(&bytes).unwrap();
+    let bits = |fi: &FreqImage| fi.data.iter().flat_map(|c| [c.re.to_bits(), c.im.to_bits()]).collect::<Vec<_>>();
+    assert_eq!(bits(&back), bits(&fi));
+
+    let json = serde_json::to_string(&fi).unwrap();
+    assert!(json.contains("[null,null]"));
+    assert!(serde_json::from_str::<FreqImage>(&json).is_err());
+}
+
+#[test]
+fn test_option_enums_round_trip() {
+    use super::pipeline::MaskSpec;
+    use super::{Normalize, WindowKind};
+
+    let normalize = [Normalize::MinMax, Normalize::Percentile(1.0, 99.5), Normalize::Gamma(0.5)];
+    let json = serde_json::to_string(&normalize).unwrap();
+    assert_eq!(serde_json::from_str::<Vec<Normalize>>(&json).unwrap(), normalize);
+
+    let window = bincode::serialize(&WindowKind::Blackman).unwrap();
+    assert_eq!(bincode::deserialize::<WindowKind>(&window).unwrap(), WindowKind::Blackman);
+
+    let spec = MaskSpec::BandPass { low: 0.05, high: 0.2, smoothing: 0.01 };
+    let json = serde_json::to_string(&spec).unwrap();
+    assert_eq!(serde_json::from_str::<MaskSpec>(&json).unwrap(), spec);
+}
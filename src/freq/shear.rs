@@ -0,0 +1,159 @@
+//! Rotation by three shears (Paeth 1986, Unser et al. 1995).
+//!
+//! A rotation by `θ` counter-clockwise as displayed factors into a horizontal shear by
+//! `tan(θ/2)`, a vertical shear by `-sin θ` and the first horizontal shear again. Each
+//! shear moves every row (or column) by a different sub-pixel amount, which the Fourier
+//! shift theorem does exactly for band-limited content, so unlike
+//! [`FreqImage::rotate_scale`] nothing is blurred by interpolation.
+
+use std::f64::consts::{FRAC_PI_2, PI, TAU};
+
+use rustfft::num_complex::Complex;
+
+use super::convolve::{boundary_index, fast_len};
+use super::fft::transpose;
+use super::{Boundary, FftContext, FreqImage};
+
+/// What [`FreqImage::rotate`] does with content that the shears push past an edge.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum RotateEdge {
+    /// Shear on a mirror-padded canvas large enough that nothing wraps, then zero the
+    /// corners that rotate in from outside the image. Content rotated out of the image is
+    /// lost, and the zeroed corners are hard edges that ring when rotated again.
+    #[default]
+    Zero,
+    /// Shear in place. Content leaving one edge comes back in at the opposite one, so
+    /// the corners hold wrapped, sheared copies of the image. Cheaper, exact for images
+    /// that tile seamlessly, and the better choice for rotating back and forth.
+    Wrap,
+}
+
+impl FreqImage {
+    /// Rotate a spatial-domain image by `angle_rad` counter-clockwise as displayed, about
+    /// the image center `((width-1)/2, (height-1)/2)` like [`FreqImage::rotate_scale`],
+    /// keeping the size. See the module docs for the method and [`RotateEdge`] for what
+    /// ends up in the corners.
+    ///
+    /// The shears are only well behaved for `|angle_rad| <= π/2`; larger angles are
+    /// reduced by first turning the image with [`FreqImage::rotate180`].
+    pub fn rotate(&self, angle_rad: f64, edge: RotateEdge) -> FreqImage {
+        let mut angle = angle_rad.rem_euclid(TAU);
+        if angle > PI {
+            angle -= TAU;
+        }
+        if angle.abs() > FRAC_PI_2 {
+            return self.rotate180().rotate(angle - PI.copysign(angle), edge);
+        }
+        if self.data.is_empty() || angle == 0.0 {
+            return self.clone();
+        }
+
+        let (w, h) = (self.width, self.height);
+        let (mut canvas, left, top) = match edge {
+            RotateEdge::Wrap => (self.clone(), 0, 0),
+            // Large enough for the widest intermediate: `w + h` wide after the first shear
+            // and `2h + w` tall after the second.
+            RotateEdge::Zero => {
+                let (cw, ch) = (fast_len(w + h), fast_len(2 * h + w));
+                let (left, top) = ((cw - w) / 2, (ch - h) / 2);
+                let mut canvas = FreqImage {
+                    width: cw,
+                    height: ch,
+                    data: vec![Complex::new(0.0, 0.0); cw as usize * ch as usize],
+                };
+                // Mirrored margins continue the image smoothly, so the sub-pixel shifts
+                // don't ring at its borders the way they would against zeros.
+                for (x, y, c) in canvas.enumerate_pixels_mut() {
+                    let sx = boundary_index(x as i64 - left as i64, w, Boundary::Reflect).unwrap();
+                    let sy = boundary_index(y as i64 - top as i64, h, Boundary::Reflect).unwrap();
+                    *c = self[(sx, sy)];
+                }
+                (canvas, left, top)
+            }
+        };
+        let cx = left as f64 + (w as f64 - 1.0) / 2.0;
+        let cy = top as f64 + (h as f64 - 1.0) / 2.0;
+        let (a, b) = ((angle / 2.0).tan(), -angle.sin());
+
+        let mut ctx = FftContext::new();
+        ctx.shift_rows(&mut canvas, |y| a * (y as f64 - cy));
+        let mut columns = FreqImage {
+            width: canvas.height,
+            height: canvas.width,
+            data: transpose(canvas.width as usize, canvas.height as usize, &canvas.data),
+        };
+        ctx.shift_rows(&mut columns, |x| b * (x as f64 - cx));
+        canvas.data = transpose(columns.width as usize, columns.height as usize, &columns.data);
+        ctx.shift_rows(&mut canvas, |y| a * (y as f64 - cy));
+        let mut out = canvas.crop(left, top, w, h);
+        if edge == RotateEdge::Zero {
+            let (sin, cos) = angle.sin_cos();
+            let (cx, cy) = ((w as f64 - 1.0) / 2.0, (h as f64 - 1.0) / 2.0);
+            for (x, y, c) in out.enumerate_pixels_mut() {
+                let (u, v) = (x as f64 - cx, y as f64 - cy);
+                let (sx, sy) = (u * cos - v * sin + cx, u * sin + v * cos + cy);
+                if !(-0.5..w as f64 - 0.5).contains(&sx) || !(-0.5..h as f64 - 0.5).contains(&sy) {
+                    *c = Complex::new(0.0, 0.0);
+                }
+            }
+        }
+        out
+    }
+}
+
+#[test]
+fn test_rotate_quarter_turn_is_exact() {
+    // Odd sizes put the center on a pixel, so a quarter turn only shifts by whole pixels.
+    let fi = super::ramp(7, 7);
+    for edge in [RotateEdge::Zero, RotateEdge::Wrap] {
+        assert!(fi.rotate(FRAC_PI_2, edge).approx_eq(&fi.rotate270(), 1e-9), "{:?}", edge);
+        assert!(fi.rotate(-FRAC_PI_2, edge).approx_eq(&fi.rotate90(), 1e-9), "{:?}", edge);
+        assert!(fi.rotate(PI, edge).approx_eq(&fi.rotate180(), 1e-9), "{:?}", edge);
+        assert!(fi.rotate(TAU, edge).approx_eq(&fi, 1e-9), "{:?}", edge);
+    }
+}
+
+#[test]
+fn test_rotate_round_trip() {
+    use super::Kernel2D;
+
+    // A blurred texture, with little energy near Nyquist for the shears to alias.
+    let fi = super::synth::spectral_noise(128, 96, 2.0, 6).convolve_with(&Kernel2D::gaussian(1.0), Boundary::Wrap);
+    let back = fi.rotate(10f64.to_radians(), RotateEdge::Wrap).rotate(-10f64.to_radians(), RotateEdge::Wrap);
+    let (before, after) = (fi.to_image(), back.to_image());
+    // The central 80% of each side.
+    for (x, y, p) in after.enumerate_pixels().filter(|&(x, y, _)| (13..115).contains(&x) && (10..86).contains(&y)) {
+        assert!(p[0].abs_diff(before.get_pixel(x, y)[0]) <= 1, "({}, {})", x, y);
+    }
+}
+
+#[test]
+fn test_rotate_zeroes_corners() {
+    let flat = FreqImage { width: 40, height: 30, data: vec![Complex::new(1.0, 0.0); 1200] };
+    let rotated = flat.rotate(0.3, RotateEdge::Zero);
+    for corner in [(0, 0), (39, 0), (0, 29), (39, 29)] {
+        assert_eq!(rotated[corner], Complex::new(0.0, 0.0));
+    }
+    assert!((rotated[(20, 15)] - Complex::new(1.0, 0.0)).norm() < 1e-9);
+    assert!(flat.rotate(0.3, RotateEdge::Wrap).approx_eq(&flat, 1e-9));
+}
+
+#[test]
+fn test_rotate_turns_spectrum() {
+    use super::WindowKind;
+
+    // Eight cycles across, varying along +x only.
+    let grating = super::synth::grating(128, 128, 8.0, 0.0);
+    for degrees in [20.0, -35.0, 70.0] {
+        let mut spectrum = grating.rotate(f64::to_radians(degrees), RotateEdge::Zero);
+        let mean = spectrum.mean_value();
+        spectrum.data.iter_mut().for_each(|c| *c -= mean);
+        spectrum.apply_window(WindowKind::Hann);
+        spectrum.fft_forward();
+        let peak = spectrum.find_spectral_peaks(1, 2.0, 2.0)[0];
+        let found = (-peak.v).atan2(peak.u).to_degrees();
+        let error = (found - degrees).rem_euclid(180.0);
+        assert!(error.min(180.0 - error) < 2.0, "rotated by {}, found {}", degrees, found);
+    }
+}
@@ -0,0 +1,102 @@
+//! Moving the zero-frequency bin between the corner and the center of the buffer,
+//! and sub-pixel translation by the Fourier shift theorem.
+
+use std::f64::consts::PI;
+
+use rustfft::num_complex::Complex;
+
+use super::FreqImage;
+
+impl FreqImage {
+    /// Swap quadrants so the DC bin moves from `(0, 0)` to `(width / 2, height / 2)`,
+    /// matching numpy's `fftshift`.
+    pub fn fftshift(&mut self) {
+        self.quadrant_shift(self.width / 2, self.height / 2);
+    }
+
+    /// Undo [`FreqImage::fftshift`], moving the DC bin back to `(0, 0)`. Differs from
+    /// `fftshift` only when a dimension is odd.
+    pub fn ifftshift(&mut self) {
+        self.quadrant_shift(self.width.div_ceil(2), self.height.div_ceil(2));
+    }
+
+    /// Move a spatial-domain image `dx` pixels right and `dy` pixels down, wrapping
+    /// around the edges. Fractional shifts are band-limited (sinc) interpolation;
+    /// Nyquist bins are kept real so real images stay real.
+    pub fn translate(&self, dx: f64, dy: f64) -> FreqImage {
+        let mut out = self.clone();
+        out.fft_forward();
+        let (w, h) = (self.width, self.height);
+        for (x, y, c) in out.enumerate_pixels_mut() {
+            *c *= shift_factor(x, w, dx) * shift_factor(y, h, dy);
+        }
+        out.fft_inverse();
+        out
+    }
+
+    /// Circularly move every coefficient `dx` columns right and `dy` rows down.
+    fn quadrant_shift(&mut self, dx: u32, dy: u32) {
+        let (w, h) = (self.width, self.height);
+        if self.data.is_empty() || (dx.is_multiple_of(w) && dy.is_multiple_of(h)) {
+            return;
+        }
+        let mut out = self.data.clone();
+        for (x, y, &c) in self.enumerate_pixels() {
+            out[(((y + dy) % h) * w + (x + dx) % w) as usize] = c;
+        }
+        self.data = out;
+    }
+}
+
+/// The phase ramp `exp(-2πi·f·d/n)` for bin `k` of an axis of length `n`.
+pub(super) fn shift_factor(k: u32, n: u32, d: f64) -> Complex<f64> {
+    if 2 * k == n {
+        return Complex::new((PI * d).cos(), 0.0);
+    }
+    let f = if 2 * k < n { k as f64 } else { k as f64 - n as f64 };
+    Complex::from_polar(1.0, -2.0 * PI * f * d / n as f64)
+}
+
+#[test]
+fn test_fftshift() {
+    let mut fi = super::ramp(4, 3);
+    fi.fftshift();
+    assert_eq!(fi[(2, 1)], super::ramp(4, 3)[(0, 0)]);
+    fi.ifftshift();
+    assert_eq!(fi, super::ramp(4, 3));
+
+    let mut odd = super::ramp(5, 3);
+    odd.ifftshift();
+    assert_eq!(odd[(0, 0)], super::ramp(5, 3)[(2, 1)]);
+    odd.fftshift();
+    assert_eq!(odd, super::ramp(5, 3));
+}
+
+#[test]
+fn test_degenerate_shifts() {
+    let mut single = super::ramp(1, 1);
+    single.fftshift();
+    assert_eq!(single, super::ramp(1, 1));
+
+    for (w, h) in [(1, 5), (5, 1), (1, 4), (4, 1)] {
+        let mut fi = super::ramp(w, h);
+        fi.fftshift();
+        assert_eq!(fi[(w / 2, h / 2)], super::ramp(w, h)[(0, 0)]);
+        fi.ifftshift();
+        assert_eq!(fi, super::ramp(w, h));
+    }
+}
+
+#[test]
+fn test_translate() {
+    let fi = super::ramp(5, 4);
+    let moved = fi.translate(2.0, -1.0);
+    for (x, y, &c) in fi.enumerate_pixels() {
+        assert!((moved[((x + 2) % 5, (y + 3) % 4)] - c).norm() < 1e-9);
+    }
+
+    let half = fi.translate(0.5, 0.0).translate(-0.5, 0.0);
+    for (a, b) in half.data.iter().zip(&fi.data) {
+        assert!((a - b).norm() < 1e-9);
+    }
+}
@@ -0,0 +1,195 @@
+//! Summary statistics for inspecting a buffer mid-pipeline.
+
+use std::fmt;
+
+use rustfft::num_complex::Complex;
+
+use super::FreqImage;
+
+/// A snapshot of a FreqImage's contents, see [`FreqImage::stats`].
+///
+/// Coefficients with a NaN or infinite component are counted in `non_finite`
+/// and left out of every other field.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FreqStats {
+    /// Smallest real part.
+    pub real_min: f64,
+    /// Largest real part.
+    pub real_max: f64,
+    /// Mean of the real parts.
+    pub real_mean: f64,
+    /// Largest absolute imaginary part.
+    pub max_abs_imag: f64,
+    /// Total energy `Σ|c|²`.
+    pub energy: f64,
+    /// Number of coefficients with a NaN or infinite component.
+    pub non_finite: usize,
+    /// `(x, y)` of the largest-magnitude coefficient.
+    pub peak: (u32, u32),
+    /// The largest-magnitude coefficient itself.
+    pub peak_value: Complex<f64>,
+}
+
+impl FreqImage {
+    /// Collect min/max/mean of the real parts, imaginary extent, energy, the
+    /// non-finite count and the largest-magnitude coefficient in one pass.
+    /// With no finite coefficients the real min/max/mean are NaN.
+    pub fn stats(&self) -> FreqStats {
+        let mut stats = FreqStats {
+            real_min: f64::INFINITY,
+            real_max: f64::NEG_INFINITY,
+            real_mean: 0.0,
+            max_abs_imag: 0.0,
+            energy: 0.0,
+            non_finite: 0,
+            peak: (0, 0),
+            peak_value: Complex::new(0.0, 0.0),
+        };
+        let mut finite = 0usize;
+        let mut sum = 0.0;
+        let mut peak_norm = -1.0;
+        for (x, y, c) in self.enumerate_pixels() {
+            if !c.re.is_finite() || !c.im.is_finite() {
+                stats.non_finite += 1;
+                continue;
+            }
+            finite += 1;
+            sum += c.re;
+            stats.real_min = stats.real_min.min(c.re);
+            stats.real_max = stats.real_max.max(c.re);
+            stats.max_abs_imag = stats.max_abs_imag.max(c.im.abs());
+            let norm = c.norm_sqr();
+            stats.energy += norm;
+            if norm > peak_norm {
+                peak_norm = norm;
+                stats.peak = (x, y);
+                stats.peak_value = *c;
+            }
+        }
+        if finite == 0 {
+            stats.real_min = f64::NAN;
+            stats.real_max = f64::NAN;
+            stats.real_mean = f64::NAN;
+        } else {
+            stats.real_mean = sum / finite as f64;
+        }
+        stats
+    }
+}
+
+impl FreqImage {
+    /// Total energy `Σ|c|²`.
+    ///
+    /// The forward FFT is unnormalized, so Parseval's relation reads
+    /// `spatial.energy() == spectrum.energy() / (width * height)`.
+    pub fn energy(&self) -> f64 {
+        self.data.iter().map(|c| c.norm_sqr()).sum()
+    }
+
+    /// The mean of all coefficients, zero for an empty image.
+    pub fn mean_value(&self) -> Complex<f64> {
+        if self.data.is_empty() {
+            return Complex::new(0.0, 0.0);
+        }
+        self.data.iter().sum::<Complex<f64>>() / self.data.len() as f64
+    }
+
+    /// The DC coefficient of a spectrum: bin `(0, 0)` in the FFT's natural layout,
+    /// or `(width / 2, height / 2)` (rounded down) once quadrant-shifted.
+    ///
+    /// # Panics
+    /// Panics if the image is empty.
+    pub fn dc(&self, shifted: bool) -> Complex<f64> {
+        if shifted {
+            self[(self.width / 2, self.height / 2)]
+        } else {
+            self[(0, 0)]
+        }
+    }
+}
+
+impl fmt::Display for FreqStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "re [{:.6e}, {:.6e}] mean {:.6e}, |im| <= {:.6e}, energy {:.6e}, non-finite {}, peak {} at ({}, {})",
+            self.real_min,
+            self.real_max,
+            self.real_mean,
+            self.max_abs_imag,
+            self.energy,
+            self.non_finite,
+            self.peak_value,
+            self.peak.0,
+            self.peak.1,
+        )
+    }
+}
+
+#[test]
+fn test_stats() {
+    let fi = FreqImage {
+        width: 3,
+        height: 2,
+        data: vec![
+            Complex::new(1.0, 0.0),
+            Complex::new(-2.0, 0.5),
+            Complex::new(f64::NAN, 0.0),
+            Complex::new(3.0, -4.0),
+            Complex::new(0.0, f64::INFINITY),
+            Complex::new(4.0, 0.0),
+        ],
+    };
+    let stats = fi.stats();
+    assert_eq!(stats.real_min, -2.0);
+    assert_eq!(stats.real_max, 4.0);
+    assert_eq!(stats.real_mean, 1.5);
+    assert_eq!(stats.max_abs_imag, 4.0);
+    assert_eq!(stats.energy, 1.0 + 4.25 + 25.0 + 16.0);
+    assert_eq!(stats.non_finite, 2);
+    assert_eq!(stats.peak, (0, 1));
+    assert_eq!(stats.peak_value, Complex::new(3.0, -4.0));
+
+    let line = stats.to_string();
+    assert!(line.contains("non-finite 2"));
+    assert!(line.contains("at (0, 1)"));
+}
+
+#[test]
+fn test_stats_all_non_finite() {
+    let fi = FreqImage {
+        width: 1,
+        height: 1,
+        data: vec![Complex::new(f64::NAN, 0.0)],
+    };
+    let stats = fi.stats();
+    assert_eq!(stats.non_finite, 1);
+    assert!(stats.real_mean.is_nan());
+    assert_eq!(stats.energy, 0.0);
+}
+
+#[test]
+fn test_dc_and_mean() {
+    let fi = FreqImage {
+        width: 3,
+        height: 3,
+        data: (0..9).map(|i| Complex::new(i as f64, 0.0)).collect(),
+    };
+    assert_eq!(fi.dc(false), Complex::new(0.0, 0.0));
+    assert_eq!(fi.dc(true), Complex::new(4.0, 0.0));
+    assert_eq!(fi.mean_value(), Complex::new(4.0, 0.0));
+
+    let mut spectrum = fi.clone();
+    spectrum.fft_forward();
+    assert!((spectrum.dc(false) - fi.mean_value() * 9.0).norm() < 1e-9);
+}
+
+#[test]
+fn test_parseval() {
+    let mut fi = FreqImage::open("img/mandrill.jpg").unwrap();
+    let spatial = fi.energy();
+    fi.fft_forward();
+    let n = (fi.width * fi.height) as f64;
+    let spectral = fi.energy() / n;
+    assert!((spatial - spectral).abs() / spatial < 1e-6);
+}
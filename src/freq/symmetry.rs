@@ -0,0 +1,97 @@
+//! Conjugate (Hermitian) symmetry, which a spectrum needs to transform back to a real image.
+
+use rustfft::num_complex::Complex;
+
+use super::FreqImage;
+use crate::FreqError;
+
+/// The spectra of two real images of equal size from a single complex FFT.
+///
+/// `a` goes in the real part and `b` in the imaginary part; since each real image has a
+/// Hermitian spectrum, `A[k] = (Z[k] + conj(Z[-k])) / 2` and `B[k] = (Z[k] - conj(Z[-k])) / 2i`
+/// separate them again. Imaginary parts of the inputs are ignored. Results are in the
+/// natural layout, as from [`FreqImage::fft_forward`].
+pub fn fft_pair(a: &FreqImage, b: &FreqImage) -> Result<(FreqImage, FreqImage), FreqError> {
+    if (a.width, a.height) != (b.width, b.height) {
+        return Err(FreqError::DimensionMismatch {
+            expected: (a.width, a.height),
+            got: (b.width, b.height),
+        });
+    }
+    let mut packed = FreqImage {
+        width: a.width,
+        height: a.height,
+        data: a.data.iter().zip(&b.data).map(|(p, q)| Complex::new(p.re, q.re)).collect(),
+    };
+    packed.fft_forward();
+
+    let (mut first, mut second) = (packed.clone(), packed.clone());
+    for (x, y, z) in packed.enumerate_pixels() {
+        let mirrored = packed[packed.mirror(x, y)].conj();
+        first[(x, y)] = (z + mirrored) * 0.5;
+        second[(x, y)] = (z - mirrored) * Complex::new(0.0, -0.5);
+    }
+    Ok((first, second))
+}
+
+impl FreqImage {
+    /// Whether this natural-layout spectrum satisfies `X[u, v] == conj(X[-u, -v])` to within
+    /// an absolute `tol` everywhere, indices taken modulo the size. DC and the Nyquist rows
+    /// and columns of even sizes are their own mirrors, so they must be real.
+    pub fn is_hermitian(&self, tol: f64) -> bool {
+        self.enumerate_pixels().all(|(x, y, c)| (c - self[self.mirror(x, y)].conj()).norm() <= tol)
+    }
+
+    /// Make the spectrum Hermitian by replacing each bin with the average of itself and the
+    /// conjugate of its mirror; self-mirrored bins keep only their real part. This is the
+    /// spectrum of the real part of the original image.
+    pub fn enforce_hermitian(&mut self) {
+        let symmetric = self
+            .enumerate_pixels()
+            .map(|(x, y, c)| (c + self[self.mirror(x, y)].conj()) * 0.5)
+            .collect();
+        self.data = symmetric;
+    }
+
+    fn mirror(&self, x: u32, y: u32) -> (u32, u32) {
+        ((self.width - x) % self.width, (self.height - y) % self.height)
+    }
+}
+
+#[test]
+fn test_hermitian() {
+    for (w, h) in [(8, 6), (7, 5)] {
+        let mut fi = super::synth::spectral_noise(w, h, 1.0, 4);
+        fi.fft_forward();
+        assert!(fi.is_hermitian(1e-9));
+
+        fi[(2, 1)].im += 0.5;
+        assert!(!fi.is_hermitian(1e-9));
+        fi.enforce_hermitian();
+        assert!(fi.is_hermitian(1e-12));
+        fi.fft_inverse();
+        assert!(fi.data.iter().all(|c| c.im.abs() < 1e-9));
+    }
+
+    // A self-conjugate bin must be real.
+    let mut dc = super::ramp(4, 4);
+    dc.data.iter_mut().for_each(|c| *c = 0.0.into());
+    dc.data[0].im = 1.0;
+    assert!(!dc.is_hermitian(1e-9));
+}
+
+#[test]
+fn test_fft_pair() {
+    let a = FreqImage::open("img/mandrill.jpg").unwrap();
+    let b = FreqImage::open("img/sjb-aerial.png").unwrap();
+    let (w, h) = (a.width.min(b.width), a.height.min(b.height));
+    let (a, b) = (a.crop(0, 0, w, h), b.crop(0, 0, w, h));
+
+    let (fa, fb) = fft_pair(&a, &b).unwrap();
+    for (pair, image) in [(fa, a), (fb, b)] {
+        let mut expected = image.clone();
+        expected.fft_forward();
+        assert!(pair.data.iter().zip(&expected.data).all(|(p, e)| (p - e).norm() < 1e-9));
+    }
+    assert!(matches!(fft_pair(&super::ramp(2, 3), &super::ramp(3, 2)), Err(FreqError::DimensionMismatch { .. })));
+}
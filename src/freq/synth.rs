@@ -0,0 +1,226 @@
+//! Synthetic images generated directly in the frequency domain.
+
+use std::f64::consts::{PI, TAU};
+
+use rustfft::num_complex::Complex;
+
+use super::rng::SplitMix64;
+use super::FreqImage;
+
+/// Fractal `1/f^β` noise: a spectrum with power falling as `f^-β` (magnitude `f^(-β/2)`)
+/// and random phases from `seed`, mirrored so the image is real, then transformed back
+/// and rescaled to `[0, 1]`. `β = 0` is white noise, 2 looks like clouds or terrain.
+/// The same seed always gives the same image.
+pub fn spectral_noise(width: u32, height: u32, beta: f64, seed: u64) -> FreqImage {
+    let mut fi = FreqImage {
+        width,
+        height,
+        data: vec![Complex::new(0.0, 0.0); width as usize * height as usize],
+    };
+    let phases = fi.hermitian_phases(seed);
+    for ((x, y, c), theta) in fi.enumerate_pixels_mut().zip(phases) {
+        let fx = if 2 * x < width { x as f64 } else { x as f64 - width as f64 } / width as f64;
+        let fy = if 2 * y < height { y as f64 } else { y as f64 - height as f64 } / height as f64;
+        let f = fx.hypot(fy);
+        if f > 0.0 {
+            *c = Complex::from_polar(f.powf(-beta / 2.0), theta);
+        }
+    }
+    fi.fft_inverse();
+
+    let (lo, hi) = fi
+        .data
+        .iter()
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), c| (lo.min(c.re), hi.max(c.re)));
+    let scale = if hi > lo { 1.0 / (hi - lo) } else { 0.0 };
+    for c in fi.data.iter_mut() {
+        *c = Complex::new((c.re - lo) * scale, 0.0);
+    }
+    fi
+}
+
+/// A circular zone plate: a chirp whose local frequency grows linearly from zero at the
+/// center `(width/2, height/2)` to Nyquist at the middle of each edge. Values in `[0, 1]`.
+pub fn zone_plate(width: u32, height: u32) -> FreqImage {
+    let (cx, cy) = ((width / 2) as f64, (height / 2) as f64);
+    from_fn(width, height, |x, y| {
+        let (dx, dy) = (x - cx, y - cy);
+        0.5 + 0.5 * (PI * (dx * dx / width as f64 + dy * dy / height as f64)).cos()
+    })
+}
+
+/// A sinusoidal grating with `cycles_x` periods across the width and `cycles_y` down the
+/// height. Whole cycle counts put all energy in the DC bin and the two bins at
+/// `±(cycles_x, cycles_y)`. Values in `[0, 1]`.
+pub fn grating(width: u32, height: u32, cycles_x: f64, cycles_y: f64) -> FreqImage {
+    from_fn(width, height, |x, y| {
+        0.5 + 0.5 * (TAU * (cycles_x * x / width as f64 + cycles_y * y / height as f64)).cos()
+    })
+}
+
+/// Alternating `cell`×`cell` squares of 0 and 1, starting with 0 at the origin.
+///
+/// # Panics
+/// Panics if `cell` is zero.
+pub fn checkerboard(width: u32, height: u32, cell: u32) -> FreqImage {
+    assert!(cell > 0, "checkerboard cell size must be positive");
+    from_fn(width, height, |x, y| ((x as u32 / cell + y as u32 / cell) % 2) as f64)
+}
+
+/// A Gaussian of standard deviation `sigma` pixels peaking at 1 in `(width/2, height/2)`.
+pub fn gaussian_spot(width: u32, height: u32, sigma: f64) -> FreqImage {
+    let (cx, cy) = ((width / 2) as f64, (height / 2) as f64);
+    from_fn(width, height, |x, y| (-((x - cx).powi(2) + (y - cy).powi(2)) / (2.0 * sigma * sigma)).exp())
+}
+
+fn from_fn(width: u32, height: u32, f: impl Fn(f64, f64) -> f64) -> FreqImage {
+    FreqImage {
+        width,
+        height,
+        data: (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .map(|(x, y)| Complex::new(f(x as f64, y as f64), 0.0))
+            .collect(),
+    }
+}
+
+/// Noise added to a spatial-domain image, e.g. to exercise denoising code. Only the
+/// real parts are touched, and every generator is deterministic in its arguments.
+impl FreqImage {
+    /// Add white Gaussian noise with standard deviation `sigma`.
+    pub fn add_gaussian_noise(&mut self, sigma: f64, seed: u64) {
+        let mut rng = SplitMix64(seed);
+        for c in self.data.iter_mut() {
+            c.re += sigma * rng.next_gaussian();
+        }
+    }
+
+    /// Add Gaussian noise confined to the annulus of
+    /// [`FreqImage::band_pass_mask`]`(low, high, 0.0)`, rescaled so its spatial standard
+    /// deviation is `sigma`. The band is trimmed to its conjugate-symmetric part so the
+    /// noise is exactly real; an empty band adds nothing.
+    pub fn add_band_noise(&mut self, low: f64, high: f64, sigma: f64, seed: u64) {
+        let (w, h) = (self.width, self.height);
+        let mut band = FreqImage {
+            width: w,
+            height: h,
+            data: self.band_pass_mask(low, high, 0.0).into_iter().map(Complex::from).collect(),
+        };
+        band.ifftshift();
+
+        let mut noise = FreqImage { width: w, height: h, data: vec![Complex::new(0.0, 0.0); self.data.len()] };
+        noise.add_gaussian_noise(1.0, seed);
+        noise.fft_forward();
+        for (x, y, c) in noise.enumerate_pixels_mut() {
+            *c *= band[(x, y)].re * band[((w - x) % w, (h - y) % h)].re;
+        }
+        noise.fft_inverse();
+
+        let n = noise.data.len() as f64;
+        let mean = noise.data.iter().map(|c| c.re).sum::<f64>() / n;
+        let std = (noise.data.iter().map(|c| (c.re - mean).powi(2)).sum::<f64>() / n).sqrt();
+        if std > 0.0 {
+            for (c, z) in self.data.iter_mut().zip(&noise.data) {
+                c.re += z.re * sigma / std;
+            }
+        }
+    }
+
+    /// Add `amplitude · cos(2π · freq_cpp · d)`, a plane wave of `freq_cpp` cycles per pixel
+    /// whose direction of variation `d` is `angle` degrees counter-clockwise from +x as
+    /// displayed (y down).
+    pub fn add_sinusoidal_noise(&mut self, freq_cpp: f64, angle: f64, amplitude: f64) {
+        let (sin, cos) = angle.to_radians().sin_cos();
+        for (x, y, c) in self.enumerate_pixels_mut() {
+            c.re += amplitude * (TAU * freq_cpp * (x as f64 * cos - y as f64 * sin)).cos();
+        }
+    }
+}
+
+#[cfg(test)]
+fn spatial_std(fi: &FreqImage) -> f64 {
+    let n = fi.data.len() as f64;
+    let mean = fi.data.iter().map(|c| c.re).sum::<f64>() / n;
+    (fi.data.iter().map(|c| (c.re - mean).powi(2)).sum::<f64>() / n).sqrt()
+}
+
+#[test]
+fn test_spectral_noise_deterministic() {
+    let a = spectral_noise(32, 24, 2.0, 7);
+    assert_eq!(a, spectral_noise(32, 24, 2.0, 7));
+    assert_ne!(a, spectral_noise(32, 24, 2.0, 8));
+    assert!(a.data.iter().all(|c| (0.0..=1.0).contains(&c.re) && c.im == 0.0));
+}
+
+#[test]
+fn test_spectral_noise_slope() {
+    for beta in [1.0, 2.0, 3.0] {
+        let mut fi = spectral_noise(128, 128, beta, 1);
+        fi.fft_forward();
+        let slope = fi.spectral_slope();
+        assert!((slope + beta).abs() < 0.3, "beta {} slope {}", beta, slope);
+    }
+}
+
+#[test]
+fn test_add_band_noise() {
+    let blank = FreqImage { width: 64, height: 48, data: vec![Complex::new(0.0, 0.0); 64 * 48] };
+    let mut noisy = blank.clone();
+    noisy.add_band_noise(0.05, 0.2, 0.5, 3);
+    assert!((spatial_std(&noisy) - 0.5).abs() < 0.025);
+    assert!(noisy.data.iter().all(|c| c.im == 0.0));
+
+    let mut again = blank.clone();
+    again.add_band_noise(0.05, 0.2, 0.5, 3);
+    assert_eq!(noisy, again);
+
+    noisy.fft_forward();
+    noisy.fftshift();
+    let band = noisy.band_pass_mask(0.05, 0.2, 0.0);
+    let total: f64 = noisy.data.iter().map(|c| c.norm_sqr()).sum();
+    let outside: f64 = noisy.data.iter().zip(&band).filter(|(_, &m)| m == 0.0).map(|(c, _)| c.norm_sqr()).sum();
+    assert!(outside < 1e-12 * total, "{} of {}", outside, total);
+}
+
+#[test]
+fn test_add_gaussian_and_sinusoidal_noise() {
+    let blank = FreqImage { width: 64, height: 64, data: vec![Complex::new(0.0, 0.0); 64 * 64] };
+    let mut noisy = blank.clone();
+    noisy.add_gaussian_noise(2.0, 9);
+    assert!((spatial_std(&noisy) - 2.0).abs() < 0.1);
+
+    // 8 cycles across the width at 90° varies along -y only: all energy in (0, ±8).
+    let mut wave = blank.clone();
+    wave.add_sinusoidal_noise(0.125, 90.0, 1.0);
+    assert!((spatial_std(&wave) - 0.5f64.sqrt()).abs() < 1e-9);
+    wave.fft_forward();
+    let total: f64 = wave.data.iter().map(|c| c.norm_sqr()).sum();
+    assert!((wave[(0, 8)].norm_sqr() + wave[(0, 56)].norm_sqr()) > 0.999999 * total);
+}
+
+#[test]
+fn test_grating_energy() {
+    let mut fi = grating(64, 64, 8.0, 0.0);
+    fi.fft_forward();
+    let off_dc: f64 = fi.data.iter().skip(1).map(|c| c.norm_sqr()).sum();
+    let peaks = fi[(8, 0)].norm_sqr() + fi[(56, 0)].norm_sqr();
+    assert!(peaks > 0.99 * off_dc);
+
+    let mut oblique = grating(64, 48, 3.0, -5.0);
+    oblique.fft_forward();
+    let off_dc: f64 = oblique.data.iter().skip(1).map(|c| c.norm_sqr()).sum();
+    assert!(oblique[(3, 43)].norm_sqr() + oblique[(61, 5)].norm_sqr() > 0.99 * off_dc);
+}
+
+#[test]
+fn test_patterns_in_range() {
+    for fi in [zone_plate(33, 20), grating(16, 16, 1.5, 2.0), checkerboard(10, 7, 3), gaussian_spot(15, 12, 2.0)] {
+        assert!(fi.data.iter().all(|c| (0.0..=1.0).contains(&c.re) && c.im == 0.0));
+    }
+    let board = checkerboard(6, 6, 3);
+    assert_eq!((board[(0, 0)].re, board[(3, 0)].re, board[(3, 3)].re, board[(2, 4)].re), (0.0, 1.0, 0.0, 1.0));
+    let spot = gaussian_spot(15, 12, 2.0);
+    assert_eq!(spot[(7, 6)].re, 1.0);
+    assert!((spot[(9, 6)].re - (-0.5f64).exp()).abs() < 1e-12);
+    assert_eq!(zone_plate(32, 32)[(16, 16)].re, 1.0);
+}
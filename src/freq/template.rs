@@ -0,0 +1,136 @@
+//! Template matching by FFT-accelerated normalized cross-correlation.
+
+use rustfft::num_complex::Complex;
+
+use super::{FftContext, FreqImage};
+
+/// The result of [`match_template`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct MatchResult {
+    /// Scores for every placement of the template's top-left corner, sized
+    /// `(width - tw + 1) × (height - th + 1)`, with values in the real parts.
+    pub surface: FreqImage,
+    /// Column of the best match.
+    pub x: u32,
+    /// Row of the best match.
+    pub y: u32,
+    /// Score of the best match, in `[-1, 1]`.
+    pub score: f64,
+}
+
+/// Find where `template` best matches `image`, both spatial-domain real images, by
+/// normalized cross-correlation (the Pearson correlation of the template with each
+/// window), which is invariant to brightness and contrast changes.
+///
+/// The numerator comes from one FFT correlation and the per-window means and variances
+/// from summed-area tables. Flat windows, or a flat template, score zero.
+///
+/// # Panics
+/// Panics if the template is empty or larger than the image in either dimension.
+pub fn match_template(image: &FreqImage, template: &FreqImage) -> MatchResult {
+    let (w, h) = (image.width, image.height);
+    let (tw, th) = (template.width, template.height);
+    assert!(
+        tw > 0 && th > 0 && tw <= w && th <= h,
+        "template {}x{} does not fit in {}x{} image",
+        tw, th, w, h
+    );
+    let n = (tw * th) as f64;
+    let t_mean = template.data.iter().map(|c| c.re).sum::<f64>() / n;
+    let t_norm = template.data.iter().map(|c| (c.re - t_mean).powi(2)).sum::<f64>().sqrt();
+
+    // Σ I·(T - mean T) for every placement; valid placements never wrap.
+    let mut ctx = FftContext::new();
+    let mut numerator = FreqImage {
+        width: w,
+        height: h,
+        data: image.data.iter().map(|c| Complex::new(c.re, 0.0)).collect(),
+    };
+    let mut kernel = FreqImage {
+        width: w,
+        height: h,
+        data: vec![Complex::new(0.0, 0.0); w as usize * h as usize],
+    };
+    for (x, y, c) in template.enumerate_pixels() {
+        kernel[(x, y)] = Complex::new(c.re - t_mean, 0.0);
+    }
+    ctx.forward(&mut numerator);
+    ctx.forward(&mut kernel);
+    numerator.mul_conj_elementwise(&kernel).unwrap();
+    ctx.inverse(&mut numerator);
+
+    let sums = SummedArea::new(image, |c| c.re);
+    let squares = SummedArea::new(image, |c| c.re * c.re);
+    let (sw, sh) = (w - tw + 1, h - th + 1);
+    let mut surface = FreqImage {
+        width: sw,
+        height: sh,
+        data: Vec::with_capacity(sw as usize * sh as usize),
+    };
+    let mut best = (0, 0, f64::NEG_INFINITY);
+    for y in 0..sh {
+        for x in 0..sw {
+            let sum = sums.window(x, y, tw, th);
+            let variance = (squares.window(x, y, tw, th) - sum * sum / n).max(0.0);
+            let denom = variance.sqrt() * t_norm;
+            let score = if denom > 1e-12 * n {
+                (numerator[(x, y)].re / denom).clamp(-1.0, 1.0)
+            } else {
+                0.0
+            };
+            if score > best.2 {
+                best = (x, y, score);
+            }
+            surface.data.push(Complex::new(score, 0.0));
+        }
+    }
+    MatchResult { surface, x: best.0, y: best.1, score: best.2 }
+}
+
+/// A summed-area table with a zero guard row and column, for O(1) window sums.
+struct SummedArea {
+    width: usize,
+    table: Vec<f64>,
+}
+
+impl SummedArea {
+    fn new(fi: &FreqImage, value: impl Fn(&Complex<f64>) -> f64) -> Self {
+        let width = fi.width as usize + 1;
+        let mut table = vec![0.0; width * (fi.height as usize + 1)];
+        for (x, y, c) in fi.enumerate_pixels() {
+            let (x, y) = (x as usize + 1, y as usize + 1);
+            table[y * width + x] =
+                value(c) + table[(y - 1) * width + x] + table[y * width + x - 1] - table[(y - 1) * width + x - 1];
+        }
+        SummedArea { width, table }
+    }
+
+    /// Sum over the `w × h` window whose top-left corner is `(x, y)`.
+    fn window(&self, x: u32, y: u32, w: u32, h: u32) -> f64 {
+        let (x0, y0, x1, y1) = (x as usize, y as usize, (x + w) as usize, (y + h) as usize);
+        let at = |x: usize, y: usize| self.table[y * self.width + x];
+        at(x1, y1) - at(x0, y1) - at(x1, y0) + at(x0, y0)
+    }
+}
+
+#[test]
+fn test_match_template_brightness_invariant() {
+    let image = FreqImage::open("img/mandrill.jpg").unwrap();
+    let mut template = image.crop(120, 200, 64, 64);
+    for c in template.data.iter_mut() {
+        *c = *c * 0.5 + 0.2;
+    }
+    let found = match_template(&image, &template);
+    assert_eq!((found.surface.width, found.surface.height), (image.width - 63, image.height - 63));
+    assert_eq!((found.x, found.y), (120, 200));
+    assert!(found.score > 0.99);
+    assert!(found.surface.data.iter().all(|c| (-1.0..=1.0).contains(&c.re)));
+}
+
+#[test]
+fn test_summed_area() {
+    let fi = super::ramp(4, 3);
+    let sat = SummedArea::new(&fi, |c| c.re);
+    assert_eq!(sat.window(0, 0, 4, 3), (0..12).sum::<u32>() as f64);
+    assert_eq!(sat.window(1, 1, 2, 2), (5 + 6 + 9 + 10) as f64);
+}
@@ -0,0 +1,162 @@
+//! Regression-test helpers: stable spectrum hashes, tolerant image comparison and
+//! golden reference PNGs. Enabled by the `testing` feature.
+
+use std::path::Path;
+
+use image::GrayImage;
+
+use super::FreqImage;
+
+/// Coefficients are rounded to multiples of this before hashing.
+const HASH_QUANTUM: f64 = 1e-6;
+
+/// Set to `1` to rewrite golden files instead of comparing against them.
+const UPDATE_VAR: &str = "UPDATE_GOLDEN";
+
+/// A hash of the dimensions and of every coefficient rounded to a multiple of `1e-6`,
+/// so round-off well below that doesn't change it. The hash (64-bit FNV-1a) is the same
+/// on every platform and release. All NaNs hash alike.
+pub fn hash_spectrum(fi: &FreqImage) -> u64 {
+    let mut hash = Fnv1a::new();
+    hash.write(fi.width as u64);
+    hash.write(fi.height as u64);
+    for c in &fi.data {
+        hash.write(quantize(c.re));
+        hash.write(quantize(c.im));
+    }
+    hash.0
+}
+
+/// Assert that `actual` and `expected` have the same size and that at most
+/// `max_fraction_differing` of their pixels differ by more than `max_abs_diff`.
+///
+/// # Panics
+/// Panics with the size mismatch, or with the number of differing pixels and the
+/// largest difference, if the images aren't close.
+pub fn assert_images_close(actual: &GrayImage, expected: &GrayImage, max_abs_diff: u8, max_fraction_differing: f64) {
+    assert_eq!(actual.dimensions(), expected.dimensions(), "images differ in size");
+    let (mut differing, mut worst) = (0, (0, 0, 0));
+    for (x, y, a) in actual.enumerate_pixels() {
+        let diff = a[0].abs_diff(expected.get_pixel(x, y)[0]);
+        if diff > max_abs_diff {
+            differing += 1;
+        }
+        if diff > worst.2 {
+            worst = (x, y, diff);
+        }
+    }
+    let fraction = differing as f64 / (actual.width() as f64 * actual.height() as f64).max(1.0);
+    assert!(
+        fraction <= max_fraction_differing,
+        "{} of {} pixels differ by more than {} (largest difference {} at ({}, {}))",
+        differing,
+        actual.width() * actual.height(),
+        max_abs_diff,
+        worst.2,
+        worst.0,
+        worst.1
+    );
+}
+
+/// Compare `actual` with the reference PNG at `path` using [`assert_images_close`].
+/// With `UPDATE_GOLDEN=1` set, write `actual` there instead (creating its directory)
+/// and pass, so new or intentionally changed references can be reviewed and committed.
+///
+/// # Panics
+/// Panics if the images aren't close, if the reference is missing and `UPDATE_GOLDEN=1`
+/// isn't set, or if the reference can't be read or written.
+pub fn assert_golden<P: AsRef<Path>>(path: P, actual: &GrayImage, max_abs_diff: u8, max_fraction_differing: f64) {
+    check_golden(path.as_ref(), actual, max_abs_diff, max_fraction_differing, update_requested());
+}
+
+fn check_golden(path: &Path, actual: &GrayImage, max_abs_diff: u8, max_fraction_differing: f64, update: bool) {
+    if update {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).unwrap_or_else(|err| panic!("creating {}: {}", dir.display(), err));
+        }
+        actual.save(path).unwrap_or_else(|err| panic!("writing {}: {}", path.display(), err));
+        eprintln!("wrote golden image {}", path.display());
+        return;
+    }
+    assert!(
+        path.exists(),
+        "golden image {} is missing; run with {}=1 to create it",
+        path.display(),
+        UPDATE_VAR
+    );
+    let expected = image::open(path)
+        .unwrap_or_else(|err| panic!("reading {}: {}", path.display(), err))
+        .into_luma8();
+    assert_images_close(actual, &expected, max_abs_diff, max_fraction_differing);
+}
+
+fn update_requested() -> bool {
+    std::env::var(UPDATE_VAR).is_ok_and(|v| v == "1")
+}
+
+fn quantize(v: f64) -> u64 {
+    if v.is_nan() {
+        u64::MAX
+    } else {
+        // `as` saturates, so infinities hash as the extreme values.
+        (v / HASH_QUANTUM).round() as i64 as u64
+    }
+}
+
+struct Fnv1a(u64);
+
+impl Fnv1a {
+    fn new() -> Self {
+        Fnv1a(0xcbf2_9ce4_8422_2325)
+    }
+
+    fn write(&mut self, v: u64) {
+        for byte in v.to_le_bytes() {
+            self.0 ^= byte as u64;
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+}
+
+#[test]
+fn test_hash_spectrum() {
+    let fi = super::ramp(5, 3);
+    let mut nudged = fi.clone();
+    nudged.data[4].re += 1e-9;
+    assert_eq!(hash_spectrum(&fi), hash_spectrum(&nudged));
+    nudged.data[4].re += 1e-3;
+    assert_ne!(hash_spectrum(&fi), hash_spectrum(&nudged));
+    // Same coefficients, different shape.
+    assert_ne!(hash_spectrum(&fi), hash_spectrum(&FreqImage { width: 3, height: 5, ..fi.clone() }));
+    // Pinned so the hash can't drift between releases.
+    assert_eq!(hash_spectrum(&super::ramp(2, 1)), 0x6858_5e3e_8a1b_17e1);
+}
+
+#[test]
+fn test_assert_images_close() {
+    let a = GrayImage::from_fn(10, 10, |x, y| image::Luma([(x * 20 + y) as u8]));
+    let mut b = a.clone();
+    b.put_pixel(3, 4, image::Luma([0]));
+    assert_images_close(&a, &b, 0, 0.01);
+    assert_images_close(&a, &b, 255, 0.0);
+    let result = std::panic::catch_unwind(|| assert_images_close(&a, &b, 0, 0.0));
+    let message = *result.unwrap_err().downcast::<String>().unwrap();
+    assert_eq!(message, "1 of 100 pixels differ by more than 0 (largest difference 64 at (3, 4))");
+}
+
+#[test]
+fn test_assert_golden() {
+    let path = std::env::temp_dir().join(format!("freqshow_golden_{}", std::process::id())).join("g.png");
+    let img = GrayImage::from_fn(6, 4, |x, y| image::Luma([(x * 40 + y) as u8]));
+    let missing = std::panic::catch_unwind(|| check_golden(&path, &img, 0, 0.0, false));
+    assert!(!path.exists());
+    check_golden(&path, &img, 0, 0.0, true);
+    assert!(path.exists());
+    check_golden(&path, &img, 0, 0.0, false);
+    let other = GrayImage::new(6, 4);
+    let mismatch = std::panic::catch_unwind(|| check_golden(&path, &other, 0, 0.0, false));
+    std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    let message = *missing.unwrap_err().downcast::<String>().unwrap();
+    assert!(message.ends_with("is missing; run with UPDATE_GOLDEN=1 to create it"), "{}", message);
+    assert!(mismatch.is_err());
+}
@@ -0,0 +1,77 @@
+//! Single-band floating point TIFF import/export of the real component.
+//!
+//! Unlike `to_image`-style exports the values are written as-is, with no
+//! clamping or scaling, so negative and out-of-range values survive.
+
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+use std::path::Path;
+
+use rustfft::num_complex::Complex;
+use tiff::decoder::{Decoder, DecodingResult};
+use tiff::encoder::{colortype::Gray32Float, TiffEncoder};
+use tiff::{ColorType, TiffError};
+
+use super::FreqImage;
+use crate::FreqError;
+
+impl FreqImage {
+    /// Write the real parts as a single-band 32-bit float TIFF; imaginary parts are dropped.
+    pub fn save_tiff_f32<P: AsRef<Path>>(&self, path: P) -> Result<(), FreqError> {
+        let real: Vec<f32> = self.data.iter().map(|c| c.re as f32).collect();
+        let mut encoder = TiffEncoder::new(BufWriter::new(File::create(path)?)).map_err(tiff_error)?;
+        encoder
+            .write_image::<Gray32Float>(self.width, self.height, &real)
+            .map_err(tiff_error)
+    }
+
+    /// Read a single-band 32- or 64-bit float TIFF into the real parts of a FreqImage.
+    pub fn open_tiff_f32<P: AsRef<Path>>(path: P) -> Result<Self, FreqError> {
+        let mut decoder = Decoder::new(BufReader::new(File::open(path)?)).map_err(tiff_error)?;
+        let (width, height) = decoder.dimensions().map_err(tiff_error)?;
+        let colortype = decoder.colortype().map_err(tiff_error)?;
+        if !matches!(colortype, ColorType::Gray(32) | ColorType::Gray(64)) {
+            return Err(FreqError::InvalidFormat(format!(
+                "TIFF: expected a single-band float image, got {:?}",
+                colortype
+            )));
+        }
+        let data: Vec<Complex<f64>> = match decoder.read_image().map_err(tiff_error)? {
+            DecodingResult::F32(values) => values.iter().map(|&v| Complex::new(v as f64, 0.0)).collect(),
+            DecodingResult::F64(values) => values.iter().map(|&v| Complex::new(v, 0.0)).collect(),
+            _ => {
+                return Err(FreqError::InvalidFormat(
+                    "TIFF: expected floating point samples".to_string(),
+                ))
+            }
+        };
+        let expected = width as usize * height as usize;
+        if data.len() != expected {
+            return Err(FreqError::LengthMismatch { expected, got: data.len() });
+        }
+        Ok(FreqImage { width, height, data })
+    }
+}
+
+fn tiff_error(err: TiffError) -> FreqError {
+    match err {
+        TiffError::IoError(err) => FreqError::Io(err),
+        err => FreqError::InvalidFormat(format!("TIFF: {}", err)),
+    }
+}
+
+#[test]
+fn test_tiff_f32_round_trip() {
+    let values = [-5.5, 0.0, 0.25, 1e6, 1.0, -0.125];
+    let fi = FreqImage {
+        width: 3,
+        height: 2,
+        data: values.iter().map(|&v| Complex::new(v, 0.0)).collect(),
+    };
+    let path = std::env::temp_dir().join(format!("freqshow_tiff_{}.tif", std::process::id()));
+    fi.save_tiff_f32(&path).unwrap();
+    let back = FreqImage::open_tiff_f32(&path).unwrap();
+    std::fs::remove_file(path).unwrap();
+
+    assert_eq!(back, fi);
+}
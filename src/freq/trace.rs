@@ -0,0 +1,128 @@
+//! Wall-clock timing of processing stages, without a profiler.
+//!
+//! A [`StageTimer`] is an ordinary value: create one, pass it to the `*_timed` variants
+//! such as [`FilterPipeline::run_timed`](super::pipeline::FilterPipeline::run_timed) or
+//! wrap your own steps with [`StageTimer::time`], then read or print the results.
+
+use std::fmt::Write;
+use std::time::{Duration, Instant};
+
+/// Durations of named stages, in the order each stage first ran. Timing a stage again
+/// adds to its total.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct StageTimer {
+    stages: Vec<(String, Duration)>,
+}
+
+impl StageTimer {
+    /// A timer with no stages recorded.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Run `f` and add the time it took to `stage`.
+    pub fn time<T>(&mut self, stage: &str, f: impl FnOnce() -> T) -> T {
+        let start = Instant::now();
+        let out = f();
+        self.record(stage, start.elapsed());
+        out
+    }
+
+    /// Add `duration` to `stage`, for time measured elsewhere.
+    pub fn record(&mut self, stage: &str, duration: Duration) {
+        match self.stages.iter_mut().find(|(name, _)| name == stage) {
+            Some((_, total)) => *total += duration,
+            None => self.stages.push((stage.to_string(), duration)),
+        }
+    }
+
+    /// Each stage with its total time, in the order they first ran.
+    pub fn stages(&self) -> &[(String, Duration)] {
+        &self.stages
+    }
+
+    /// The total time of `stage`, or `None` if it never ran.
+    pub fn get(&self, stage: &str) -> Option<Duration> {
+        self.stages.iter().find(|(name, _)| name == stage).map(|&(_, d)| d)
+    }
+
+    /// The sum over all stages.
+    pub fn total(&self) -> Duration {
+        self.stages.iter().map(|&(_, d)| d).sum()
+    }
+
+    /// A plain-text table with one line per stage in milliseconds, then the total.
+    pub fn table(&self) -> String {
+        let width = self.stages.iter().map(|(name, _)| name.len()).max().unwrap_or(0).max("total".len());
+        let mut out = String::new();
+        for (name, duration) in self.stages.iter().map(|(n, d)| (n.as_str(), *d)).chain([("total", self.total())]) {
+            let _ = writeln!(out, "{:<width$}  {:>10.3} ms", name, duration.as_secs_f64() * 1e3, width = width);
+        }
+        out
+    }
+
+    /// The stages as JSON:
+    /// `{"stages":[{"name":"load","seconds":0.0012},...],"total_seconds":0.0034}`.
+    pub fn to_json(&self) -> String {
+        let stages: Vec<String> = self
+            .stages
+            .iter()
+            .map(|(name, d)| format!("{{\"name\":{},\"seconds\":{}}}", json_string(name), d.as_secs_f64()))
+            .collect();
+        format!("{{\"stages\":[{}],\"total_seconds\":{}}}", stages.join(","), self.total().as_secs_f64())
+    }
+}
+
+/// `s` as a quoted JSON string.
+fn json_string(s: &str) -> String {
+    let mut out = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            c if c.is_control() => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+#[test]
+fn test_stage_timer() {
+    let mut timer = StageTimer::new();
+    assert_eq!(timer.time("load", || 7), 7);
+    timer.record("fft_forward", Duration::from_millis(5));
+    timer.record("load", Duration::from_millis(2));
+    let names: Vec<&str> = timer.stages().iter().map(|(n, _)| n.as_str()).collect();
+    assert_eq!(names, ["load", "fft_forward"]);
+    assert!(timer.get("load").unwrap() >= Duration::from_millis(2));
+    assert_eq!(timer.get("save"), None);
+    assert_eq!(timer.total(), timer.get("load").unwrap() + Duration::from_millis(5));
+
+    let table = timer.table();
+    assert_eq!(table.lines().count(), 3);
+    assert!(table.lines().nth(1).unwrap().starts_with("fft_forward") && table.contains("5.000 ms"));
+    assert!(table.lines().last().unwrap().starts_with("total"));
+}
+
+#[test]
+fn test_pipeline_timing_json() {
+    use super::pipeline::{FilterPipeline, MaskSpec};
+
+    let fi = super::ramp(8, 6);
+    let mut timer = StageTimer::new();
+    let mut pipeline = FilterPipeline::new().mask(MaskSpec::LowPass { cutoff: 0.2, smoothing: 0.05 });
+    let timed = pipeline.run_timed(&fi, &mut timer).unwrap();
+    assert_eq!(timed, pipeline.run(&fi).unwrap());
+    timer.record("quote\"d", Duration::ZERO);
+
+    let json: serde_json::Value = serde_json::from_str(&timer.to_json()).unwrap();
+    let stages = json["stages"].as_array().unwrap();
+    let names: Vec<&str> = stages.iter().map(|s| s["name"].as_str().unwrap()).collect();
+    assert_eq!(names, ["fft_forward", "shift", "mask", "filter", "fft_inverse", "quote\"d"]);
+    let sum: f64 = stages.iter().map(|s| s["seconds"].as_f64().unwrap()).sum();
+    assert!((json["total_seconds"].as_f64().unwrap() - sum).abs() < 1e-9);
+}
@@ -0,0 +1,337 @@
+//! Grayscale visualizations of a spectrum.
+
+use std::path::Path;
+
+use std::f64::consts::PI;
+
+use image::{GrayImage, ImageBuffer, ImageFormat, Luma, Rgb, RgbImage};
+
+use super::normalize::check_finite;
+use super::polar::phase_of;
+use super::FreqImage;
+use crate::FreqError;
+
+impl FreqImage {
+    /// Convert the norm of the FFT into an 8-bit image for visualization,
+    /// using `ln(1 + |c|)` scaled so the largest coefficient is white. Coefficients with a
+    /// NaN or infinite part render black and don't count towards the maximum.
+    pub fn view_fft_norm(&self) -> GrayImage {
+        let (log_norm, max) = self.log_norm();
+        let scale = if max > 0.0 { 255.0 / max } else { 0.0 };
+        let pixels = log_norm.iter().map(|&v| (v * scale) as u8).collect();
+        GrayImage::from_raw(self.width, self.height, pixels).unwrap()
+    }
+
+    /// [`FreqImage::view_fft_norm`], but fail with [`FreqError::NonFinite`] if any
+    /// coefficient has a NaN or infinite part.
+    pub fn view_fft_norm_checked(&self) -> Result<GrayImage, FreqError> {
+        check_finite(self.data.iter().map(|c| c.is_finite()))?;
+        Ok(self.view_fft_norm())
+    }
+
+    /// Same as [`FreqImage::view_fft_norm`] but quantized to the full 16-bit range.
+    pub fn view_fft_norm16(&self) -> ImageBuffer<Luma<u16>, Vec<u16>> {
+        let (log_norm, max) = self.log_norm();
+        let scale = if max > 0.0 { 65535.0 / max } else { 0.0 };
+        let pixels = log_norm.iter().map(|&v| (v * scale) as u16).collect();
+        ImageBuffer::from_raw(self.width, self.height, pixels).unwrap()
+    }
+
+    /// Write [`FreqImage::view_fft_norm16`] to `path` as a 16-bit PNG.
+    pub fn save_spectrum16<P: AsRef<Path>>(&self, path: P) -> image::ImageResult<()> {
+        self.view_fft_norm16().save_with_format(path, ImageFormat::Png)
+    }
+
+    /// Like [`FreqImage::view_fft_norm`], but clip the `ln(1 + |c|)` values to their
+    /// `lo_pct` and `hi_pct` percentiles and stretch that range to `[0, 255]`, so a
+    /// single dominant coefficient (typically DC) no longer darkens everything else.
+    ///
+    /// The percentiles are taken over the values plus one zero, the black level of
+    /// `view_fft_norm`, so `(0.0, 100.0)` reproduces it. Like every percentile, black
+    /// then rises one sample at a time as `lo_pct` grows: it leaves zero for the smallest
+    /// coefficient only once `lo_pct` reaches half a sample, `50 / N` percent.
+    ///
+    /// # Panics
+    /// Panics unless `0 <= lo_pct <= hi_pct <= 100`.
+    pub fn view_fft_norm_clipped(&self, lo_pct: f64, hi_pct: f64) -> GrayImage {
+        check_percentiles(lo_pct, hi_pct).unwrap_or_else(|err| panic!("{}", err));
+        let (log_norm, _) = self.log_norm();
+        let mut scratch = log_norm.clone();
+        scratch.push(0.0);
+        let lo = percentile(&mut scratch, lo_pct);
+        let hi = percentile(&mut scratch, hi_pct);
+        let scale = if hi > lo { 255.0 / (hi - lo) } else { 0.0 };
+        let pixels = log_norm
+            .iter()
+            .map(|&v| ((v.clamp(lo, hi) - lo) * scale) as u8)
+            .collect();
+        GrayImage::from_raw(self.width, self.height, pixels).unwrap()
+    }
+
+    /// Render the magnitude in decibels relative to the peak, `20·log10(|c| / max|c|)`,
+    /// clamped to `[floor_db, 0]` and mapped linearly onto `[0, 255]`.
+    ///
+    /// `floor_db` should be negative (e.g. `-80.0`); an all-zero spectrum renders black.
+    pub fn view_fft_db(&self, floor_db: f64) -> GrayImage {
+        let max = self.data.iter().map(|c| c.norm()).fold(0.0, f64::max);
+        let pixels = self
+            .data
+            .iter()
+            .map(|c| {
+                if max <= 0.0 {
+                    return 0;
+                }
+                let db = 20.0 * (c.norm() / max).log10();
+                if floor_db >= 0.0 {
+                    return if db >= 0.0 { 255 } else { 0 };
+                }
+                ((db.max(floor_db) - floor_db) / -floor_db * 255.0).round() as u8
+            })
+            .collect();
+        GrayImage::from_raw(self.width, self.height, pixels).unwrap()
+    }
+
+    /// Render the phase of every coefficient, mapping `(-π, π]` linearly onto `[0, 255]`.
+    /// Zero-magnitude bins (whose phase is undefined) are mid-gray.
+    pub fn view_fft_phase(&self) -> GrayImage {
+        let pixels = self
+            .data
+            .iter()
+            .map(|&c| ((phase_of(c) + PI) / (2.0 * PI) * 255.0).round() as u8)
+            .collect();
+        GrayImage::from_raw(self.width, self.height, pixels).unwrap()
+    }
+
+    /// Domain-coloring view: hue encodes phase and value encodes the
+    /// `ln(1 + |c|)` magnitude used by [`FreqImage::view_fft_norm`].
+    pub fn view_fft_phase_hsv(&self) -> RgbImage {
+        let (log_norm, max) = self.log_norm();
+        let scale = if max > 0.0 { 1.0 / max } else { 0.0 };
+        let mut out = RgbImage::new(self.width, self.height);
+        for ((pix, &c), &v) in out.pixels_mut().zip(&self.data).zip(&log_norm) {
+            let hue = (phase_of(c) + PI) / (2.0 * PI);
+            *pix = hsv_to_rgb(hue, 1.0, v * scale);
+        }
+        out
+    }
+
+    /// `ln(1 + |c|)` for every coefficient, plus the largest such value. Non-finite
+    /// coefficients count as zero.
+    pub(crate) fn log_norm(&self) -> (Vec<f64>, f64) {
+        let log_norm: Vec<f64> = self
+            .data
+            .iter()
+            .map(|c| if c.is_finite() { c.norm().ln_1p() } else { 0.0 })
+            .collect();
+        let max = log_norm.iter().cloned().fold(0.0, f64::max);
+        (log_norm, max)
+    }
+}
+
+/// The `pct` percentile (0–100, nearest rank) of `values`, found by selection
+/// rather than a full sort. Reorders `values`; returns 0 for an empty slice.
+pub(crate) fn percentile(values: &mut [f64], pct: f64) -> f64 {
+    if values.is_empty() {
+        return 0.0;
+    }
+    let rank = (pct.clamp(0.0, 100.0) / 100.0 * (values.len() - 1) as f64).round() as usize;
+    *values.select_nth_unstable_by(rank, |a, b| a.total_cmp(b)).1
+}
+
+/// [`FreqError::InvalidArgument`] unless `0 <= lo_pct <= hi_pct <= 100`.
+pub(crate) fn check_percentiles(lo_pct: f64, hi_pct: f64) -> Result<(), FreqError> {
+    if (0.0..=100.0).contains(&lo_pct) && (lo_pct..=100.0).contains(&hi_pct) {
+        Ok(())
+    } else {
+        Err(FreqError::InvalidArgument(format!(
+            "percentiles must satisfy 0 <= lo <= hi <= 100, got ({}, {})",
+            lo_pct, hi_pct
+        )))
+    }
+}
+
+/// Convert a color with hue, saturation and value in [0, 1] to 8-bit RGB.
+fn hsv_to_rgb(hue: f64, saturation: f64, value: f64) -> Rgb<u8> {
+    let h = (hue.rem_euclid(1.0)) * 6.0;
+    let sector = h.floor();
+    let f = h - sector;
+    let (p, q, t) = (
+        value * (1.0 - saturation),
+        value * (1.0 - saturation * f),
+        value * (1.0 - saturation * (1.0 - f)),
+    );
+    let (r, g, b) = match sector as u8 {
+        0 => (value, t, p),
+        1 => (q, value, p),
+        2 => (p, value, t),
+        3 => (p, q, value),
+        4 => (t, p, value),
+        _ => (value, p, q),
+    };
+    Rgb([(r * 255.0).round() as u8, (g * 255.0).round() as u8, (b * 255.0).round() as u8])
+}
+
+#[cfg(test)]
+fn real_image(width: u32, height: u32, values: &[f64]) -> FreqImage {
+    FreqImage {
+        width,
+        height,
+        data: values.iter().map(|&v| rustfft::num_complex::Complex::new(v, 0.0)).collect(),
+    }
+}
+
+#[test]
+fn test_view_fft_norm16_resolves_8bit_collisions() {
+    let fi = real_image(3, 1, &[1000.0, 10.0, 10.11]);
+
+    let view8 = fi.view_fft_norm();
+    assert_eq!(view8.as_raw()[0], 255);
+    assert_eq!(view8.as_raw()[1], view8.as_raw()[2]);
+
+    let view16 = fi.view_fft_norm16();
+    assert_eq!(view16.as_raw()[0], 65535);
+    assert!(view16.as_raw()[2] > view16.as_raw()[1]);
+}
+
+#[test]
+fn test_view_fft_norm_zero_spectrum() {
+    let fi = real_image(2, 2, &[0.0; 4]);
+    assert!(fi.view_fft_norm().as_raw().iter().all(|&p| p == 0));
+    assert!(fi.view_fft_norm16().as_raw().iter().all(|&p| p == 0));
+}
+
+#[test]
+fn test_view_fft_norm_non_finite() {
+    let mut fi = real_image(4, 1, &[0.0, (255f64).exp_m1(), f64::NAN, 1.0]);
+    fi.data[3].im = f64::NEG_INFINITY;
+    fi.data[0].re = f64::INFINITY;
+    let view = fi.view_fft_norm();
+    assert_eq!(view.as_raw(), &[0, 255, 0, 0]);
+    assert!(matches!(fi.view_fft_norm_checked(), Err(FreqError::NonFinite { count: 3, first_index: 0 })));
+    assert_eq!(real_image(2, 1, &[1.0, 2.0]).view_fft_norm_checked().unwrap(), real_image(2, 1, &[1.0, 2.0]).view_fft_norm());
+}
+
+#[test]
+fn test_view_fft_phase_real_positive_is_constant() {
+    let fi = real_image(3, 2, &[1.0, 2.0, 0.5, 0.0, 7.0, 3.0]);
+    let phase = fi.view_fft_phase();
+    assert!(phase.as_raw().iter().all(|&p| p == 128));
+}
+
+#[test]
+fn test_view_fft_phase_shows_translation() {
+    let (width, height) = (32u32, 24u32);
+    let pattern = |x: u32, y: u32| ((x * 7 + y * 13) % 17) as f64 / 17.0 + if x < 10 && y < 8 { 1.0 } else { 0.0 };
+    let mut original = real_image(width, height, &vec![0.0; (width * height) as usize]);
+    let mut shifted = original.clone();
+    for y in 0..height {
+        for x in 0..width {
+            original[(x, y)].re = pattern(x, y);
+            shifted[((x + 5) % width, (y + 3) % height)].re = pattern(x, y);
+        }
+    }
+    original.fft_forward();
+    shifted.fft_forward();
+
+    let a = original.view_fft_phase();
+    let b = shifted.view_fft_phase();
+    let mean_diff = a
+        .as_raw()
+        .iter()
+        .zip(b.as_raw())
+        .map(|(&p, &q)| (p as f64 - q as f64).abs())
+        .sum::<f64>()
+        / a.as_raw().len() as f64;
+    assert!(mean_diff > 30.0, "mean phase difference {}", mean_diff);
+
+    // Magnitude is translation invariant, so the HSV value channel is unchanged.
+    let hsv_a = original.view_fft_phase_hsv();
+    let hsv_b = shifted.view_fft_phase_hsv();
+    for (p, q) in hsv_a.pixels().zip(hsv_b.pixels()) {
+        let value = |px: &Rgb<u8>| *px.0.iter().max().unwrap() as i32;
+        assert!((value(p) - value(q)).abs() <= 1);
+    }
+}
+
+#[test]
+fn test_view_fft_db() {
+    // Peak, -20 dB, -40 dB, -80 dB and zero.
+    let fi = real_image(5, 1, &[1000.0, 100.0, 10.0, 0.1, 0.0]);
+
+    let view = fi.view_fft_db(-40.0);
+    assert_eq!(view.as_raw()[..], [255, 128, 0, 0, 0]);
+
+    let view = fi.view_fft_db(-80.0);
+    assert_eq!(view.as_raw()[0], 255);
+    assert_eq!(view.as_raw()[3], 0);
+
+    let mut previous = 0;
+    for floor in [-30.0, -40.0, -60.0, -80.0] {
+        let mid = fi.view_fft_db(floor).as_raw()[1];
+        assert!(mid > previous);
+        previous = mid;
+    }
+}
+
+#[test]
+fn test_view_fft_db_zero_spectrum() {
+    let fi = real_image(2, 2, &[0.0; 4]);
+    assert!(fi.view_fft_db(-80.0).as_raw().iter().all(|&p| p == 0));
+}
+
+#[test]
+fn test_view_fft_norm_clipped_full_range() {
+    let mut fi = FreqImage::open("img/mandrill.jpg").unwrap();
+    fi.fft_forward();
+
+    let clipped = fi.view_fft_norm_clipped(0.0, 100.0);
+    let plain = fi.view_fft_norm();
+    for (a, b) in clipped.as_raw().iter().zip(plain.as_raw()) {
+        assert!((*a as i32 - *b as i32).abs() <= 1);
+    }
+}
+
+#[test]
+fn test_view_fft_norm_clipped_ignores_outlier() {
+    let mut fi = FreqImage::open("img/mandrill.jpg").unwrap();
+    fi.fft_forward();
+    fi.data[0] *= 1e12;
+
+    let median = |img: GrayImage| {
+        let mut v = img.into_raw();
+        v.sort_unstable();
+        v[v.len() / 2]
+    };
+    let plain = median(fi.view_fft_norm());
+    let clipped = median(fi.view_fft_norm_clipped(1.0, 99.0));
+    assert!(clipped >= 32 && clipped as u32 > 4 * plain as u32, "median {} vs {}", clipped, plain);
+}
+
+#[test]
+fn test_view_fft_norm_clipped_low_percentile_is_continuous() {
+    let mut fi = FreqImage::open("img/mandrill.jpg").unwrap();
+    fi.fft_forward();
+
+    // Black stays at zero until lo_pct reaches half a sample.
+    let half_sample = 50.0 / fi.data.len() as f64;
+    assert_eq!(fi.view_fft_norm_clipped(1e-9, 100.0), fi.view_fft_norm_clipped(0.0, 100.0));
+    assert_eq!(fi.view_fft_norm_clipped(0.9 * half_sample, 100.0), fi.view_fft_norm());
+    assert_ne!(fi.view_fft_norm_clipped(1.1 * half_sample, 100.0), fi.view_fft_norm());
+    let mean = |img: GrayImage| img.as_raw().iter().map(|&p| p as f64).sum::<f64>() / img.as_raw().len() as f64;
+    let means: Vec<f64> = [0.0, 0.01, 1.0, 10.0].iter().map(|&lo| mean(fi.view_fft_norm_clipped(lo, 100.0))).collect();
+    assert!(means.windows(2).all(|w| w[1] <= w[0]), "{:?}", means);
+}
+
+#[test]
+#[should_panic(expected = "percentiles must satisfy")]
+fn test_view_fft_norm_clipped_reversed() {
+    super::ramp(4, 4).view_fft_norm_clipped(90.0, 10.0);
+}
+
+#[test]
+fn test_percentile() {
+    let mut values = [5.0, 1.0, 4.0, 2.0, 3.0];
+    assert_eq!(percentile(&mut values, 0.0), 1.0);
+    assert_eq!(percentile(&mut values, 50.0), 3.0);
+    assert_eq!(percentile(&mut values, 100.0), 5.0);
+}
@@ -0,0 +1,89 @@
+//! On-screen display with show_image, behind the `viewer` feature.
+//!
+//! show_image runs its event loop on the main thread, so a program that opens windows
+//! must wrap its `main` in `#[show_image::main]`. The returned [`WindowProxy`] keeps
+//! working after the call; use it to replace the image or read keyboard events.
+
+use image::GrayImage;
+use show_image::{create_window, ImageInfo, ImageView, WindowProxy};
+
+use super::FreqImage;
+use crate::FreqError;
+
+/// Width in pixels of the black gap between the halves of [`show_side_by_side`].
+const GAP: u32 = 4;
+
+impl FreqImage {
+    /// Open a window titled `title` showing the image, rescaled to [0, 255] as by
+    /// [`FreqImage::to_image`].
+    ///
+    /// # Panics
+    /// Panics if the show_image context is not running; see the module docs.
+    pub fn show(&self, title: &str) -> Result<WindowProxy, FreqError> {
+        show_gray(&self.to_image(), title)
+    }
+
+    /// Open a window titled `title` showing the log-magnitude view of a spectrum, as by
+    /// [`FreqImage::view_fft_norm`].
+    ///
+    /// # Panics
+    /// Panics if the show_image context is not running; see the module docs.
+    pub fn show_spectrum(&self, title: &str) -> Result<WindowProxy, FreqError> {
+        show_gray(&self.view_fft_norm(), title)
+    }
+}
+
+/// Open a window titled `title` with `a` on the left and `b` on the right, for
+/// before/after comparisons. Images of different heights are top-aligned on black.
+///
+/// # Panics
+/// Panics if the show_image context is not running; see the module docs.
+pub fn show_side_by_side(a: &GrayImage, b: &GrayImage, title: &str) -> Result<WindowProxy, FreqError> {
+    show_gray(&side_by_side(a, b), title)
+}
+
+/// Borrow a grayscale image as a show_image view without copying.
+pub fn image_view(img: &GrayImage) -> ImageView<'_> {
+    ImageView::new(ImageInfo::mono8(img.width(), img.height()), img.as_raw())
+}
+
+/// `a` and `b` next to each other, separated by a [`GAP`]-pixel black strip.
+fn side_by_side(a: &GrayImage, b: &GrayImage) -> GrayImage {
+    let mut out = GrayImage::new(a.width() + GAP + b.width(), a.height().max(b.height()));
+    for (x, y, &p) in a.enumerate_pixels() {
+        out.put_pixel(x, y, p);
+    }
+    for (x, y, &p) in b.enumerate_pixels() {
+        out.put_pixel(a.width() + GAP + x, y, p);
+    }
+    out
+}
+
+fn show_gray(img: &GrayImage, title: &str) -> Result<WindowProxy, FreqError> {
+    let window = create_window(title, Default::default()).map_err(|err| FreqError::Viewer(err.to_string()))?;
+    window
+        .set_image(title, image_view(img))
+        .map_err(|err| FreqError::Viewer(err.to_string()))?;
+    Ok(window)
+}
+
+#[test]
+fn test_image_view() {
+    let img = GrayImage::from_fn(5, 3, |x, y| image::Luma([(10 * y + x) as u8]));
+    let view = image_view(&img);
+    assert_eq!(view.info(), ImageInfo::mono8(5, 3));
+    assert_eq!(view.data(), img.as_raw().as_slice());
+    assert!(std::ptr::eq(view.data(), img.as_raw().as_slice()));
+}
+
+#[test]
+fn test_side_by_side() {
+    let a = GrayImage::from_pixel(3, 2, image::Luma([100]));
+    let b = GrayImage::from_pixel(2, 4, image::Luma([200]));
+    let out = side_by_side(&a, &b);
+    assert_eq!(out.dimensions(), (3 + GAP + 2, 4));
+    assert_eq!(out.get_pixel(2, 1)[0], 100);
+    assert_eq!(out.get_pixel(0, 3)[0], 0);
+    assert_eq!(out.get_pixel(3, 0)[0], 0);
+    assert_eq!(out.get_pixel(3 + GAP, 3)[0], 200);
+}
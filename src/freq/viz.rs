@@ -0,0 +1,439 @@
+//! Composite figures built from rendered images and spectra.
+
+mod font;
+
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+
+use image::codecs::gif::{GifEncoder, Repeat};
+use image::{Delay, DynamicImage, Frame, GrayImage, Luma, Rgb, RgbImage};
+
+use super::{FftContext, FreqImage};
+use crate::FreqError;
+
+/// Something to draw over a centered spectrum view with [`annotate_spectrum`]. Positions
+/// are relative to the DC bin at `(width/2, height/2)`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Annotation {
+    /// A red crosshair on the DC bin.
+    CenterCross,
+    /// A circle around DC at `radius_frac` of the image diagonal, matching the cutoffs of
+    /// [`FreqImage::low_pass_mask`] and friends.
+    Ring {
+        /// Radius as a fraction of the diagonal.
+        radius_frac: f64,
+        /// Line color.
+        color: Rgb<u8>,
+    },
+    /// A small diagonal cross on the signed bin `(u, v)`, as in
+    /// [`SpectralPeak`](super::SpectralPeak).
+    Point {
+        /// Horizontal frequency in bins, signed.
+        u: f64,
+        /// Vertical frequency in bins, signed, counting rows downward.
+        v: f64,
+        /// Marker color.
+        color: Rgb<u8>,
+    },
+    /// White text with its top-left corner at pixel `(x, y)`.
+    Label {
+        /// Left edge in pixels.
+        x: u32,
+        /// Top edge in pixels.
+        y: u32,
+        /// Text to draw; letters are shown in upper case.
+        text: String,
+    },
+}
+
+/// Copy `base` to RGB and draw `annotations` over it in order, clipped to the image.
+pub fn annotate_spectrum(base: &GrayImage, annotations: &[Annotation]) -> RgbImage {
+    let mut img = DynamicImage::ImageLuma8(base.clone()).into_rgb8();
+    let (w, h) = img.dimensions();
+    let (cx, cy) = ((w / 2) as i64, (h / 2) as i64);
+    for annotation in annotations {
+        match annotation {
+            Annotation::CenterCross => {
+                let arm = (w.min(h) as i64 / 32).max(3);
+                let red = Rgb([255, 0, 0]);
+                draw_line(&mut img, (cx - arm, cy), (cx + arm, cy), red);
+                draw_line(&mut img, (cx, cy - arm), (cx, cy + arm), red);
+            }
+            Annotation::Ring { radius_frac, color } => {
+                let radius = (radius_frac * (w as f64).hypot(h as f64)).round() as i64;
+                draw_circle(&mut img, (cx, cy), radius, *color);
+            }
+            Annotation::Point { u, v, color } => {
+                let (x, y) = (cx + u.round() as i64, cy + v.round() as i64);
+                draw_line(&mut img, (x - 2, y - 2), (x + 2, y + 2), *color);
+                draw_line(&mut img, (x - 2, y + 2), (x + 2, y - 2), *color);
+            }
+            Annotation::Label { x, y, text } => {
+                font::draw_text(&mut img, *x as i64, *y as i64, text, w, Rgb([255, 255, 255]));
+            }
+        }
+    }
+    img
+}
+
+impl FreqImage {
+    /// [`FreqImage::view_fft_norm`] with `annotations` drawn over it; see
+    /// [`annotate_spectrum`]. The spectrum should already be centered.
+    pub fn view_fft_annotated(&self, annotations: &[Annotation]) -> RgbImage {
+        annotate_spectrum(&self.view_fft_norm(), annotations)
+    }
+
+    /// [`FreqImage::view_fft_norm`] with the `level` contour of `mask` drawn in red: every
+    /// pixel whose mask weight lies on the other side of `level` from its right or bottom
+    /// neighbour.
+    ///
+    /// # Panics
+    /// Panics if `mask` does not hold `width * height` weights.
+    pub fn view_with_mask(&self, mask: &[f64], level: f64) -> RgbImage {
+        if mask.len() != self.data.len() {
+            panic!("mask has {} weights for a {}x{} FreqImage", mask.len(), self.width, self.height);
+        }
+        let mut img = DynamicImage::ImageLuma8(self.view_fft_norm()).into_rgb8();
+        let (w, h) = (self.width as usize, self.height as usize);
+        let above = |x: usize, y: usize| mask[y * w + x] >= level;
+        for y in 0..h {
+            for x in 0..w {
+                let right = x + 1 < w && above(x, y) != above(x + 1, y);
+                let below = y + 1 < h && above(x, y) != above(x, y + 1);
+                if right || below {
+                    img.put_pixel(x as u32, y as u32, Rgb([255, 0, 0]));
+                }
+            }
+        }
+        img
+    }
+}
+
+fn plot(img: &mut RgbImage, x: i64, y: i64, color: Rgb<u8>) {
+    if (0..img.width() as i64).contains(&x) && (0..img.height() as i64).contains(&y) {
+        img.put_pixel(x as u32, y as u32, color);
+    }
+}
+
+/// Bresenham's line from `a` to `b`, both ends included.
+fn draw_line(img: &mut RgbImage, a: (i64, i64), b: (i64, i64), color: Rgb<u8>) {
+    let (dx, dy) = ((b.0 - a.0).abs(), -(b.1 - a.1).abs());
+    let (sx, sy) = ((b.0 - a.0).signum(), (b.1 - a.1).signum());
+    let (mut x, mut y, mut err) = (a.0, a.1, dx + dy);
+    loop {
+        plot(img, x, y, color);
+        if (x, y) == b {
+            break;
+        }
+        let e2 = 2 * err;
+        if e2 >= dy {
+            err += dy;
+            x += sx;
+        }
+        if e2 <= dx {
+            err += dx;
+            y += sy;
+        }
+    }
+}
+
+/// Midpoint circle of the given radius, drawn one octant at a time.
+fn draw_circle(img: &mut RgbImage, center: (i64, i64), radius: i64, color: Rgb<u8>) {
+    let (mut x, mut y, mut err) = (radius, 0, 1 - radius);
+    while x >= y {
+        for (px, py) in [(x, y), (y, x), (-y, x), (-x, y), (-x, -y), (-y, -x), (y, -x), (x, -y)] {
+            plot(img, center.0 + px, center.1 + py, color);
+        }
+        y += 1;
+        if err < 0 {
+            err += 2 * y + 1;
+        } else {
+            x -= 1;
+            err += 2 * (y - x) + 1;
+        }
+    }
+}
+
+/// Lay equal-sized grayscale images out in a grid of `columns` columns, left to right and
+/// top to bottom, with `padding` pixels of black around and between them. With `labels`,
+/// each tile gets a strip beneath it holding its label in a tiny built-in font, cut off at
+/// the tile's width.
+///
+/// Errors if `images` is empty or `columns` is zero, if the images differ in size, or if
+/// `labels` doesn't have one entry per image.
+pub fn contact_sheet(
+    images: &[GrayImage],
+    columns: u32,
+    padding: u32,
+    labels: Option<&[String]>,
+) -> Result<RgbImage, FreqError> {
+    let first = images.first().ok_or_else(|| FreqError::InvalidArgument("contact sheet needs at least one image".into()))?;
+    if columns == 0 {
+        return Err(FreqError::InvalidArgument("contact sheet needs at least one column".into()));
+    }
+    let (tile_w, tile_h) = first.dimensions();
+    if let Some(other) = images.iter().find(|img| img.dimensions() != (tile_w, tile_h)) {
+        return Err(FreqError::DimensionMismatch { expected: (tile_w, tile_h), got: other.dimensions() });
+    }
+    if let Some(labels) = labels.filter(|l| l.len() != images.len()) {
+        return Err(FreqError::LengthMismatch { expected: images.len(), got: labels.len() });
+    }
+
+    let label_h = if labels.is_some() { font::GLYPH_HEIGHT + 2 } else { 0 };
+    let columns = columns.min(images.len() as u32);
+    let rows = (images.len() as u32).div_ceil(columns);
+    let (cell_w, cell_h) = (tile_w + padding, tile_h + label_h + padding);
+    let mut sheet = RgbImage::new(padding + columns * cell_w, padding + rows * cell_h);
+    for (i, img) in images.iter().enumerate() {
+        let (x0, y0) = (padding + (i as u32 % columns) * cell_w, padding + (i as u32 / columns) * cell_h);
+        for (x, y, p) in img.enumerate_pixels() {
+            sheet.put_pixel(x0 + x, y0 + y, Rgb([p[0]; 3]));
+        }
+        if let Some(labels) = labels {
+            let at = (x0 as i64, (y0 + tile_h + 1) as i64);
+            font::draw_text(&mut sheet, at.0, at.1, &labels[i], tile_w, Rgb([255, 255, 255]));
+        }
+    }
+    Ok(sheet)
+}
+
+/// Place images of equal height next to each other, left to right, separated by white
+/// dividers `divider_px` pixels wide. An empty slice gives an empty image.
+///
+/// Errors with [`FreqError::DimensionMismatch`] if the images differ in height.
+pub fn side_by_side(images: &[&GrayImage], divider_px: u32) -> Result<GrayImage, FreqError> {
+    let height = images.first().map_or(0, |img| img.height());
+    if let Some(other) = images.iter().find(|img| img.height() != height) {
+        return Err(FreqError::DimensionMismatch { expected: (other.width(), height), got: other.dimensions() });
+    }
+    let dividers = divider_px * (images.len() as u32).saturating_sub(1);
+    let width = images.iter().map(|img| img.width()).sum::<u32>() + dividers;
+    let mut out = GrayImage::from_pixel(width, height, Luma([255]));
+    let mut x0 = 0;
+    for img in images {
+        for (x, y, &p) in img.enumerate_pixels() {
+            out.put_pixel(x0 + x, y, p);
+        }
+        x0 += img.width() + divider_px;
+    }
+    Ok(out)
+}
+
+/// Line colors of [`plot_log_log`], one per series in turn.
+const SERIES_COLORS: [Rgb<u8>; 6] = [
+    Rgb([31, 119, 180]),
+    Rgb([214, 39, 40]),
+    Rgb([44, 160, 44]),
+    Rgb([255, 127, 14]),
+    Rgb([148, 103, 189]),
+    Rgb([23, 190, 207]),
+];
+
+/// A `width × height` log-log line chart of each series of `(x, y)` points, on white in a
+/// gray frame, one color per series (repeating after six). Both axes span the points of
+/// all series together. Points where either coordinate isn't positive and finite are
+/// left out, breaking the line there. There are no ticks or labels.
+pub fn plot_log_log(series: &[Vec<(f64, f64)>], width: u32, height: u32) -> RgbImage {
+    const MARGIN: i64 = 8;
+    let mut img = RgbImage::from_pixel(width, height, Rgb([255, 255, 255]));
+    let (right, bottom) = (width as i64 - 1 - MARGIN, height as i64 - 1 - MARGIN);
+    let (l, t, r, b) = (MARGIN / 2, MARGIN / 2, right + MARGIN / 2, bottom + MARGIN / 2);
+    let gray = Rgb([160, 160, 160]);
+    for (from, to) in [((l, t), (r, t)), ((r, t), (r, b)), ((r, b), (l, b)), ((l, b), (l, t))] {
+        draw_line(&mut img, from, to, gray);
+    }
+
+    let usable = |&(x, y): &(f64, f64)| x > 0.0 && y > 0.0 && x.is_finite() && y.is_finite();
+    let (mut lo, mut hi) = ((f64::INFINITY, f64::INFINITY), (f64::NEG_INFINITY, f64::NEG_INFINITY));
+    for &(x, y) in series.iter().flatten().filter(|p| usable(p)) {
+        lo = (lo.0.min(x.log10()), lo.1.min(y.log10()));
+        hi = (hi.0.max(x.log10()), hi.1.max(y.log10()));
+    }
+    // A flat axis has nothing to stretch, so its points go in the middle.
+    let map = |v: f64, lo: f64, hi: f64, from: i64, to: i64| {
+        if hi > lo {
+            from + ((v - lo) / (hi - lo) * (to - from) as f64).round() as i64
+        } else {
+            (from + to) / 2
+        }
+    };
+    let to_pixel = |(x, y): (f64, f64)| (map(x.log10(), lo.0, hi.0, MARGIN, right), map(y.log10(), lo.1, hi.1, bottom, MARGIN));
+    for (points, &color) in series.iter().zip(SERIES_COLORS.iter().cycle()) {
+        let mut previous = None;
+        for &p in points {
+            if !usable(&p) {
+                previous = None;
+                continue;
+            }
+            let here = to_pixel(p);
+            match previous {
+                Some(from) => draw_line(&mut img, from, here, color),
+                None => plot(&mut img, here.0, here.1, color),
+            }
+            previous = Some(here);
+        }
+    }
+    img
+}
+
+/// Write an animated GIF with one frame per entry of `cutoffs`, in the order given, each
+/// showing `fi` (a spatial image) low-passed at that cutoff with
+/// [`FreqImage::low_pass_mask`]. The spectrum is computed once; every frame is shown for
+/// `delay_ms` milliseconds and the animation loops forever.
+///
+/// Errors if `cutoffs` is empty, or if the file can't be written.
+pub fn animate_sweep(fi: &FreqImage, cutoffs: &[f64], smoothing: f64, path: &Path, delay_ms: u16) -> Result<(), FreqError> {
+    if cutoffs.is_empty() {
+        return Err(FreqError::InvalidArgument("a sweep needs at least one cutoff".into()));
+    }
+    let mut ctx = FftContext::new();
+    let mut spectrum = fi.clone();
+    ctx.forward(&mut spectrum);
+    spectrum.fftshift();
+
+    let mut encoder = GifEncoder::new(BufWriter::new(File::create(path)?));
+    encoder.set_repeat(Repeat::Infinite)?;
+    for &cutoff in cutoffs {
+        let mut frame = spectrum.clone();
+        frame.apply_filter(&frame.low_pass_mask(cutoff, smoothing));
+        frame.ifftshift();
+        ctx.inverse(&mut frame);
+        let rgba = DynamicImage::ImageLuma8(frame.to_image()).into_rgba8();
+        encoder.encode_frame(Frame::from_parts(rgba, 0, 0, Delay::from_numer_denom_ms(delay_ms.into(), 1)))?;
+    }
+    Ok(())
+}
+
+#[test]
+fn test_contact_sheet_layout() {
+    let tiles: Vec<GrayImage> = (0..5u8).map(|i| GrayImage::from_fn(6, 4, |x, y| image::Luma([i * 40 + (x + y) as u8]))).collect();
+    let sheet = contact_sheet(&tiles, 3, 2, None).unwrap();
+    assert_eq!(sheet.dimensions(), (2 + 3 * 8, 2 + 2 * 6));
+    // Tile 4 is the second one on the second row.
+    for (x, y, p) in tiles[4].enumerate_pixels() {
+        assert_eq!(sheet.get_pixel(2 + 8 + x, 2 + 6 + y), &Rgb([p[0]; 3]));
+    }
+    // The empty sixth cell stays background.
+    assert_eq!(sheet.get_pixel(2 + 16, 2 + 6), &Rgb([0, 0, 0]));
+
+    let labels: Vec<String> = (0..5).map(|i| format!("c{}", i)).collect();
+    let labelled = contact_sheet(&tiles, 5, 0, Some(&labels)).unwrap();
+    assert_eq!(labelled.dimensions(), (30, 4 + 7));
+    assert!(labelled.enumerate_pixels().any(|(_, y, p)| y > 4 && p == &Rgb([255, 255, 255])));
+}
+
+#[test]
+fn test_contact_sheet_errors() {
+    let tiles = vec![GrayImage::new(4, 4), GrayImage::new(4, 3)];
+    assert!(matches!(
+        contact_sheet(&tiles, 2, 1, None),
+        Err(FreqError::DimensionMismatch { expected: (4, 4), got: (4, 3) })
+    ));
+    let same = vec![GrayImage::new(4, 4); 2];
+    assert!(matches!(contact_sheet(&same, 2, 1, Some(&["one".to_string()])), Err(FreqError::LengthMismatch { .. })));
+    assert!(contact_sheet(&same, 0, 1, None).is_err());
+    assert!(contact_sheet(&[], 2, 1, None).is_err());
+}
+
+#[test]
+fn test_side_by_side() {
+    let a = GrayImage::from_fn(3, 2, |x, y| Luma([(10 + x + 3 * y) as u8]));
+    let b = GrayImage::from_fn(5, 2, |x, y| Luma([(100 + x + 5 * y) as u8]));
+    let out = side_by_side(&[&a, &b, &a], 2).unwrap();
+    assert_eq!(out.dimensions(), (3 + 2 + 5 + 2 + 3, 2));
+    for y in 0..2 {
+        for x in 0..3 {
+            assert_eq!(out.get_pixel(x, y), a.get_pixel(x, y));
+            assert_eq!(out.get_pixel(12 + x, y), a.get_pixel(x, y));
+        }
+        for x in 0..5 {
+            assert_eq!(out.get_pixel(5 + x, y), b.get_pixel(x, y));
+        }
+        for x in [3, 4, 10, 11] {
+            assert_eq!(out.get_pixel(x, y)[0], 255);
+        }
+    }
+    assert_eq!(side_by_side(&[&a], 4).unwrap(), a);
+    assert_eq!(side_by_side(&[], 4).unwrap().dimensions(), (0, 0));
+    assert!(matches!(
+        side_by_side(&[&GrayImage::new(2, 2), &GrayImage::new(4, 3)], 1),
+        Err(FreqError::DimensionMismatch { expected: (4, 2), got: (4, 3) })
+    ));
+}
+
+#[test]
+fn test_plot_log_log() {
+    let falling = vec![(0.01, 100.0), (0.1, 10.0), (0.0, 5.0), (1.0, 1.0)];
+    let flat = vec![(0.1, 10.0), (1.0, 10.0)];
+    let img = plot_log_log(&[falling, flat], 41, 31);
+    // The extremes land on the corners of the plotting area, 8 pixels in.
+    assert_eq!(*img.get_pixel(8, 8), SERIES_COLORS[0]);
+    assert_eq!(*img.get_pixel(32, 22), SERIES_COLORS[0]);
+    assert_eq!(*img.get_pixel(24, 15), SERIES_COLORS[1]);
+    assert_eq!(*img.get_pixel(4, 4), Rgb([160, 160, 160]));
+    assert_eq!(*img.get_pixel(1, 1), Rgb([255, 255, 255]));
+    assert_eq!(*img.get_pixel(10, 22), Rgb([255, 255, 255]));
+
+    let empty = plot_log_log(&[vec![(0.0, 1.0)]], 20, 20);
+    assert!(empty.pixels().all(|p| *p != SERIES_COLORS[0]));
+}
+
+#[test]
+fn test_annotate_ring() {
+    let green = Rgb([0, 255, 0]);
+    let base = GrayImage::new(60, 80);
+    // The diagonal is 100 pixels, so the ring has radius 20 around (30, 40).
+    let img = annotate_spectrum(&base, &[Annotation::Ring { radius_frac: 0.2, color: green }]);
+    for (x, y) in [(50, 40), (10, 40), (30, 20), (30, 60), (44, 54)] {
+        assert_eq!(img.get_pixel(x, y), &green, "({}, {})", x, y);
+    }
+    assert_eq!(img.get_pixel(30, 40), &Rgb([0, 0, 0]));
+    for (x, y, _) in img.enumerate_pixels().filter(|(_, _, p)| **p == green) {
+        let r = (x as f64 - 30.0).hypot(y as f64 - 40.0);
+        assert!((r - 20.0).abs() < 1.0, "({}, {}) is {} from the center", x, y, r);
+    }
+
+    let marked = annotate_spectrum(&base, &[Annotation::CenterCross, Annotation::Point { u: -10.0, v: 5.0, color: green }]);
+    assert_eq!(marked.get_pixel(30, 40), &Rgb([255, 0, 0]));
+    assert_eq!(marked.get_pixel(20, 45), &green);
+}
+
+#[test]
+fn test_view_with_mask_ring() {
+    // A 200x150 image has a 250 pixel diagonal, so the cutoff ring has radius 25.
+    let fi = FreqImage { width: 200, height: 150, data: vec![Default::default(); 200 * 150] };
+    let img = fi.view_with_mask(&fi.low_pass_mask(0.1, 0.0), 0.5);
+    let radii: Vec<f64> = img
+        .enumerate_pixels()
+        .filter(|(_, _, p)| **p == Rgb([255, 0, 0]))
+        .map(|(x, y, _)| (x as f64 - 99.5).hypot(y as f64 - 74.5))
+        .collect();
+    assert!(radii.len() > 100);
+    let mean = radii.iter().sum::<f64>() / radii.len() as f64;
+    assert!((mean - 25.0).abs() < 1.0, "mean radius {}", mean);
+    assert!(radii.iter().all(|r| (r - 25.0).abs() < 1.5));
+}
+
+#[test]
+#[should_panic(expected = "mask has 3 weights")]
+fn test_view_with_mask_length() {
+    super::ramp(4, 4).view_with_mask(&[0.0; 3], 0.5);
+}
+
+#[test]
+fn test_animate_sweep() {
+    use image::AnimationDecoder;
+
+    let fi = super::synth::zone_plate(48, 32);
+    let path = std::env::temp_dir().join(format!("freqshow_sweep_{}.gif", std::process::id()));
+    animate_sweep(&fi, &[0.05, 0.1, 0.2, 0.4], 0.01, &path, 250).unwrap();
+    let decoder = image::codecs::gif::GifDecoder::new(File::open(&path).unwrap()).unwrap();
+    let frames = decoder.into_frames().collect_frames().unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(frames.len(), 4);
+    assert!(frames.iter().all(|f| f.buffer().dimensions() == (48, 32)));
+
+    assert!(matches!(animate_sweep(&fi, &[], 0.01, &path, 250), Err(FreqError::InvalidArgument(_))));
+    assert!(!path.exists());
+}
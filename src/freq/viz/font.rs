@@ -0,0 +1,99 @@
+//! A 3×5 pixel bitmap font for labels, so drawing text needs no font files.
+
+use image::{Rgb, RgbImage};
+
+/// Glyph height in pixels; every glyph is three pixels wide plus one of spacing.
+pub(super) const GLYPH_HEIGHT: u32 = 5;
+const ADVANCE: u32 = 4;
+
+/// Five rows of three bits each, top row first and the leftmost pixel in the high bit.
+fn glyph(c: char) -> [u8; 5] {
+    match c.to_ascii_uppercase() {
+        '0' => [0b111, 0b101, 0b101, 0b101, 0b111],
+        '1' => [0b010, 0b110, 0b010, 0b010, 0b111],
+        '2' => [0b111, 0b001, 0b111, 0b100, 0b111],
+        '3' => [0b111, 0b001, 0b111, 0b001, 0b111],
+        '4' => [0b101, 0b101, 0b111, 0b001, 0b001],
+        '5' => [0b111, 0b100, 0b111, 0b001, 0b111],
+        '6' => [0b111, 0b100, 0b111, 0b101, 0b111],
+        '7' => [0b111, 0b001, 0b001, 0b001, 0b001],
+        '8' => [0b111, 0b101, 0b111, 0b101, 0b111],
+        '9' => [0b111, 0b101, 0b111, 0b001, 0b111],
+        'A' => [0b010, 0b101, 0b111, 0b101, 0b101],
+        'B' => [0b110, 0b101, 0b110, 0b101, 0b110],
+        'C' => [0b011, 0b100, 0b100, 0b100, 0b011],
+        'D' => [0b110, 0b101, 0b101, 0b101, 0b110],
+        'E' => [0b111, 0b100, 0b110, 0b100, 0b111],
+        'F' => [0b111, 0b100, 0b110, 0b100, 0b100],
+        'G' => [0b011, 0b100, 0b101, 0b101, 0b011],
+        'H' => [0b101, 0b101, 0b111, 0b101, 0b101],
+        'I' => [0b111, 0b010, 0b010, 0b010, 0b111],
+        'J' => [0b001, 0b001, 0b001, 0b101, 0b010],
+        'K' => [0b101, 0b101, 0b110, 0b101, 0b101],
+        'L' => [0b100, 0b100, 0b100, 0b100, 0b111],
+        'M' => [0b101, 0b111, 0b111, 0b101, 0b101],
+        'N' => [0b110, 0b101, 0b101, 0b101, 0b101],
+        'O' => [0b010, 0b101, 0b101, 0b101, 0b010],
+        'P' => [0b110, 0b101, 0b110, 0b100, 0b100],
+        'Q' => [0b010, 0b101, 0b101, 0b111, 0b011],
+        'R' => [0b110, 0b101, 0b110, 0b101, 0b101],
+        'S' => [0b011, 0b100, 0b010, 0b001, 0b110],
+        'T' => [0b111, 0b010, 0b010, 0b010, 0b010],
+        'U' => [0b101, 0b101, 0b101, 0b101, 0b111],
+        'V' => [0b101, 0b101, 0b101, 0b101, 0b010],
+        'W' => [0b101, 0b101, 0b111, 0b111, 0b101],
+        'X' => [0b101, 0b101, 0b010, 0b101, 0b101],
+        'Y' => [0b101, 0b101, 0b010, 0b010, 0b010],
+        'Z' => [0b111, 0b001, 0b010, 0b100, 0b111],
+        '.' => [0b000, 0b000, 0b000, 0b000, 0b010],
+        ',' => [0b000, 0b000, 0b000, 0b010, 0b100],
+        ':' => [0b000, 0b010, 0b000, 0b010, 0b000],
+        '-' => [0b000, 0b000, 0b111, 0b000, 0b000],
+        '=' => [0b000, 0b111, 0b000, 0b111, 0b000],
+        '_' => [0b000, 0b000, 0b000, 0b000, 0b111],
+        '/' => [0b001, 0b001, 0b010, 0b100, 0b100],
+        '%' => [0b101, 0b001, 0b010, 0b100, 0b101],
+        '(' => [0b001, 0b010, 0b010, 0b010, 0b001],
+        ')' => [0b100, 0b010, 0b010, 0b010, 0b100],
+        ' ' => [0; 5],
+        _ => [0b111, 0b001, 0b010, 0b000, 0b010],
+    }
+}
+
+/// Draw `text` with its top-left corner at `(x, y)`, clipped to `max_width` pixels and to
+/// the image. Letters are drawn in upper case; unknown characters as `?`.
+pub(super) fn draw_text(img: &mut RgbImage, x: i64, y: i64, text: &str, max_width: u32, color: Rgb<u8>) {
+    for (i, c) in text.chars().enumerate() {
+        let left = i as u32 * ADVANCE;
+        if left + 3 > max_width {
+            break;
+        }
+        for (row, bits) in glyph(c).iter().enumerate() {
+            for col in 0..3 {
+                if bits >> (2 - col) & 1 == 1 {
+                    let (px, py) = (x + (left + col) as i64, y + row as i64);
+                    if (0..img.width() as i64).contains(&px) && (0..img.height() as i64).contains(&py) {
+                        img.put_pixel(px as u32, py as u32, color);
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn test_draw_text() {
+    let mut img = RgbImage::new(12, 6);
+    let white = Rgb([255, 255, 255]);
+    draw_text(&mut img, 0, 0, "1a?", 12, white);
+    // The middle column of the "1" is fully lit.
+    assert!((0..5).all(|y| img.get_pixel(1, y) == &white));
+    // "A" starts with a single pixel at the top middle.
+    assert_eq!(img.get_pixel(4, 0), &Rgb([0, 0, 0]));
+    assert_eq!(img.get_pixel(5, 0), &white);
+
+    // Glyphs that would overflow max_width are dropped whole.
+    let mut clipped = RgbImage::new(12, 6);
+    draw_text(&mut clipped, 0, 0, "88", 6, white);
+    assert!(clipped.enumerate_pixels().all(|(x, _, p)| x < 3 || p == &Rgb([0, 0, 0])));
+}
@@ -0,0 +1,184 @@
+//! Spread-spectrum watermarking in the mid-frequency band.
+
+use super::rng::SplitMix64;
+use super::FreqImage;
+
+/// Watermark band, as fractions of the image diagonal (see [`FreqImage::low_pass_mask`]).
+const BAND: (f64, f64) = (0.05, 0.15);
+
+/// One coefficient pair carrying a chip of a payload bit.
+struct Chip {
+    index: usize,
+    partner: usize,
+    bit: usize,
+    sign: f64,
+}
+
+impl FreqImage {
+    /// Embed `payload` into a spatial-domain image. Each bit is spread over many
+    /// conjugate-symmetric mid-band coefficient pairs, chosen and signed pseudo-randomly
+    /// from `seed`, whose magnitudes are scaled by `1 ± strength`. The image stays real.
+    ///
+    /// # Panics
+    /// Panics unless `0 < strength < 1`.
+    pub fn embed_watermark(&mut self, payload: &[u8], strength: f64, seed: u64) {
+        assert!(strength > 0.0 && strength < 1.0, "watermark strength must be in (0, 1)");
+        self.fft_forward();
+        for chip in self.watermark_chips(payload.len() * 8, seed) {
+            let factor = 1.0 + strength * chip.sign * bit_sign(payload, chip.bit);
+            self.data[chip.index] *= factor;
+            self.data[chip.partner] *= factor;
+        }
+        self.fft_inverse();
+    }
+
+    /// Recover a `payload_len`-byte watermark embedded with `seed`, without the original.
+    pub fn detect_watermark(&self, payload_len: usize, seed: u64) -> Vec<u8> {
+        let sums = self.watermark_correlations(payload_len * 8, seed);
+        let mut payload = vec![0u8; payload_len];
+        for (bit, sum) in sums.iter().enumerate() {
+            if *sum > 0.0 {
+                payload[bit / 8] |= 0x80 >> (bit % 8);
+            }
+        }
+        payload
+    }
+
+    /// Normalized correlation in `[-1, 1]` between this image and the watermark pattern
+    /// for `payload` and `seed`: near zero when absent, clearly positive when present.
+    pub fn watermark_score(&self, payload: &[u8], seed: u64) -> f64 {
+        let deviations = self.watermark_deviations(payload.len() * 8, seed);
+        let chips = deviations.len() as f64;
+        let (mut dot, mut norm) = (0.0, 0.0);
+        for (chip, d) in deviations {
+            dot += chip.sign * bit_sign(payload, chip.bit) * d;
+            norm += d * d;
+        }
+        if norm > 0.0 {
+            dot / (norm * chips).sqrt()
+        } else {
+            0.0
+        }
+    }
+
+    /// Per-bit sums of chip-signed log-magnitude deviations.
+    fn watermark_correlations(&self, bits: usize, seed: u64) -> Vec<f64> {
+        let deviations = self.watermark_deviations(bits, seed);
+        let mut sums = vec![0.0; bits];
+        for (chip, d) in deviations {
+            sums[chip.bit] += chip.sign * d;
+        }
+        sums
+    }
+
+    /// Each chip's log-magnitude minus the mean log-magnitude of its 8 neighbours, which
+    /// cancels most of the host image's own spectrum. Deviations are clipped to ±1 so the
+    /// deep nulls of sparse spectra don't swamp the correlation.
+    fn watermark_deviations(&self, bits: usize, seed: u64) -> Vec<(Chip, f64)> {
+        let mut spectrum = self.clone();
+        spectrum.fft_forward();
+        let log_mag: Vec<f64> = spectrum.data.iter().map(|c| (c.norm() + 1e-12).ln()).collect();
+        let (w, h) = (self.width as usize, self.height as usize);
+        self.watermark_chips(bits, seed)
+            .into_iter()
+            .map(|chip| {
+                let (x, y) = (chip.index % w, chip.index / w);
+                let mut neighbours = 0.0;
+                for (dx, dy) in [(w - 1, h - 1), (0, h - 1), (1, h - 1), (w - 1, 0), (1, 0), (w - 1, 1), (0, 1), (1, 1)] {
+                    neighbours += log_mag[((y + dy) % h) * w + (x + dx) % w];
+                }
+                let d = (log_mag[chip.index] - neighbours / 8.0).clamp(-1.0, 1.0);
+                (chip, d)
+            })
+            .collect()
+    }
+
+    /// Shuffle the mid-band half-plane bins with `seed` and deal them round-robin to the
+    /// payload bits, each with a pseudo-random sign. Nyquist rows and columns are skipped
+    /// since they are their own conjugate partners.
+    fn watermark_chips(&self, bits: usize, seed: u64) -> Vec<Chip> {
+        if bits == 0 {
+            return Vec::new();
+        }
+        let (w, h) = (self.width as i64, self.height as i64);
+        let diagonal = (w as f64).hypot(h as f64);
+        let mut bins = Vec::new();
+        for ky in 0..h {
+            for kx in 0..w {
+                let fx = if 2 * kx <= w { kx } else { kx - w };
+                let fy = if 2 * ky <= h { ky } else { ky - h };
+                let upper = fy > 0 || (fy == 0 && fx > 0);
+                let nyquist = 2 * fx.abs() == w || 2 * fy.abs() == h;
+                let r = (fx as f64).hypot(fy as f64) / diagonal;
+                if upper && !nyquist && r >= BAND.0 && r < BAND.1 {
+                    let partner = (-fy).rem_euclid(h) * w + (-fx).rem_euclid(w);
+                    bins.push(((ky * w + kx) as usize, partner as usize));
+                }
+            }
+        }
+        let mut rng = SplitMix64(seed);
+        for i in (1..bins.len()).rev() {
+            bins.swap(i, (rng.next_u64() % (i as u64 + 1)) as usize);
+        }
+        bins.into_iter()
+            .enumerate()
+            .map(|(i, (index, partner))| Chip {
+                index,
+                partner,
+                bit: i % bits,
+                sign: if rng.next_u64() & 1 == 0 { 1.0 } else { -1.0 },
+            })
+            .collect()
+    }
+}
+
+/// `+1` for a set bit of `payload` (most significant bit first), `-1` otherwise.
+fn bit_sign(payload: &[u8], bit: usize) -> f64 {
+    if payload[bit / 8] & (0x80 >> (bit % 8)) != 0 {
+        1.0
+    } else {
+        -1.0
+    }
+}
+
+#[cfg(test)]
+fn psnr(a: &FreqImage, b: &FreqImage) -> f64 {
+    let mse = a.data.iter().zip(&b.data).map(|(x, y)| (x - y).norm_sqr()).sum::<f64>() / a.data.len() as f64;
+    10.0 * (1.0 / mse).log10()
+}
+
+#[test]
+fn test_watermark_round_trip() {
+    let original = FreqImage::open("img/mandrill.jpg").unwrap();
+    let payload = b"fqsh";
+    let mut marked = original.clone();
+    marked.embed_watermark(payload, 0.08, 42);
+    let p = psnr(&original, &marked);
+    assert!(p > 40.0, "PSNR {}", p);
+    assert!(marked.data.iter().all(|c| c.im.abs() < 1e-9));
+    assert_eq!(marked.detect_watermark(payload.len(), 42), payload);
+
+    let score = marked.watermark_score(payload, 42);
+    let clean = original.watermark_score(payload, 42);
+    assert!(score > 0.1 && clean.abs() < score / 3.0, "{} vs {}", score, clean);
+    assert!(marked.watermark_score(payload, 7).abs() < score / 3.0);
+}
+
+#[test]
+fn test_watermark_survives_quantization_and_low_pass() {
+    let payload = b"fqsh";
+    let mut marked = FreqImage::open("img/mandrill.jpg").unwrap();
+    marked.embed_watermark(payload, 0.08, 42);
+
+    let quantized = FreqImage::from_image(image::DynamicImage::ImageLuma8(marked.to_image()));
+    assert_eq!(quantized.detect_watermark(payload.len(), 42), payload);
+
+    let mut blurred = marked.clone();
+    let mask = blurred.low_pass_mask(0.3, 0.05);
+    blurred.fft_forward();
+    blurred.fftshift();
+    blurred.apply_filter(&mask);
+    blurred.ifftshift();
+    blurred.fft_inverse();
+    assert_eq!(blurred.detect_watermark(payload.len(), 42), payload);
+}
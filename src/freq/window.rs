@@ -0,0 +1,64 @@
+//! Tapering windows that suppress the edge discontinuities the FFT sees as a cross.
+
+use std::f64::consts::PI;
+
+use super::FreqImage;
+
+/// A separable window, applied along both axes.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum WindowKind {
+    /// No tapering.
+    Rectangular,
+    /// `0.5 - 0.5·cos(2πi/n)`: falls to zero at the edges.
+    #[default]
+    Hann,
+    /// `0.54 - 0.46·cos(2πi/n)`: lower first sidelobe than Hann, but nonzero at the edges.
+    Hamming,
+    /// Three-term Blackman: the widest main lobe and lowest sidelobes of these.
+    Blackman,
+}
+
+impl WindowKind {
+    /// The periodic window value at sample `i` of `n`.
+    pub(crate) fn weight(self, i: f64, n: f64) -> f64 {
+        let t = 2.0 * PI * i / n;
+        match self {
+            WindowKind::Rectangular => 1.0,
+            WindowKind::Hann => 0.5 - 0.5 * t.cos(),
+            WindowKind::Hamming => 0.54 - 0.46 * t.cos(),
+            WindowKind::Blackman => 0.42 - 0.5 * t.cos() + 0.08 * (2.0 * t).cos(),
+        }
+    }
+}
+
+impl FreqImage {
+    /// Multiply this spatial-domain image by `kind` along x and y.
+    pub fn apply_window(&mut self, kind: WindowKind) {
+        let (w, h) = (self.width as f64, self.height as f64);
+        let columns: Vec<f64> = (0..self.width).map(|x| kind.weight(x as f64, w)).collect();
+        for (y, row) in self.rows_mut().enumerate() {
+            let wy = kind.weight(y as f64, h);
+            for (c, wx) in row.iter_mut().zip(&columns) {
+                *c *= wx * wy;
+            }
+        }
+    }
+}
+
+#[test]
+fn test_apply_window() {
+    let mut fi = super::ramp(8, 4);
+    fi.apply_window(WindowKind::Rectangular);
+    assert_eq!(fi, super::ramp(8, 4));
+
+    fi.apply_window(WindowKind::Hann);
+    assert!(fi.rows().next().unwrap().iter().all(|c| c.norm() == 0.0));
+    // Hann peaks at one in the middle sample.
+    assert!((fi[(4, 2)] - super::ramp(8, 4)[(4, 2)]).norm() < 1e-12);
+
+    for kind in [WindowKind::Hamming, WindowKind::Blackman] {
+        assert!((kind.weight(4.0, 8.0) - 1.0).abs() < 1e-12);
+        assert!(kind.weight(0.0, 8.0) < kind.weight(1.0, 8.0));
+    }
+}
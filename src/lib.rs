@@ -2,4 +2,12 @@
 
 // default implementation on mutable slices
 pub mod freq;
+mod error;
+#[cfg(feature = "capi")]
+pub mod ffi;
+#[cfg(feature = "python")]
+mod python;
+
+pub use error::FreqError;
+pub use freq::FreqImage;
 
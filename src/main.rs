@@ -0,0 +1,109 @@
+//! The `freqshow` command-line tool; run it without arguments for usage.
+
+mod cli;
+
+use std::process::ExitCode;
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.iter().any(|arg| arg == "-h" || arg == "--help") {
+        println!("{}", cli::USAGE);
+        return ExitCode::SUCCESS;
+    }
+    match args.first().map(String::as_str) {
+        Some("batch") => return batch(&args[1..]),
+        Some("hybrid") => return hybrid(&args[1..]),
+        Some("profile") => return profile(&args[1..]),
+        Some("register") => return register(&args[1..]),
+        _ => {}
+    }
+    let command = match cli::parse_args(&args) {
+        Ok(command) => command,
+        Err(err) => return usage_error(err),
+    };
+    match command.run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("freqshow: {}: {}", command.input.display(), err);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn batch(args: &[String]) -> ExitCode {
+    let batch = match cli::batch::parse_batch_args(args) {
+        Ok(batch) => batch,
+        Err(err) => return usage_error(err),
+    };
+    let summary = match batch.run(&mut cli::progress_bar()) {
+        Ok(summary) => summary,
+        Err(err) => {
+            eprintln!("freqshow: {}: {}", batch.input_dir.display(), err);
+            return ExitCode::FAILURE;
+        }
+    };
+    for (path, err) in &summary.skipped {
+        eprintln!("skipped {}: {}", path.display(), err);
+    }
+    println!(
+        "processed {}, skipped {} in {:.1}s",
+        summary.processed,
+        summary.skipped.len(),
+        summary.elapsed.as_secs_f64()
+    );
+    if summary.skipped.is_empty() {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+fn hybrid(args: &[String]) -> ExitCode {
+    let hybrid = match cli::hybrid::parse_hybrid_args(args) {
+        Ok(hybrid) => hybrid,
+        Err(err) => return usage_error(err),
+    };
+    match hybrid.run() {
+        Ok(_) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("freqshow: {}: {}", hybrid.high_source.display(), err);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn profile(args: &[String]) -> ExitCode {
+    let profile = match cli::profile::parse_profile_args(args) {
+        Ok(profile) => profile,
+        Err(err) => return usage_error(err),
+    };
+    match profile.run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("freqshow: profile: {}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn register(args: &[String]) -> ExitCode {
+    let register = match cli::register::parse_register_args(args) {
+        Ok(register) => register,
+        Err(err) => return usage_error(err),
+    };
+    match register.run() {
+        Ok(reg) => {
+            println!("{}", register.report(&reg));
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            eprintln!("freqshow: {}: {}", register.moving.display(), err);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn usage_error(err: freqshow::FreqError) -> ExitCode {
+    eprintln!("freqshow: {}\n\n{}", err, cli::USAGE);
+    ExitCode::from(2)
+}
@@ -0,0 +1,153 @@
+//! Python bindings, built as the `pyfreqshow` extension module with maturin
+//! (`maturin develop --release`, see `pyproject.toml`).
+//!
+//! Images cross the boundary as 2-D numpy arrays of shape `(height, width)`. Inputs may
+//! be any layout numpy allows and are copied into the image; outputs are built from
+//! freshly computed buffers that numpy takes over without another copy. Bad shapes
+//! raise `ValueError` rather than panicking.
+
+use numpy::{Complex64, Element, IntoPyArray, PyArray2, PyArrayDyn};
+use pyo3::exceptions::{PyTypeError, PyValueError};
+use pyo3::prelude::*;
+
+use crate::FreqImage;
+
+/// A grayscale image or spectrum, wrapping [`FreqImage`].
+#[pyclass(name = "FreqImage")]
+pub struct PyFreqImage {
+    inner: FreqImage,
+}
+
+#[pymethods]
+impl PyFreqImage {
+    /// Build an image from a 2-D float64 or complex128 array of shape `(height, width)`.
+    #[staticmethod]
+    fn from_numpy(array: &PyAny) -> PyResult<Self> {
+        let inner = if let Ok(real) = array.downcast::<PyArrayDyn<f64>>() {
+            let values = real.readonly();
+            image_from_values(real.shape(), values.as_array().iter().map(|&v| Complex64::new(v, 0.0)))
+        } else if let Ok(complex) = array.downcast::<PyArrayDyn<Complex64>>() {
+            let values = complex.readonly();
+            image_from_values(complex.shape(), values.as_array().iter().copied())
+        } else {
+            return Err(PyTypeError::new_err("expected a float64 or complex128 numpy array"));
+        };
+        Ok(PyFreqImage { inner: inner.map_err(PyValueError::new_err)? })
+    }
+
+    /// The coefficients as a complex128 array of shape `(height, width)`.
+    fn to_numpy<'py>(&self, py: Python<'py>) -> PyResult<&'py PyArray2<Complex64>> {
+        to_array(py, &self.inner, self.inner.data.clone())
+    }
+
+    /// Image width in pixels.
+    #[getter]
+    fn width(&self) -> u32 {
+        self.inner.width
+    }
+
+    /// Image height in pixels.
+    #[getter]
+    fn height(&self) -> u32 {
+        self.inner.height
+    }
+
+    /// Forward 2-D FFT in place.
+    fn fft_forward(&mut self) {
+        self.inner.fft_forward();
+    }
+
+    /// Inverse 2-D FFT in place, scaled by `1/N`.
+    fn fft_inverse(&mut self) {
+        self.inner.fft_inverse();
+    }
+
+    /// Move the DC term to the center.
+    fn fftshift(&mut self) {
+        self.inner.fftshift();
+    }
+
+    /// Undo `fftshift`.
+    fn ifftshift(&mut self) {
+        self.inner.ifftshift();
+    }
+
+    /// A float64 low-pass mask of shape `(height, width)` for the centered spectrum.
+    fn low_pass_mask<'py>(&self, py: Python<'py>, cutoff: f64, smoothing: f64) -> PyResult<&'py PyArray2<f64>> {
+        to_array(py, &self.inner, self.inner.low_pass_mask(cutoff, smoothing))
+    }
+
+    /// Multiply every coefficient by the matching weight of a float64 mask of shape
+    /// `(height, width)`.
+    fn apply_filter(&mut self, mask: &PyAny) -> PyResult<()> {
+        let mask = mask
+            .downcast::<PyArrayDyn<f64>>()
+            .map_err(|_| PyTypeError::new_err("expected a float64 numpy array"))?;
+        check_shape_matches(mask.shape(), &self.inner).map_err(PyValueError::new_err)?;
+        let weights: Vec<f64> = mask.readonly().as_array().iter().copied().collect();
+        self.inner.apply_filter(&weights);
+        Ok(())
+    }
+
+    /// The log-magnitude view as a uint8 array of shape `(height, width)`.
+    fn view_fft_norm<'py>(&self, py: Python<'py>) -> PyResult<&'py PyArray2<u8>> {
+        to_array(py, &self.inner, self.inner.view_fft_norm().into_raw())
+    }
+}
+
+/// The `pyfreqshow` extension module.
+#[pymodule]
+fn pyfreqshow(_py: Python<'_>, m: &PyModule) -> PyResult<()> {
+    m.add_class::<PyFreqImage>()?;
+    Ok(())
+}
+
+/// Hand `values`, one per pixel of `fi` in row-major order, to numpy as a 2-D array.
+fn to_array<'py, T: Element>(py: Python<'py>, fi: &FreqImage, values: Vec<T>) -> PyResult<&'py PyArray2<T>> {
+    values.into_pyarray(py).reshape([fi.height as usize, fi.width as usize])
+}
+
+/// `(width, height)` for a numpy shape `(height, width)`, or why it can't be an image.
+fn image_dimensions(shape: &[usize]) -> Result<(u32, u32), String> {
+    let [height, width] = shape else {
+        return Err(format!("expected a 2-D array, got {} dimensions", shape.len()));
+    };
+    if *width == 0 || *height == 0 {
+        return Err(format!("image dimensions must be non-zero, got shape ({}, {})", height, width));
+    }
+    match (u32::try_from(*width), u32::try_from(*height)) {
+        (Ok(w), Ok(h)) => Ok((w, h)),
+        _ => Err(format!("shape ({}, {}) is too large for an image", height, width)),
+    }
+}
+
+/// An image of the given numpy shape from its values in logical row-major order.
+fn image_from_values(shape: &[usize], values: impl Iterator<Item = Complex64>) -> Result<FreqImage, String> {
+    let (width, height) = image_dimensions(shape)?;
+    Ok(FreqImage { width, height, data: values.collect() })
+}
+
+fn check_shape_matches(shape: &[usize], fi: &FreqImage) -> Result<(), String> {
+    if shape != [fi.height as usize, fi.width as usize] {
+        return Err(format!("mask shape {:?} does not match image shape ({}, {})", shape, fi.height, fi.width));
+    }
+    Ok(())
+}
+
+#[test]
+fn test_conversion_checks() {
+    assert_eq!(image_dimensions(&[3, 5]), Ok((5, 3)));
+    assert!(image_dimensions(&[15]).unwrap_err().contains("2-D"));
+    assert!(image_dimensions(&[3, 5, 1]).is_err());
+    assert!(image_dimensions(&[0, 5]).unwrap_err().contains("non-zero"));
+    assert!(image_dimensions(&[1, u32::MAX as usize + 1]).is_err());
+
+    let values = (0..6).map(|i| Complex64::new(i as f64, 0.0));
+    let fi = image_from_values(&[2, 3], values).unwrap();
+    assert_eq!((fi.width, fi.height), (3, 2));
+    assert_eq!(fi[(2, 1)], Complex64::new(5.0, 0.0));
+
+    assert!(check_shape_matches(&[2, 3], &fi).is_ok());
+    assert!(check_shape_matches(&[3, 2], &fi).is_err());
+    assert!(check_shape_matches(&[6], &fi).is_err());
+}
@@ -0,0 +1,68 @@
+//! Filter and spectrum outputs on the bundled images, compared with reference PNGs in
+//! `tests/golden/`. Run with `UPDATE_GOLDEN=1` to regenerate them after an intended change.
+
+use std::path::PathBuf;
+
+use freqshow::freq::pipeline::{FilterPipeline, MaskSpec};
+use freqshow::freq::testing::{assert_golden, hash_spectrum};
+use freqshow::freq::FftContext;
+use freqshow::FreqImage;
+use image::GrayImage;
+
+const IMAGES: [&str; 2] = ["mandrill.jpg", "sjb-aerial.png"];
+
+/// Allow one gray level of round-off on a handful of pixels.
+const MAX_ABS_DIFF: u8 = 1;
+const MAX_FRACTION_DIFFERING: f64 = 0.001;
+
+fn check(operation: &str, render: impl Fn(&FreqImage) -> GrayImage) {
+    let root = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    for name in IMAGES {
+        let fi = FreqImage::open(root.join("img").join(name)).unwrap();
+        let stem = name.split('.').next().unwrap();
+        let golden = root.join("tests/golden").join(format!("{}_{}.png", operation, stem));
+        assert_golden(golden, &render(&fi), MAX_ABS_DIFF, MAX_FRACTION_DIFFERING);
+    }
+}
+
+fn filtered(spec: MaskSpec) -> impl Fn(&FreqImage) -> GrayImage {
+    move |fi| FilterPipeline::new().mask(spec).run(fi).unwrap().to_image()
+}
+
+#[test]
+fn golden_lowpass() {
+    check("lowpass", filtered(MaskSpec::LowPass { cutoff: 0.15, smoothing: 0.05 }));
+}
+
+#[test]
+fn golden_highpass() {
+    check("highpass", filtered(MaskSpec::HighPass { cutoff: 0.05, smoothing: 0.02 }));
+}
+
+#[test]
+fn golden_bandpass() {
+    check("bandpass", filtered(MaskSpec::BandPass { low: 0.05, high: 0.2, smoothing: 0.02 }));
+}
+
+#[test]
+fn golden_spectrum() {
+    check("spectrum", |fi| {
+        let mut spectrum = fi.clone();
+        spectrum.fft_forward();
+        spectrum.fftshift();
+        spectrum.view_fft_norm()
+    });
+}
+
+#[test]
+fn spectrum_hash_is_deterministic() {
+    let fi = FreqImage::open(concat!(env!("CARGO_MANIFEST_DIR"), "/img/mandrill.jpg")).unwrap();
+    let mut one_shot = fi.clone();
+    one_shot.fft_forward();
+    let mut ctx = FftContext::new();
+    for _ in 0..2 {
+        let mut reused = fi.clone();
+        ctx.forward(&mut reused);
+        assert_eq!(hash_spectrum(&reused), hash_spectrum(&one_shot));
+    }
+}
@@ -0,0 +1,104 @@
+//! Mathematical invariants of the FFT checked on random images of random sizes,
+//! including odd and prime ones.
+
+use freqshow::FreqImage;
+use proptest::prelude::*;
+use rustfft::num_complex::Complex;
+
+const PRIMES: [u32; 18] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53, 59, 61];
+
+/// A side length in `1..=64`, with primes drawn more often than chance.
+fn side() -> impl Strategy<Value = u32> {
+    prop_oneof![1u32..=64, prop::sample::select(PRIMES.to_vec())]
+}
+
+fn buffer(width: u32, height: u32, imaginary: bool) -> impl Strategy<Value = FreqImage> {
+    prop::collection::vec((-1.0..1.0, -1.0..1.0), (width * height) as usize).prop_map(move |values| FreqImage {
+        width,
+        height,
+        data: values.into_iter().map(|(re, im)| Complex::new(re, if imaginary { im } else { 0.0 })).collect(),
+    })
+}
+
+/// A complex image with parts in `[-1, 1)`.
+fn complex_image() -> impl Strategy<Value = FreqImage> {
+    (side(), side()).prop_flat_map(|(w, h)| buffer(w, h, true))
+}
+
+/// Two complex images of the same random size.
+fn complex_pair() -> impl Strategy<Value = (FreqImage, FreqImage)> {
+    (side(), side()).prop_flat_map(|(w, h)| (buffer(w, h, true), buffer(w, h, true)))
+}
+
+/// A real image with values in `[-1, 1)`.
+fn real_image() -> impl Strategy<Value = FreqImage> {
+    (side(), side()).prop_flat_map(|(w, h)| buffer(w, h, false))
+}
+
+fn spectrum(fi: &FreqImage) -> FreqImage {
+    let mut out = fi.clone();
+    out.fft_forward();
+    out
+}
+
+/// Round-off allowance for a transform of `fi`: its coefficients are bounded by the sum
+/// of the input magnitudes.
+fn tolerance(fi: &FreqImage) -> f64 {
+    1e-9 * (1.0 + fi.data.iter().map(|c| c.norm()).sum::<f64>())
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(128))]
+
+    #[test]
+    fn forward_then_inverse_is_identity(fi in complex_image()) {
+        let mut round_trip = spectrum(&fi);
+        round_trip.fft_inverse();
+        prop_assert!(round_trip.approx_eq(&fi, 1e-9), "{}x{}", fi.width, fi.height);
+    }
+
+    #[test]
+    fn fft_is_linear((x, y) in complex_pair(), a in -10.0..10.0, b in -10.0..10.0) {
+        let mut combined = x.clone();
+        combined.scale(a);
+        let mut scaled_y = y.clone();
+        scaled_y.scale(b);
+        combined.add_elementwise(&scaled_y).unwrap();
+
+        let mut expected = spectrum(&x);
+        expected.scale(a);
+        let mut fy = spectrum(&y);
+        fy.scale(b);
+        expected.add_elementwise(&fy).unwrap();
+
+        let tol = tolerance(&x) * a.abs() + tolerance(&y) * b.abs();
+        prop_assert!(spectrum(&combined).approx_eq(&expected, tol), "{}x{}", x.width, x.height);
+    }
+
+    #[test]
+    fn parseval(fi in complex_image()) {
+        // The forward transform is unnormalized, so energy grows by the pixel count.
+        let n = fi.data.len() as f64;
+        let (spatial, spectral) = (fi.energy(), spectrum(&fi).energy());
+        prop_assert!(
+            (spectral - n * spatial).abs() <= 1e-9 * (1.0 + n * spatial),
+            "{}x{}: spectral energy {} vs {} * {}", fi.width, fi.height, spectral, n, spatial
+        );
+    }
+
+    #[test]
+    fn shifts_round_trip(fi in complex_image()) {
+        let mut shifted = fi.clone();
+        shifted.fftshift();
+        shifted.ifftshift();
+        prop_assert_eq!(&shifted, &fi, "fftshift then ifftshift, {}x{}", fi.width, fi.height);
+        shifted.ifftshift();
+        shifted.fftshift();
+        prop_assert_eq!(&shifted, &fi, "ifftshift then fftshift, {}x{}", fi.width, fi.height);
+    }
+
+    #[test]
+    fn real_input_has_hermitian_spectrum(fi in real_image()) {
+        prop_assert!(spectrum(&fi).is_hermitian(tolerance(&fi)), "{}x{}", fi.width, fi.height);
+    }
+}